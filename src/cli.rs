@@ -1,9 +1,10 @@
 use std::{path::PathBuf, str::FromStr};
 
+use cargo_metadata::semver;
 use clap::{Parser, Subcommand, ValueEnum};
 use tracing::level_filters::LevelFilter;
 
-use crate::format::{CriteriaName, ImportName, PackageName, VersionReq, VetVersion};
+use crate::format::{CriteriaName, DiffScope, ImportName, PackageName, VersionReq, VetVersion};
 
 #[derive(Parser)]
 #[clap(version, about, long_about = None)]
@@ -56,7 +57,31 @@ pub struct Cli {
     #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
     pub features: Vec<String>,
 
-    /// Do not fetch new imported audits.
+    /// Only vet dependencies which are built for the given target triple
+    ///
+    /// May be specified multiple times to vet for multiple targets. Dependencies
+    /// which are only enabled by `cfg()` conditions that match none of the
+    /// given targets will be ignored. Overrides the `targets` key in
+    /// `config.toml`. By default all dependencies are vetted, regardless of
+    /// platform.
+    #[clap(long, action, value_name = "TRIPLE")]
+    #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
+    pub target: Vec<String>,
+
+    /// Read the dependency graph from a file instead of running `cargo metadata`
+    ///
+    /// The file must contain the output of `cargo metadata --format-version 1`
+    /// for the workspace containing the current directory, run with
+    /// `--all-features` and without `--filter-platform`. Pass `-` to read it
+    /// from stdin. This allows cargo-vet to be used from build systems which
+    /// can't invoke cargo themselves.
+    #[clap(long, value_name = "PATH", parse(from_os_str))]
+    #[clap(conflicts_with_all = &["PATH", "no-all-features", "no-default-features", "features", "cargo-arg"])]
+    #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
+    pub metadata_file: Option<PathBuf>,
+
+    /// Do not fetch new imported audits from the network. Imports from local
+    /// files or git repositories are still read.
     #[clap(long, action)]
     #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
     pub locked: bool,
@@ -80,6 +105,15 @@ pub struct Cli {
     #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
     pub no_registry_suggestions: bool,
 
+    /// Use the registry of peers at this URL or path, rather than the
+    /// cargo-vet registry
+    ///
+    /// This can be a local mirror of the registry, whose peers' audits are
+    /// local files (relative to the registry), to use the registry offline.
+    #[clap(long, action, value_name = "URL")]
+    #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
+    pub registry_url: Option<String>,
+
     /// How verbose logging should be (log level)
     #[clap(long, action)]
     #[clap(default_value_t = LevelFilter::WARN)]
@@ -235,6 +269,49 @@ pub enum Commands {
     #[clap(disable_version_flag = true)]
     Suggest(SuggestArgs),
 
+    /// Explain how a package was vetted
+    ///
+    /// For each of the criteria the package is required to satisfy, this prints
+    /// the chain of audits which satisfied it, along with where each entry came
+    /// from (a local audit, an import, a wildcard audit, a trusted publisher or
+    /// an exemption). The first-party crates which required the criteria are
+    /// also listed.
+    ///
+    /// Use `--output-format=json` to get a machine-readable version of the
+    /// explanation.
+    #[clap(disable_version_flag = true)]
+    Explain(ExplainArgs),
+
+    /// Explain why a package is required to satisfy its criteria
+    ///
+    /// This prints every path from a root crate to each version of the package
+    /// in the dependency graph. Each hop along a path is annotated with the
+    /// criteria required at that point, and the `policy` entry (if any) which
+    /// changed them.
+    ///
+    /// Use `--output-format=json` to get a machine-readable version of the
+    /// paths.
+    #[clap(disable_version_flag = true)]
+    Why(WhyArgs),
+
+    /// Estimate the audit cost of a dependency change
+    ///
+    /// This applies a hypothetical change to your dependency graph and vets
+    /// the result, without touching your manifests or lockfile. Each
+    /// third-party package which the change would add is listed along with
+    /// the existing audits, imports, trust entries and exemptions which would
+    /// vet it, and the audit we'd suggest for it if it wouldn't be vetted.
+    ///
+    /// Cargo's resolver isn't run over the modified graph. Instead, the
+    /// dependencies of the new version are read from the crates.io index,
+    /// reusing versions which are already in the graph where possible and
+    /// otherwise picking the newest version which isn't yanked. If the index
+    /// can't be read, the output warns that transitive dependencies aren't
+    /// included.
+    #[clap(disable_version_flag = true)]
+    #[clap(subcommand)]
+    WhatIf(WhatIfSubcommands),
+
     /// Initialize cargo-vet for your project
     ///
     /// This will add `exemptions` and `audit-as-crates-io = false` for all packages that
@@ -263,6 +340,19 @@ pub enum Commands {
     #[clap(disable_version_flag = true)]
     Diff(DiffArgs),
 
+    /// Compare a published package with the commit it was published from
+    ///
+    /// Packages published from a git checkout record the commit they were
+    /// published from in `.cargo_vcs_info.json`. This extracts that commit from
+    /// a local clone of the package's repository, and lists the published
+    /// files which aren't in it or differ from it, ignoring the changes cargo
+    /// makes when packaging, such as normalizing `Cargo.toml`.
+    ///
+    /// Code which only exists in the published package is worth particular
+    /// attention when auditing.
+    #[clap(disable_version_flag = true)]
+    DiffSource(DiffSourceArgs),
+
     // Update State Commands
     /// Mark a package as audited
     ///
@@ -293,6 +383,24 @@ pub enum Commands {
     #[clap(disable_version_flag = true)]
     Trust(TrustArgs),
 
+    /// Explore the peers in the cargo-vet registry
+    ///
+    /// The audits of every peer in the registry are downloaded (and cached for
+    /// a day), to find which peers have audited the packages you depend on.
+    /// Use `--registry-url` to explore a local mirror of the registry offline.
+    #[clap(disable_version_flag = true)]
+    #[clap(subcommand)]
+    Registry(RegistrySubcommands),
+
+    /// Search the local index of audits for a package
+    ///
+    /// The index is kept in the cache, and is updated with the audits of every
+    /// imported peer and every peer in the registry whenever they're fetched
+    /// (by commands like `check`, `suggest` and `registry coverage`). Searching
+    /// it never uses the network.
+    #[clap(disable_version_flag = true)]
+    Search(SearchArgs),
+
     /// Explicitly regenerate various pieces of information
     ///
     /// There are several things that `cargo vet` *can* do for you automatically
@@ -339,8 +447,9 @@ pub enum Commands {
     /// tests and have an audit that only claims safe-to-run, we won't mention it.
     ///
     /// When a violation *does* cause an integrity error, it's up to you and your
-    /// peers to figure out what to do about it. There isn't yet a mechanism for
-    /// dealing with disagreements with a peer's published violations.
+    /// peers to figure out what to do about it. In the meantime, the
+    /// `violation-conflicts` key in config.toml can be used to keep vetting by
+    /// preferring one side of each conflict.
     #[clap(disable_version_flag = true)]
     RecordViolation(RecordViolationArgs),
 
@@ -404,6 +513,20 @@ pub enum Commands {
     #[clap(disable_version_flag = true)]
     Gc(GcArgs),
 
+    /// Load crate information from a crates.io database dump
+    ///
+    /// crates.io publishes a daily dump of its database at
+    /// <https://static.crates.io/db-dump.tar.gz>, which records who published
+    /// every version of every crate. Loading it into the cache means commands
+    /// such as `trust`, and checks of wildcard audits, can run without making a
+    /// crates.io API request for each crate, including with `--frozen`.
+    ///
+    /// Either the downloaded `.tar.gz` file or the directory it was extracted
+    /// to can be given. By default only the crates in the dependency graph are
+    /// loaded.
+    #[clap(disable_version_flag = true)]
+    IngestDbDump(IngestDbDumpArgs),
+
     /// Renew wildcard audit expirations
     ///
     /// This will set a wildcard audit expiration to be one year in the future from when it is run.
@@ -450,7 +573,15 @@ pub enum RegenerateSubcommands {
 }
 
 #[derive(clap::Args)]
-pub struct CheckArgs {}
+pub struct CheckArgs {
+    /// Only vet the given feature profile from config.toml
+    ///
+    /// May be specified multiple times. By default every profile in
+    /// config.toml is vetted, unless feature flags are passed on the command
+    /// line.
+    #[clap(long, action, value_name = "NAME")]
+    pub profile: Vec<String>,
+}
 
 #[derive(clap::Args)]
 pub struct InitArgs {}
@@ -484,6 +615,26 @@ pub struct DiffArgs {
     /// How to inspect the source
     #[clap(long, action, default_value = "sourcegraph")]
     pub mode: FetchMode,
+    /// Which files to diff with `--mode=local`: `all`, or only those `shipped`
+    /// in the package, skipping tests, benches, examples and documentation
+    ///
+    /// Defaults to the scope configured in the `[diff]` table of config.toml.
+    #[clap(long, action, value_name("SCOPE"))]
+    pub scope: Option<DiffScope>,
+}
+
+/// Compares a published package with its source repository
+#[derive(clap::Args)]
+pub struct DiffSourceArgs {
+    /// The package to compare
+    #[clap(action)]
+    pub package: PackageName,
+    /// The published version to compare
+    #[clap(action)]
+    pub version: VetVersion,
+    /// A local clone of the package's git repository
+    #[clap(long, action)]
+    pub repo: PathBuf,
 }
 
 /// Certifies a package as audited
@@ -552,20 +703,43 @@ pub struct CertifyArgs {
     /// `1.0.0->1.0.0@git:2222222`. Passing this flag would prevent this.
     #[clap(long, action, requires("version2"))]
     pub no_collapse: bool,
+    /// Check that the published package matches its source, and note it
+    ///
+    /// Compares the audited version with the commit it was published from in
+    /// this local clone of its repository, as `cargo vet diff-source` does,
+    /// and records the commit in the audit's notes if every published file
+    /// matches. The audit isn't recorded if they don't match.
+    #[clap(long, action, value_name("REPO"), conflicts_with("wildcard"))]
+    pub matches_source: Option<PathBuf>,
 }
 
 /// Import a new peer
 #[derive(clap::Args)]
 pub struct ImportArgs {
     /// The name of the peer to import
-    #[clap(action)]
-    pub name: ImportName,
+    ///
+    /// Must be specified unless --review has been specified.
+    #[clap(action, required_unless_present("review"))]
+    pub name: Option<ImportName>,
     /// The URL(s) of the peer's audits.toml file(s).
     ///
     /// If a URL is not provided, a peer with the given name will be looked up
     /// in the cargo-vet registry to determine the import URL(s).
     #[clap(action)]
     pub url: Vec<String>,
+    /// Accept the current contents of an existing peer, showing what changed
+    ///
+    /// The peer is pinned to these contents by recording their `sha256` in
+    /// config.toml.
+    #[clap(long, action, conflicts_with("url"))]
+    pub update: bool,
+    /// Review each new entry which would be imported, accepting or rejecting it
+    ///
+    /// Reviews the entries from the named peer, or from every peer if no name
+    /// is given. Rejected entries are remembered in imports.lock, and won't be
+    /// offered again.
+    #[clap(long, action, conflicts_with_all(&["url", "update"]))]
+    pub review: bool,
 }
 
 /// Trust a crate's publisher
@@ -678,7 +852,120 @@ pub struct AddExemptionArgs {
 }
 
 #[derive(clap::Args)]
-pub struct SuggestArgs {}
+pub struct SuggestArgs {
+    /// Also suggest already-audited versions of failing packages
+    ///
+    /// For each failing package, the versions published on crates.io are
+    /// searched for the nearest one which is already audited for everything
+    /// the package requires, and which satisfies every semver requirement on
+    /// the package in your dependency graph. The `cargo update` command which
+    /// would switch to that version is printed alongside the suggested audits.
+    ///
+    /// Yanked versions are skipped, as are versions which depend on packages
+    /// that aren't already in your dependency graph. This requires reading
+    /// the crates.io index, so nothing is suggested while offline.
+    #[clap(long, action)]
+    pub prefer_audited_versions: bool,
+}
+
+#[derive(clap::Args)]
+pub struct WhyArgs {
+    /// The package to explain
+    #[clap(action)]
+    pub package: PackageName,
+    /// The maximum number of paths to print for each version of the package
+    #[clap(long, action, default_value_t = 100)]
+    pub max_paths: usize,
+}
+
+#[derive(Subcommand)]
+pub enum WhatIfSubcommands {
+    /// Estimate the audit cost of adding a new dependency
+    #[clap(disable_version_flag = true)]
+    Add(WhatIfAddArgs),
+
+    /// Estimate the audit cost of upgrading an existing dependency
+    #[clap(disable_version_flag = true)]
+    Upgrade(WhatIfUpgradeArgs),
+}
+
+#[derive(Subcommand)]
+pub enum RegistrySubcommands {
+    /// List the peers in the registry, and whether they're imported
+    #[clap(disable_version_flag = true)]
+    List(RegistryListArgs),
+
+    /// Show every peer's audits of a package, with their criteria
+    #[clap(disable_version_flag = true)]
+    Search(RegistrySearchArgs),
+
+    /// Show which of your third-party dependencies each peer has audited
+    #[clap(disable_version_flag = true)]
+    Coverage(RegistryCoverageArgs),
+}
+
+#[derive(clap::Args)]
+pub struct RegistryListArgs {}
+
+#[derive(clap::Args)]
+pub struct RegistrySearchArgs {
+    /// The package to search for
+    #[clap(action)]
+    pub package: PackageName,
+}
+
+#[derive(clap::Args)]
+pub struct RegistryCoverageArgs {}
+
+/// Search the local index of audits
+#[derive(clap::Args)]
+pub struct SearchArgs {
+    /// The package to search for
+    #[clap(action)]
+    pub package: PackageName,
+    /// Only show audits of versions matching this requirement, like `0.17`
+    ///
+    /// Violations are shown regardless of their versions.
+    #[clap(action)]
+    pub version: Option<VersionReq>,
+}
+
+#[derive(clap::Args)]
+pub struct WhatIfAddArgs {
+    /// The package and crates.io version to add, in the form `name@version`
+    #[clap(action)]
+    pub package: PackageVersionArg,
+    /// The first-party crate to add the dependency to
+    ///
+    /// If not provided, the dependency is added to every root crate.
+    #[clap(long, action)]
+    pub to: Option<PackageName>,
+}
+
+#[derive(clap::Args)]
+pub struct WhatIfUpgradeArgs {
+    /// The package to upgrade
+    #[clap(action)]
+    pub package: PackageName,
+    /// The crates.io version to upgrade to
+    #[clap(action)]
+    pub version: semver::Version,
+    /// The version of the package to upgrade
+    ///
+    /// If not provided, every version of the package in the graph is upgraded.
+    #[clap(long, action)]
+    pub from: Option<VetVersion>,
+}
+
+#[derive(clap::Args)]
+pub struct ExplainArgs {
+    /// The package to explain
+    #[clap(action)]
+    pub package: PackageName,
+    /// The version to explain
+    #[clap(action)]
+    pub version: VetVersion,
+}
 
 #[derive(clap::Args)]
 pub struct FmtArgs {}
@@ -732,6 +1019,18 @@ pub struct GcArgs {
     pub clean: bool,
 }
 
+#[derive(clap::Args)]
+pub struct IngestDbDumpArgs {
+    /// The path to `db-dump.tar.gz`, or the directory it was extracted to.
+    #[clap(action)]
+    pub path: PathBuf,
+
+    /// Load every crate in the dump, rather than only the crates in the
+    /// dependency graph.
+    #[clap(long, action)]
+    pub all: bool,
+}
+
 #[derive(clap::Args)]
 pub struct RenewArgs {
     // Change this doc string if the WILDCARD_AUDIT_EXPIRATION_STRING changes.
@@ -811,6 +1110,30 @@ impl FromStr for DependencyCriteriaArg {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct PackageVersionArg {
+    pub package: PackageName,
+    pub version: semver::Version,
+}
+
+impl FromStr for PackageVersionArg {
+    // the error must be owned as well
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (package, version) = s
+            .split_once('@')
+            .ok_or_else(|| format!("expected `name@version`, found `{s}`"))?;
+        if package.is_empty() {
+            return Err(format!("missing package name in `{s}`"));
+        }
+        Ok(PackageVersionArg {
+            package: package.to_owned(),
+            version: version.parse().map_err(|e| format!("{e}"))?,
+        })
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 pub enum FetchMode {
     Local,
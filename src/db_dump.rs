@@ -0,0 +1,297 @@
+//! Reading the crates.io database dump, so that publisher information can be
+//! looked up without making a crates.io API request for each crate.
+//!
+//! The dump is published daily at <https://static.crates.io/db-dump.tar.gz>,
+//! and contains a CSV file for each table in the crates.io database. Only
+//! `crates.csv`, `versions.csv` and `users.csv` are read, along with the
+//! `metadata.json` file which records when the dump was taken.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use cargo_metadata::semver;
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::errors::DbDumpError;
+use crate::format::{
+    CratesAPICrateMetadata, CratesCache, CratesCacheEntry, CratesCacheUser,
+    CratesCacheVersionDetails, CratesUserId, FastMap, FastSet, PackageName,
+};
+
+const CRATES_CSV: &str = "crates.csv";
+const VERSIONS_CSV: &str = "versions.csv";
+const USERS_CSV: &str = "users.csv";
+const METADATA_JSON: &str = "metadata.json";
+
+/// The publisher information read from a crates.io database dump.
+pub struct DbDump {
+    /// When the dump was taken, if it recorded it.
+    pub timestamp: Option<chrono::DateTime<chrono::Utc>>,
+    /// The crates in the dump, and the users who published them. The
+    /// `last_fetched` time of each crate is the time the dump was taken, rather
+    /// than when it was read, so that the information goes stale with the dump
+    /// and doesn't replace anything fetched from crates.io since.
+    pub crates: CratesCache,
+}
+
+#[derive(Deserialize)]
+struct CrateRow {
+    id: u64,
+    name: PackageName,
+    description: Option<String>,
+    repository: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct VersionRow {
+    crate_id: u64,
+    num: String,
+    created_at: String,
+    published_by: Option<CratesUserId>,
+}
+
+#[derive(Deserialize)]
+struct UserRow {
+    id: CratesUserId,
+    gh_login: String,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DumpMetadata {
+    timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// The rows read from the dump. Each table is filtered as it's read by the
+/// tables read before it, so rows which aren't needed are never kept. Tables
+/// may be read in any order though, so anything not filtered yet is filtered
+/// once every table has been read.
+struct Tables<'a> {
+    packages: Option<&'a FastSet<PackageName>>,
+    /// The crates in `packages`, by id.
+    crates: Option<FastMap<u64, CrateRow>>,
+    /// The versions of the crates in `crates`, if it was read first.
+    versions: Option<Vec<VersionRow>>,
+    /// The users who published one of `versions`, if both were read first.
+    users: Option<Vec<UserRow>>,
+    metadata: Option<DumpMetadata>,
+}
+
+impl<'a> Tables<'a> {
+    fn new(packages: Option<&'a FastSet<PackageName>>) -> Self {
+        Self {
+            packages,
+            crates: None,
+            versions: None,
+            users: None,
+            metadata: None,
+        }
+    }
+
+    /// Read `file` from the dump, if it's one of the files we need.
+    fn read_file(&mut self, file: &str, reader: impl Read) -> Result<(), DbDumpError> {
+        match file {
+            CRATES_CSV => {
+                let packages = self.packages;
+                let rows = read_csv(file, reader, |row: &CrateRow| {
+                    packages.map_or(true, |packages| packages.contains(&row.name))
+                })?;
+                self.crates = Some(rows.into_iter().map(|row| (row.id, row)).collect());
+            }
+            VERSIONS_CSV => {
+                let crates = &self.crates;
+                self.versions = Some(read_csv(file, reader, |row: &VersionRow| {
+                    crates
+                        .as_ref()
+                        .map_or(true, |crates| crates.contains_key(&row.crate_id))
+                })?);
+            }
+            USERS_CSV => {
+                let publishers = self.publishers();
+                self.users = Some(read_csv(file, reader, |row: &UserRow| {
+                    publishers
+                        .as_ref()
+                        .map_or(true, |publishers| publishers.contains(&row.id))
+                })?);
+            }
+            METADATA_JSON => {
+                self.metadata = Some(
+                    serde_json::from_reader(reader)
+                        .map_err(|error| DbDumpError::Metadata { error })?,
+                )
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// The users who published a version of one of the crates, if both tables
+    /// have been read.
+    fn publishers(&self) -> Option<FastSet<CratesUserId>> {
+        let (Some(crates), Some(versions)) = (&self.crates, &self.versions) else {
+            return None;
+        };
+        Some(
+            versions
+                .iter()
+                .filter(|row| crates.contains_key(&row.crate_id))
+                .filter_map(|row| row.published_by)
+                .collect(),
+        )
+    }
+}
+
+/// Deserialize the rows of a CSV file from the dump one at a time, keeping
+/// only those for which `keep` returns true.
+fn read_csv<T: for<'de> Deserialize<'de>>(
+    file: &str,
+    reader: impl Read,
+    mut keep: impl FnMut(&T) -> bool,
+) -> Result<Vec<T>, DbDumpError> {
+    let mut rows = Vec::new();
+    for row in csv::Reader::from_reader(reader).into_deserialize() {
+        let row = row.map_err(|error| DbDumpError::Csv {
+            file: file.to_owned(),
+            error,
+        })?;
+        if keep(&row) {
+            rows.push(row);
+        }
+    }
+    Ok(rows)
+}
+
+/// Read the crates.io database dump at `path`, which is either the
+/// `db-dump.tar.gz` file or the directory it was extracted to. If `packages`
+/// is given, only those crates are read from it.
+pub fn read(
+    path: &Path,
+    packages: Option<&FastSet<PackageName>>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<DbDump, DbDumpError> {
+    let open_error = |error| DbDumpError::Open {
+        path: path.to_owned(),
+        error,
+    };
+
+    let mut tables = Tables::new(packages);
+    if path.is_dir() {
+        for (file, file_path) in [
+            (METADATA_JSON, PathBuf::from(METADATA_JSON)),
+            (CRATES_CSV, Path::new("data").join(CRATES_CSV)),
+            (VERSIONS_CSV, Path::new("data").join(VERSIONS_CSV)),
+            (USERS_CSV, Path::new("data").join(USERS_CSV)),
+        ] {
+            match File::open(path.join(file_path)) {
+                Ok(reader) => tables.read_file(file, reader)?,
+                // The metadata is optional, and missing tables are reported
+                // below.
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+                Err(error) => return Err(open_error(error)),
+            }
+        }
+    } else {
+        // Files in the tarball are under a directory named for the time the
+        // dump was taken, like `2023-01-01-020000/data/crates.csv`.
+        let reader = File::open(path).map_err(open_error)?;
+        let mut archive = tar::Archive::new(GzDecoder::new(reader));
+        for entry in archive.entries().map_err(open_error)? {
+            let entry = entry.map_err(open_error)?;
+            let entry_path = entry.path().map_err(open_error)?.into_owned();
+            let file = match entry_path.file_name().and_then(|name| name.to_str()) {
+                Some(METADATA_JSON) => METADATA_JSON,
+                Some(name @ (CRATES_CSV | VERSIONS_CSV | USERS_CSV))
+                    if entry_path.parent().and_then(|dir| dir.file_name())
+                        == Some("data".as_ref()) =>
+                {
+                    name
+                }
+                _ => continue,
+            };
+            info!("reading {} from the crates.io database dump", file);
+            tables.read_file(file, entry)?;
+        }
+    }
+
+    let missing = |file: &'static str| DbDumpError::MissingFile {
+        path: path.to_owned(),
+        file,
+    };
+    let publishers = tables.publishers();
+    let crates = tables.crates.ok_or_else(|| missing(CRATES_CSV))?;
+    let versions = tables.versions.ok_or_else(|| missing(VERSIONS_CSV))?;
+    let users = tables.users.ok_or_else(|| missing(USERS_CSV))?;
+    let publishers = publishers.unwrap_or_default();
+    let timestamp = tables.metadata.map(|metadata| metadata.timestamp);
+
+    let mut cache = CratesCache::default();
+    let mut crate_names = FastMap::new();
+    for (id, row) in crates {
+        crate_names.insert(id, row.name.clone());
+        cache.crates.insert(
+            row.name,
+            CratesCacheEntry {
+                last_fetched: timestamp.unwrap_or(now),
+                versions: Default::default(),
+                metadata: Some(CratesAPICrateMetadata {
+                    description: row.description,
+                    repository: row.repository,
+                }),
+            },
+        );
+    }
+
+    for row in versions {
+        let Some(name) = crate_names.get(&row.crate_id) else {
+            continue;
+        };
+        let Ok(version) = semver::Version::parse(&row.num) else {
+            warn!(
+                "ignoring invalid version {} of {} in the crates.io database dump",
+                row.num, name
+            );
+            continue;
+        };
+        let details =
+            parse_timestamp(&row.created_at).map(|created_at| CratesCacheVersionDetails {
+                created_at,
+                published_by: row.published_by,
+            });
+        cache
+            .crates
+            .get_mut(name)
+            .unwrap()
+            .versions
+            .insert(version, details);
+    }
+
+    for row in users {
+        if publishers.contains(&row.id) {
+            cache.users.insert(
+                row.id,
+                CratesCacheUser {
+                    login: row.gh_login,
+                    name: row.name,
+                },
+            );
+        }
+    }
+
+    Ok(DbDump {
+        timestamp,
+        crates: cache,
+    })
+}
+
+/// Parse a timestamp from the dump, which are in PostgreSQL's format (like
+/// `2023-01-01 02:00:00.123456`) and in UTC.
+fn parse_timestamp(timestamp: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let timestamp = timestamp.trim_end_matches("+00");
+    chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S%.f")
+        .map(|naive| chrono::DateTime::from_utc(naive, chrono::Utc))
+        .or_else(|_| chrono::DateTime::parse_from_rfc3339(timestamp).map(|date| date.into()))
+        .ok()
+}
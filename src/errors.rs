@@ -118,6 +118,46 @@ pub enum MetadataAcquireError {
     },
     #[error(transparent)]
     Other(cargo_metadata::Error),
+    #[error("Couldn't read metadata file {}", path.display())]
+    ReadFile {
+        path: PathBuf,
+        #[source]
+        error: std::io::Error,
+    },
+    #[error("The metadata file isn't valid `cargo metadata` output")]
+    InvalidJson(#[source] serde_json::Error),
+    #[error("The metadata file has an unsupported format version ({0})")]
+    #[diagnostic(help("Produce the metadata file with `cargo metadata --format-version 1`"))]
+    UnsupportedVersion(serde_json::Value),
+    #[error("The metadata file has no dependency resolution")]
+    #[diagnostic(help("Don't pass `--no-deps` to `cargo metadata`"))]
+    MissingResolve,
+    #[error("The metadata file has no workspace members")]
+    NoWorkspaceMembers,
+    #[error("The metadata file refers to package '{0}', which isn't in its package list")]
+    UnknownPackage(String),
+    #[error("The metadata file has no dependency resolution for package '{0}'")]
+    UnresolvedPackage(String),
+    #[error("The metadata file doesn't have every feature of '{0}' enabled")]
+    #[diagnostic(help(
+        "Produce the metadata file with `cargo metadata --all-features`, so that every optional dependency is vetted"
+    ))]
+    NotAllFeatures(String),
+    #[error("The metadata file is missing the dependency of '{package}' on '{dependency}'")]
+    #[diagnostic(help(
+        "Don't pass `--filter-platform` to `cargo metadata`, use the `targets` in config.toml instead"
+    ))]
+    MissingDependency { package: String, dependency: String },
+    #[error("The metadata file is for the workspace at {}, which doesn't contain the current directory {}", workspace_root.display(), current_dir.display())]
+    #[diagnostic(help(
+        "Run cargo-vet from the workspace the metadata file was produced for, as that's where the store is found"
+    ))]
+    WorkspaceMismatch {
+        workspace_root: PathBuf,
+        current_dir: PathBuf,
+    },
+    #[error("Couldn't determine the current directory")]
+    CurrentDir(#[source] std::io::Error),
 }
 
 impl From<cargo_metadata::Error> for MetadataAcquireError {
@@ -299,6 +339,18 @@ pub enum CertifyError {
     #[error("couldn't build an audit graph to determine audit collapse validity")]
     #[diagnostic(help("use --no-collapse to disable audit collapsing"))]
     BadAuditGraph,
+    #[error("{package}:{version} doesn't match commit {commit} of its source")]
+    #[diagnostic(help(
+        "run `cargo vet diff-source {package} {version} --repo <REPO>` to see which files differ"
+    ))]
+    SourceMismatch {
+        package: PackageName,
+        version: VetVersion,
+        commit: String,
+    },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    DiffSource(#[from] DiffSourceError),
     #[error(transparent)]
     IoError(#[from] std::io::Error),
     #[error(transparent)]
@@ -311,6 +363,9 @@ pub enum CertifyError {
     GetPublishersError(#[from] CrateInfoError),
     #[error(transparent)]
     CacheAcquire(#[from] CacheAcquireError),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Signing(#[from] SigningError),
 }
 
 ///////////////////////////////////////////////////////////
@@ -328,6 +383,62 @@ pub enum EditError {
     CouldntRead(#[source] std::io::Error),
 }
 
+///////////////////////////////////////////////////////////
+// SigningError
+///////////////////////////////////////////////////////////
+
+#[derive(Debug, Error, Diagnostic)]
+#[non_exhaustive]
+pub enum SigningError {
+    #[error("Failed to run `{tool}`")]
+    #[diagnostic(help("signing and verifying audits requires `{tool}` to be installed"))]
+    Spawn {
+        tool: &'static str,
+        #[source]
+        error: std::io::Error,
+    },
+    #[error("`{tool}` failed to sign the audit")]
+    #[diagnostic(help("check the `signing` key configured in config.toml"))]
+    SignFailed { tool: &'static str },
+    #[error("Failed to write temporary files for signing")]
+    Io(
+        #[from]
+        #[source]
+        std::io::Error,
+    ),
+}
+
+///////////////////////////////////////////////////////////
+// DbDumpError
+///////////////////////////////////////////////////////////
+
+#[derive(Debug, Error, Diagnostic)]
+#[non_exhaustive]
+pub enum DbDumpError {
+    #[error("Failed to read the crates.io database dump at {}", path.display())]
+    Open {
+        path: PathBuf,
+        #[source]
+        error: std::io::Error,
+    },
+    #[error("Failed to parse {file} from the crates.io database dump")]
+    Csv {
+        file: String,
+        #[source]
+        error: csv::Error,
+    },
+    #[error("Failed to parse metadata.json from the crates.io database dump")]
+    Metadata {
+        #[source]
+        error: serde_json::Error,
+    },
+    #[error("The crates.io database dump at {} doesn't contain {file}", path.display())]
+    #[diagnostic(help(
+        "expected the db-dump.tar.gz file from crates.io, or the directory it was extracted to"
+    ))]
+    MissingFile { path: PathBuf, file: &'static str },
+}
+
 ///////////////////////////////////////////////////////////
 // InitErrors
 ///////////////////////////////////////////////////////////
@@ -354,6 +465,11 @@ pub enum StoreCreateError {
         #[source]
         FlockError,
     ),
+    TargetPlatform(
+        #[from]
+        #[source]
+        TargetPlatformError,
+    ),
 }
 
 #[derive(Debug, Error, Diagnostic)]
@@ -395,6 +511,57 @@ pub enum StoreAcquireError {
     #[diagnostic(transparent)]
     #[error(transparent)]
     CacheAcquire(#[from] Box<CacheAcquireError>),
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    TargetPlatform(#[from] TargetPlatformError),
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    Extends(#[from] ExtendsError),
+    #[error("The contents of the import '{import_name}' don't match its pinned sha256")]
+    #[diagnostic(help(
+        "expected {expected}, found {actual}\n\
+        Run `cargo vet import --update {import_name}` to review and accept the new contents"
+    ))]
+    PinnedImportChanged {
+        import_name: ImportName,
+        expected: String,
+        actual: String,
+    },
+    #[error("The import '{import_name}' can't be pinned to a sha256 while also being transitive")]
+    #[diagnostic(help(
+        "The sha256 doesn't cover the audits imported through the peer. \
+        Remove either `sha256` or `transitive` from the import"
+    ))]
+    PinnedTransitiveImport { import_name: ImportName },
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[non_exhaustive]
+pub enum ExtendsError {
+    #[error("Couldn't read base config '{base}'")]
+    ReadFile {
+        base: String,
+        #[source]
+        error: std::io::Error,
+    },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    LoadToml(#[from] LoadTomlError),
+    #[error("Invalid base config url '{url}'")]
+    InvalidUrl {
+        url: String,
+        #[source]
+        error: url::ParseError,
+    },
+    #[error("Couldn't fetch base config '{url}'")]
+    Download {
+        url: String,
+        #[source]
+        error: DownloadError,
+    },
+    #[error("Base config '{url}' isn't pinned in imports.lock")]
+    #[diagnostic(help("run `cargo vet` without --locked to fetch and pin it"))]
+    NotPinned { url: String },
 }
 
 #[derive(Debug, Error, Diagnostic)]
@@ -455,6 +622,21 @@ pub enum StoreValidateError {
     #[error("imports.lock is out-of-date with respect to configuration")]
     #[diagnostic(help("run `cargo vet` without --locked to update imports"))]
     ImportsLockOutdated,
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    InvalidWorkspace(InvalidWorkspaceError),
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("'{invalid}' is not a workspace governed by this store")]
+#[diagnostic(help("the workspaces are {:?}", valid_names))]
+pub struct InvalidWorkspaceError {
+    #[source_code]
+    pub source_code: SourceFile,
+    #[label]
+    pub span: SourceSpan,
+    pub invalid: String,
+    pub valid_names: Vec<String>,
 }
 
 #[derive(Debug, Error, Diagnostic)]
@@ -569,6 +751,83 @@ pub enum CommandError {
     BadOutput(#[source] FromUtf8Error),
 }
 
+//////////////////////////////////////////////////////////
+// TargetPlatformError
+//////////////////////////////////////////////////////////
+
+#[derive(Debug, Error, Diagnostic)]
+#[non_exhaustive]
+pub enum TargetPlatformError {
+    #[error("Couldn't get the cfg values for target '{target}' from rustc")]
+    #[diagnostic(help("Run `rustc --print target-list` to see the supported target triples"))]
+    Command {
+        target: String,
+        #[source]
+        error: CommandError,
+    },
+    #[error("rustc reported an invalid cfg value for target '{target}'")]
+    InvalidCfg {
+        target: String,
+        #[source]
+        error: cargo_platform::ParseError,
+    },
+}
+
+//////////////////////////////////////////////////////////
+// ProfileError
+//////////////////////////////////////////////////////////
+
+#[derive(Debug, Error, Diagnostic)]
+#[non_exhaustive]
+pub enum ProfileError {
+    #[error("There is no feature profile named '{name}' in config.toml")]
+    #[diagnostic(help("Available profiles: {available}"))]
+    UnknownProfile { name: String, available: String },
+    #[error(
+        "--profile can't be combined with --metadata-file, --features, --no-default-features or --no-all-features"
+    )]
+    #[diagnostic(help("Feature profiles specify their own features in config.toml"))]
+    ConflictingFeatures,
+    #[error("Couldn't get the dependency graph for feature profile '{name}'")]
+    Metadata {
+        name: String,
+        #[source]
+        error: MetadataAcquireError,
+    },
+    #[error("Couldn't get the targets for feature profile '{name}'")]
+    TargetPlatform {
+        name: String,
+        #[source]
+        error: TargetPlatformError,
+    },
+}
+
+//////////////////////////////////////////////////////////
+// WorkspaceError
+//////////////////////////////////////////////////////////
+
+#[derive(Debug, Error, Diagnostic)]
+#[non_exhaustive]
+pub enum WorkspaceError {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    LoadToml(#[from] LoadTomlError),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Extends(#[from] ExtendsError),
+    #[error("Couldn't get the dependency graph for workspace '{name}'")]
+    Metadata {
+        name: String,
+        #[source]
+        error: MetadataAcquireError,
+    },
+    #[error("The workspace at {root} isn't one of the workspaces governed by this store")]
+    #[diagnostic(help("Add its Cargo.toml to the `workspaces` table in config.toml"))]
+    NotGoverned { root: String },
+    #[error("--metadata-file can't be used with a store which governs multiple workspaces")]
+    MetadataFile,
+}
+
 //////////////////////////////////////////////////////////
 // FetchAndDiffError
 //////////////////////////////////////////////////////////
@@ -603,6 +862,56 @@ pub enum DiffError {
     InvalidOutput,
 }
 
+//////////////////////////////////////////////////////////
+// DiffSourceError
+//////////////////////////////////////////////////////////
+
+#[derive(Debug, Error, Diagnostic)]
+#[non_exhaustive]
+pub enum DiffSourceError {
+    #[error("The published package at {} has no .cargo_vcs_info.json", published.display())]
+    #[diagnostic(help(
+        "cargo only records the commit a package was published from when it is published from a git checkout"
+    ))]
+    MissingVcsInfo { published: PathBuf },
+    #[error("Failed to parse .cargo_vcs_info.json in {}", published.display())]
+    InvalidVcsInfo {
+        published: PathBuf,
+        #[source]
+        error: serde_json::Error,
+    },
+    #[error("The .cargo_vcs_info.json in {} has an invalid {field}: {value:?}", published.display())]
+    #[diagnostic(help(
+        "the commit must be a full hex object name, and the path must stay within the repository"
+    ))]
+    UnsafeVcsInfo {
+        published: PathBuf,
+        field: &'static str,
+        value: String,
+    },
+    #[error("Couldn't find commit {commit} in the git checkout at {}", repo.display())]
+    #[diagnostic(help(
+        "fetch the commit into the checkout, or check that it is a clone of the right repository"
+    ))]
+    MissingCommit { repo: PathBuf, commit: String },
+    #[error("Failed to extract commit {commit} from {}", repo.display())]
+    Extract {
+        repo: PathBuf,
+        commit: String,
+        #[source]
+        error: std::io::Error,
+    },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Command(#[from] CommandError),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Diff(#[from] DiffError),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Fetch(#[from] FetchError),
+}
+
 //////////////////////////////////////////////////////////
 // UserInfoError
 //////////////////////////////////////////////////////////
@@ -646,6 +955,35 @@ pub enum FetchError {
         #[source]
         error: std::io::Error,
     },
+    #[error("failed to read cached .crate at {}", target.display())]
+    ReadCached {
+        target: std::path::PathBuf,
+        #[source]
+        error: std::io::Error,
+    },
+    #[error("The .crate for {package}:{version} doesn't match its expected checksum")]
+    #[diagnostic(help(
+        "expected sha256 {expected}, found {actual}\n\
+        The expected checksum comes from Cargo.lock, or the crates.io index"
+    ))]
+    ChecksumMismatch {
+        package: PackageName,
+        version: semver::Version,
+        expected: String,
+        actual: String,
+    },
+    #[error("Failed to look up the checksum of {package}:{version} in the crates.io index")]
+    IndexChecksum {
+        package: PackageName,
+        version: semver::Version,
+        #[source]
+        error: Box<CrateInfoError>,
+    },
+    #[error("The crates.io index has no checksum for {package}:{version}")]
+    MissingChecksum {
+        package: PackageName,
+        version: semver::Version,
+    },
     #[error("Failed to unpack checkout at {}", src.display())]
     UnpackCheckout {
         src: PathBuf,
@@ -687,6 +1025,25 @@ pub enum UnpackError {
         #[source]
         error: std::io::Error,
     },
+    #[error("Invalid archive, {} is an absolute path", entry_path.display())]
+    AbsolutePath { entry_path: PathBuf },
+    #[error("Invalid archive, {} contains a '..' component", entry_path.display())]
+    ParentDirPath { entry_path: PathBuf },
+    #[error("Invalid archive, {} is a {kind}", entry_path.display())]
+    SpecialFile {
+        entry_path: PathBuf,
+        kind: &'static str,
+    },
+    #[error("Archive is too large, unpacking {} would exceed the limit of {limit} bytes", entry_path.display())]
+    #[diagnostic(help(
+        "the limit can be raised with `max-size` in the `[unpack]` table of config.toml"
+    ))]
+    TooLarge { entry_path: PathBuf, limit: u64 },
+    #[error("Archive has too many files, unpacking {} would exceed the limit of {limit} files", entry_path.display())]
+    #[diagnostic(help(
+        "the limit can be raised with `max-files` in the `[unpack]` table of config.toml"
+    ))]
+    TooManyFiles { entry_path: PathBuf, limit: u64 },
     #[error(transparent)]
     IoError(#[from] std::io::Error),
 }
@@ -751,6 +1108,59 @@ pub enum FetchAuditError {
         #[related]
         errors: Vec<FetchAuditAggregateError>,
     },
+    #[error("{import_name} @ {import_url} must be on the network")]
+    #[diagnostic(help("audits from the registry can't be read from local files"))]
+    LocalSourceNotAllowed {
+        import_name: ImportName,
+        import_url: String,
+    },
+    #[error("{import_name} @ {import_url} can't be fetched while offline")]
+    Offline {
+        import_name: ImportName,
+        import_url: String,
+    },
+    #[error("unsupported git URL for foreign import {import_name} @ {import_url}")]
+    #[diagnostic(help(
+        "git imports must be local repositories, like git+file:///path/to/repo#rev"
+    ))]
+    UnsupportedGitUrl {
+        import_name: ImportName,
+        import_url: String,
+    },
+    #[error("couldn't read audits for foreign import {import_name} from {}", path.display())]
+    ReadFile {
+        import_name: ImportName,
+        path: PathBuf,
+        #[source]
+        error: std::io::Error,
+    },
+    #[error("couldn't run git to read foreign import {import_name} @ {import_url}")]
+    GitSpawn {
+        import_name: ImportName,
+        import_url: String,
+        #[source]
+        error: std::io::Error,
+    },
+    #[error("invalid git revision '{rev}' for foreign import {import_name} @ {import_url}")]
+    #[diagnostic(help("the revision after the '#' must be a commit, branch or tag"))]
+    InvalidGitRev {
+        import_name: ImportName,
+        import_url: String,
+        rev: String,
+    },
+    #[error("git couldn't read foreign import {import_name} @ {import_url}")]
+    #[diagnostic(help("{stderr}"))]
+    GitShow {
+        import_name: ImportName,
+        import_url: String,
+        stderr: String,
+    },
+    #[error("couldn't check signatures on audits imported from {import_name}")]
+    Signing {
+        import_name: ImportName,
+        #[source]
+        error: SigningError,
+    },
     #[diagnostic(transparent)]
     #[error(transparent)]
     Download(#[from] DownloadError),
@@ -762,6 +1172,33 @@ pub enum FetchAuditError {
     Json(#[from] LoadJsonError),
 }
 
+#[derive(Debug, Error, Diagnostic)]
+#[diagnostic(
+    severity(Warning),
+    help(
+        "only audits signed by one of the import's trusted-keys are accepted, and \
+        wildcard audits and trusted entries can't be signed"
+    )
+)]
+pub struct UnverifiedImportsError {
+    pub import_name: ImportName,
+    pub rejected: Vec<String>,
+}
+
+impl Display for UnverifiedImportsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Rejected {} entries imported from '{}' which weren't signed by one of its trusted-keys",
+            self.rejected.len(),
+            self.import_name
+        ))?;
+        for rejected in &self.rejected {
+            f.write_fmt(format_args!("\n  {rejected}"))?
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Error, Diagnostic)]
 #[error("criteria description mismatch for {criteria_name}\n{first}\n{second}")]
 #[diagnostic(help("{criteria_name} is mapped to the local criteria {mapped_to:?}"))]
@@ -787,6 +1224,13 @@ pub enum CrateInfoError {
     Json(#[from] LoadJsonError),
     #[error("Cannot fetch crate information, '{name}' does not exist.")]
     DoesNotExist { name: PackageName },
+    #[error("invalid URL {url} for crate information")]
+    #[diagnostic(help("check the `api` and `index` URLs in the `[registry]` config"))]
+    InvalidUrl {
+        url: String,
+        #[source]
+        error: url::ParseError,
+    },
 }
 
 //////////////////////////////////////////////////////////
@@ -802,6 +1246,26 @@ pub enum FetchRegistryError {
     Toml(#[from] LoadTomlError),
     #[error("Error when fetching crate information. Registry suggestions may be incomplete.")]
     CrateInfo(#[from] CrateInfoError),
+    #[error("invalid registry URL {url}")]
+    InvalidUrl {
+        url: String,
+        #[source]
+        error: url::ParseError,
+    },
+    #[error("couldn't read {} from the registry", path.display())]
+    ReadFile {
+        path: PathBuf,
+        #[source]
+        error: std::io::Error,
+    },
+    #[error("{url} hasn't been fetched from the registry, and can't be fetched while frozen")]
+    NotCached { url: String },
+    #[error("couldn't read the audits of {import_name} from the registry")]
+    PeerAudits {
+        import_name: ImportName,
+        #[source]
+        error: Box<FetchAuditError>,
+    },
 }
 
 //////////////////////////////////////////////////////////
@@ -870,6 +1334,110 @@ pub enum SuggestError {
     FetchAudit(#[from] FetchAuditError),
 }
 
+//////////////////////////////////////////////////////////
+// ExplainError
+//////////////////////////////////////////////////////////
+
+#[derive(Debug, Error, Diagnostic)]
+#[non_exhaustive]
+pub enum ExplainError {
+    #[error("{package}:{version} is not in your dependency graph")]
+    #[diagnostic(help("Run `cargo vet dump-graph` to see which packages cargo-vet is vetting"))]
+    NotInGraph {
+        package: PackageName,
+        version: VetVersion,
+    },
+    #[error("{package}:{version} is a first-party crate, and doesn't need to be audited")]
+    FirstParty {
+        package: PackageName,
+        version: VetVersion,
+    },
+    #[error("{package}:{version} has audits which conflict with violations")]
+    #[diagnostic(help("Run `cargo vet` for details of the conflict"))]
+    ViolationConflict {
+        package: PackageName,
+        version: VetVersion,
+    },
+}
+
+//////////////////////////////////////////////////////////
+// WhyError
+//////////////////////////////////////////////////////////
+
+#[derive(Debug, Error, Diagnostic)]
+#[non_exhaustive]
+pub enum WhyError {
+    #[error("{package} is not in your dependency graph")]
+    #[diagnostic(help("Run `cargo vet dump-graph` to see which packages cargo-vet is vetting"))]
+    NotInGraph { package: PackageName },
+}
+
+//////////////////////////////////////////////////////////
+// WhatIfError
+//////////////////////////////////////////////////////////
+
+#[derive(Debug, Error, Diagnostic)]
+#[non_exhaustive]
+pub enum WhatIfError {
+    #[error("{package} is not in your dependency graph")]
+    #[diagnostic(help("Run `cargo vet dump-graph` to see which packages cargo-vet is vetting"))]
+    NotInGraph { package: PackageName },
+    #[error("{package}:{version} is not in your dependency graph")]
+    #[diagnostic(help("Run `cargo vet dump-graph` to see which packages cargo-vet is vetting"))]
+    VersionNotInGraph {
+        package: PackageName,
+        version: VetVersion,
+    },
+    #[error("{package} is not a first-party crate in your dependency graph")]
+    NotFirstParty { package: PackageName },
+    #[error("{package} is a first-party crate, and can't be upgraded")]
+    UpgradeFirstParty { package: PackageName },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Explain(#[from] ExplainError),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Suggest(#[from] SuggestError),
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[non_exhaustive]
+pub enum IndexResolveError {
+    #[error("the crates.io index can't be read while offline")]
+    Offline,
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    CacheAcquire(#[from] CacheAcquireError),
+    #[error("couldn't read the crates.io index entry for {package}: {error}")]
+    CrateInfo {
+        package: PackageName,
+        #[source]
+        error: CrateInfoError,
+    },
+    #[error("{package}:{version} isn't in the crates.io index")]
+    MissingVersion {
+        package: PackageName,
+        version: semver::Version,
+    },
+    #[error("no version of {package} in the crates.io index matches {requirement}")]
+    NoMatchingVersion {
+        package: PackageName,
+        requirement: String,
+    },
+    #[error("invalid requirement {requirement} on {package}")]
+    InvalidRequirement {
+        package: PackageName,
+        requirement: String,
+        #[source]
+        error: semver::Error,
+    },
+    #[error("invalid target {target} for {package}")]
+    InvalidTarget {
+        package: PackageName,
+        target: String,
+    },
+}
+
 //////////////////////////////////////////////////////////
 // FlockError
 //////////////////////////////////////////////////////////
@@ -1,7 +1,7 @@
 //! Details of the file formats used by cargo vet
 
 use crate::errors::{StoreVersionParseError, VersionParseError};
-use crate::resolver::{DiffRecommendation, ViolationConflict};
+use crate::resolver::{DiffRecommendation, ResolvedViolationConflict, ViolationConflict};
 use crate::serialization::spanned::Spanned;
 use crate::{flock::Filesystem, serialization};
 use core::{cmp, fmt};
@@ -25,6 +25,8 @@ pub type PackageName = String;
 pub type PackageStr<'a> = &'a str;
 pub type ImportName = String;
 pub type ImportStr<'a> = &'a str;
+pub type ProfileName = String;
+pub type WorkspaceName = String;
 pub type CratesUserId = u64;
 
 // newtype VersionReq so that we can implement PartialOrd on it.
@@ -285,6 +287,8 @@ pub struct AuditEntry {
     pub notes: Option<String>,
     /// Chain of sources this audit was aggregated from, most recent last.
     pub aggregated_from: Vec<Spanned<String>>,
+    /// Signature over the audit by its author's key, if it was signed.
+    pub signature: Option<String>,
     /// A non-serialized member which indicates whether this audit is a "fresh"
     /// audit. This will be set for all audits imported found in the remote
     /// audits file which aren't also found in the local `imports.lock` cache.
@@ -335,6 +339,8 @@ impl AuditEntry {
 
         // Consume the existing audit's `from`, `who`, and `notes`.
         let mut new_entry = self.clone();
+        // The collapsed entry no longer matches any prior signature.
+        new_entry.signature = None;
         new_entry.kind = match other_from {
             Some(version) => AuditKind::Delta {
                 from: version.clone(),
@@ -390,7 +396,8 @@ impl cmp::Ord for AuditEntry {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum AuditKind {
     Full { version: VetVersion },
     Delta { from: VetVersion, to: VetVersion },
@@ -540,6 +547,17 @@ pub struct TrustEntry {
     pub aggregated_from: Vec<Spanned<String>>,
 }
 
+impl TrustEntry {
+    /// Whether this entry trusts the same publisher as `other`, ignoring
+    /// `notes`, which might have been updated uneventfully.
+    pub fn same_trust_as(&self, other: &TrustEntry) -> bool {
+        self.user_id == other.user_id
+            && self.start == other.start
+            && self.end == other.end
+            && self.criteria == other.criteria
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////////
 //                                                                                //
 //                                                                                //
@@ -557,6 +575,13 @@ pub struct ConfigFile {
     #[serde(default = "CargoVetConfig::missing")]
     pub cargo_vet: CargoVetConfig,
 
+    /// Base configs (local paths relative to the store, or URLs) which are
+    /// layered under this file. Entries defined here take precedence over
+    /// those inherited from a base.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub extends: Vec<String>,
+
     /// This top-level key specifies the default criteria that cargo vet certify will use
     /// when recording audits. If unspecified, this defaults to "safe-to-deploy".
     #[serde(rename = "default-criteria")]
@@ -564,6 +589,55 @@ pub struct ConfigFile {
     #[serde(skip_serializing_if = "is_default_criteria")]
     pub default_criteria: CriteriaName,
 
+    /// The target triples which dependencies need to be vetted for. Dependencies
+    /// which are only used on other platforms (as determined by `cfg()`
+    /// conditions in Cargo.toml) will not need to be vetted. If unspecified or
+    /// empty, dependencies for all platforms are vetted.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub targets: Vec<String>,
+
+    /// How to resolve a violation which conflicts with an audit or exemption
+    /// for the same version. If unspecified, this defaults to "fail".
+    #[serde(rename = "violation-conflicts")]
+    #[serde(skip_serializing_if = "ViolationConflictPolicy::is_fail")]
+    #[serde(default)]
+    pub violation_conflicts: ViolationConflictPolicy,
+
+    /// Named feature profiles which should each be vetted separately. When
+    /// any are specified, `check` resolves every profile against its own
+    /// `cargo metadata` invocation instead of vetting a single feature set.
+    #[serde(skip_serializing_if = "SortedMap::is_empty")]
+    #[serde(default)]
+    pub profiles: SortedMap<ProfileName, FeatureProfile>,
+
+    /// The Cargo workspaces governed by this store, if it's shared between
+    /// several of them. The dependency graphs of every workspace are vetted
+    /// together.
+    #[serde(skip_serializing_if = "SortedMap::is_empty")]
+    #[serde(default)]
+    pub workspaces: SortedMap<WorkspaceName, WorkspaceEntry>,
+
+    /// The key used to sign audits recorded by `cargo vet certify`, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub signing: Option<SigningConfig>,
+
+    /// Where to fetch crates and information about them from, if not crates.io.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub registry: Option<RegistryConfig>,
+
+    /// Limits on the size of crates unpacked for review.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub unpack: Option<UnpackConfig>,
+
+    /// Which files to count and show when diffing packages.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub diff: Option<DiffConfig>,
+
     /// Remote audits.toml's that we trust and want to import.
     #[serde(skip_serializing_if = "SortedMap::is_empty")]
     #[serde(default)]
@@ -594,8 +668,269 @@ fn is_default_criteria(val: &CriteriaName) -> bool {
     val == DEFAULT_CRITERIA
 }
 
+/// How to resolve a violation which conflicts with an audit or exemption.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ViolationConflictPolicy {
+    /// Fail to vet, listing every conflict.
+    #[default]
+    Fail,
+    /// Ignore the conflicting audit or exemption.
+    PreferViolation,
+    /// Ignore whichever side of the conflict comes from the import with the
+    /// lower `priority`, where local entries take precedence over any import.
+    /// Conflicts between entries with the same priority fail.
+    PreferHigherPriority,
+    /// Ignore the violation, reporting the conflict as a warning.
+    Warn,
+}
+
+impl ViolationConflictPolicy {
+    pub fn is_fail(&self) -> bool {
+        *self == ViolationConflictPolicy::Fail
+    }
+}
+
+/// A named feature set (and optionally set of targets) to vet the dependency
+/// graph for.
+///
+/// Example:
+///
+/// ```toml
+/// [profiles.server]
+/// features = ["tls"]
+/// default-features = false
+/// targets = ["x86_64-unknown-linux-gnu"]
+/// ```
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub struct FeatureProfile {
+    /// Features to activate for this profile.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// Whether to activate the `default` feature (defaults true).
+    #[serde(rename = "default-features")]
+    #[serde(default = "get_default_profile_default_features")]
+    #[serde(skip_serializing_if = "is_default_profile_default_features")]
+    pub default_features: bool,
+    /// Whether to activate every feature (defaults false).
+    #[serde(rename = "all-features")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub all_features: bool,
+    /// The target triples to vet this profile for. If unspecified or empty,
+    /// the top-level `targets` are used.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub targets: Vec<String>,
+}
+
+static DEFAULT_PROFILE_DEFAULT_FEATURES: bool = true;
+pub fn get_default_profile_default_features() -> bool {
+    DEFAULT_PROFILE_DEFAULT_FEATURES
+}
+fn is_default_profile_default_features(val: &bool) -> bool {
+    val == &DEFAULT_PROFILE_DEFAULT_FEATURES
+}
+
+/// An organization-wide base config, which can be inherited by a store with
+/// `extends`.
+///
+/// Example:
+///
+/// ```toml
+/// default-criteria = "reviewed"
+///
+/// [criteria.reviewed]
+/// description = "..."
+///
+/// [imports.org]
+/// url = "https://example.com/org/audits.toml"
+///
+/// [policy.internal-crate]
+/// audit-as-crates-io = false
+/// ```
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BaseConfigFile {
+    /// The default criteria for stores which don't specify one themselves.
+    #[serde(rename = "default-criteria")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub default_criteria: Option<CriteriaName>,
+
+    /// Custom criteria, inherited as if they were defined in audits.toml.
+    #[serde(skip_serializing_if = "SortedMap::is_empty")]
+    #[serde(default)]
+    pub criteria: SortedMap<CriteriaName, CriteriaEntry>,
+
+    /// Remote audits.toml's to import.
+    #[serde(skip_serializing_if = "SortedMap::is_empty")]
+    #[serde(default)]
+    pub imports: SortedMap<ImportName, RemoteImport>,
+
+    /// A table of policies for crates.
+    #[serde(skip_serializing_if = "Policy::is_empty")]
+    #[serde(default)]
+    pub policy: Policy,
+
+    /// The Cargo workspaces governed by stores extending this config.
+    #[serde(skip_serializing_if = "SortedMap::is_empty")]
+    #[serde(default)]
+    pub workspaces: SortedMap<WorkspaceName, WorkspaceEntry>,
+}
+
+/// A Cargo workspace governed by a shared store.
+///
+/// Example:
+///
+/// ```toml
+/// [workspaces.backend]
+/// manifest = "../backend/Cargo.toml"
+/// ```
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub struct WorkspaceEntry {
+    /// Path to the workspace's root Cargo.toml, relative to the store
+    /// directory.
+    pub manifest: PathBuf,
+}
+
+/// The key used to sign new audits.
+///
+/// Example:
+///
+/// ```toml
+/// [signing]
+/// format = "ssh"
+/// key = "keys/id_ed25519"
+/// ```
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub struct SigningConfig {
+    /// The kind of key, which determines the tool used to sign with it.
+    pub format: SignatureFormat,
+    /// Path to the private key, relative to the store directory.
+    pub key: PathBuf,
+}
+
+/// The endpoints to use instead of crates.io, such as those of a private
+/// mirror.
+///
+/// ```toml
+/// [registry]
+/// api = "https://mirror.example.com/crates-io"
+/// index = "https://mirror.example.com/crates-io/index/"
+/// download = "https://mirror.example.com/crates-io/dl/{crate}/{version}"
+/// peers = "https://mirror.example.com/cargo-vet/registry.toml"
+/// ```
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct RegistryConfig {
+    /// The base URL of the crates.io API, used to look up publishers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api: Option<String>,
+    /// The URL of the crates.io sparse index, used to look up versions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<String>,
+    /// The URL to download `.crate` files from, in the format of the `dl` key
+    /// of a registry's `config.json`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download: Option<String>,
+    /// The URL of the cargo-vet registry of peers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peers: Option<String>,
+}
+
+/// Limits on unpacking `.crate` files, which protect against tarballs that
+/// expand to an unreasonable size before anyone has looked at them.
+///
+/// ```toml
+/// [unpack]
+/// max-size = 536870912
+/// max-files = 100000
+/// ```
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct UnpackConfig {
+    /// The maximum total size of the files in a crate, in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u64>,
+    /// The maximum number of files and directories in a crate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_files: Option<u64>,
+}
+
+/// Which files to count and show when diffing packages, for every crate and
+/// for individual crates.
+///
+/// ```toml
+/// [diff]
+/// scope = "shipped"
+///
+/// [diff.crates]
+/// some-crate = "all"
+/// ```
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct DiffConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<DiffScope>,
+    #[serde(skip_serializing_if = "SortedMap::is_empty")]
+    #[serde(default)]
+    pub crates: SortedMap<PackageName, DiffScope>,
+}
+
+/// Which files of a package are counted and shown when diffing it.
+#[derive(
+    Debug, serde::Serialize, serde::Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffScope {
+    /// Every file in the package.
+    #[default]
+    All,
+    /// Only files which can be built into the package's library, binaries or
+    /// build script, skipping tests, benches, examples and documentation.
+    Shipped,
+}
+
+impl FromStr for DiffScope {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(DiffScope::All),
+            "shipped" => Ok(DiffScope::Shipped),
+            _ => Err(format!(
+                "unknown diff scope '{s}', expected 'all' or 'shipped'"
+            )),
+        }
+    }
+}
+
+impl ConfigFile {
+    /// The scope to diff `package` with.
+    pub fn diff_scope(&self, package: PackageStr<'_>) -> DiffScope {
+        self.diff
+            .as_ref()
+            .map(|diff| {
+                diff.crates
+                    .get(package)
+                    .copied()
+                    .or(diff.scope)
+                    .unwrap_or_default()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignatureFormat {
+    /// An SSH key, used with `ssh-keygen -Y sign`.
+    Ssh,
+    /// A minisign key, used with `minisign -S`.
+    Minisign,
+}
+
 /// The table of crate policies.
-#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default, PartialEq, Eq)]
 #[serde(try_from = "serialization::policy::AllPolicies")]
 #[serde(into = "serialization::policy::AllPolicies")]
 pub struct Policy {
@@ -734,7 +1069,7 @@ impl<'a> IntoIterator for &'a Policy {
 /// If the crate exists as a third-party crate anywhere in the dependency tree, crate versions for
 /// _all_ and _only_ the versions present in the dependency tree must be provided to set policies.
 /// Otherwise, versions may be omitted.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 // We have to use a slightly different serialization than than `serde(untagged)`, because toml only
 // parses `Spanned` elements (as contained in `PolicyEntry`) through their own Deseralizer, and
 // `serde(untagged)` deserializes everything into a buffer first to try different deserialization
@@ -759,7 +1094,7 @@ pub enum PackagePolicyEntry {
 /// If this sounds overwhelming, don't worry, everything defaults to "nothing special"
 /// and an empty PolicyTable basically just means "everything should satisfy the
 /// default criteria in audits.toml".
-#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default, PartialEq, Eq)]
 pub struct PolicyEntry {
     /// Whether this nominally-first-party crate should actually be subject to audits
     /// as-if it was third-party, based on matches to crates.io packages with the same
@@ -798,6 +1133,15 @@ pub struct PolicyEntry {
     #[serde(with = "serialization::string_or_vec_or_none")]
     pub dev_criteria: Option<Vec<Spanned<CriteriaName>>>,
 
+    /// Same as `criteria`, but for build-dependencies and proc-macros, which
+    /// only run on the machine doing the build.
+    ///
+    /// If not present, these inherit `criteria` like any other dependency.
+    #[serde(rename = "build-criteria")]
+    #[serde(default)]
+    #[serde(with = "serialization::string_or_vec_or_none")]
+    pub build_criteria: Option<Vec<Spanned<CriteriaName>>>,
+
     /// Custom criteria for a specific crate's dependencies.
     ///
     /// Any dependency edge that isn't explicitly specified defaults to `criteria`.
@@ -831,7 +1175,7 @@ pub static DEFAULT_POLICY_CRITERIA: CriteriaStr = SAFE_TO_DEPLOY;
 pub static DEFAULT_POLICY_DEV_CRITERIA: CriteriaStr = SAFE_TO_RUN;
 
 /// A remote audits.toml that we trust the contents of (by virtue of trusting the maintainer).
-#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default, PartialEq, Eq)]
 pub struct RemoteImport {
     /// URL(s) of the foreign audits.toml
     #[serde(with = "serialization::string_or_vec")]
@@ -840,12 +1184,131 @@ pub struct RemoteImport {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     #[serde(default)]
     pub exclude: Vec<PackageName>,
+    /// If non-empty, only audits or violations for crates matching one of
+    /// these patterns are imported. Patterns may contain `*` wildcards.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// If non-empty, imported audits only certify these local criteria (and
+    /// the criteria they imply), after applying the criteria map.
+    #[serde(rename = "allowed-criteria")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(with = "serialization::string_or_vec")]
+    #[serde(default)]
+    pub allowed_criteria: Vec<Spanned<CriteriaName>>,
+    /// The auditors whose audits may be imported.
+    #[serde(skip_serializing_if = "WhoFilter::is_empty")]
+    #[serde(default)]
+    pub who: WhoFilter,
+    /// If non-empty, imported entries only certify the criteria of the scopes
+    /// which cover them, after applying the criteria map.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub scope: Vec<ImportScope>,
     /// A list of criteria that are implied by foreign criteria
     #[serde(rename = "criteria-map")]
     #[serde(skip_serializing_if = "CriteriaMap::is_empty")]
     #[serde(with = "serialization::criteria_map")]
     #[serde(default)]
     pub criteria_map: CriteriaMap,
+    /// Public keys (SSH or minisign) which audits must be signed by to be
+    /// imported. If empty, audits are imported without checking signatures.
+    #[serde(rename = "trusted-keys")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub trusted_keys: Vec<String>,
+    /// The hex-encoded SHA-256 hash of the import's contents. If set, the
+    /// import won't be updated until it is re-pinned with `import --update`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Whether new entries from this import must be accepted with
+    /// `import --review` before they're used.
+    #[serde(rename = "require-review")]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[serde(default)]
+    pub require_review: bool,
+    /// Whether to also import the audits which this peer imports, as listed in
+    /// the `config.toml` next to its `audits.toml`.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[serde(default)]
+    pub transitive: bool,
+    /// The maximum number of imports to follow from this peer when importing
+    /// transitively. Defaults to `DEFAULT_TRANSITIVE_IMPORT_DEPTH`.
+    #[serde(rename = "transitive-depth")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub transitive_depth: Option<usize>,
+    /// The precedence of this import's entries when resolving violation
+    /// conflicts with the "prefer-higher-priority" policy.
+    #[serde(skip_serializing_if = "is_zero")]
+    #[serde(default)]
+    pub priority: i64,
+}
+
+fn is_zero(val: &i64) -> bool {
+    *val == 0
+}
+
+/// How many imports deep audits are imported through transitive imports by
+/// default.
+pub const DEFAULT_TRANSITIVE_IMPORT_DEPTH: usize = 3;
+
+impl RemoteImport {
+    /// How many imports deep to follow this peer's imports, which is 0 if it
+    /// isn't imported transitively.
+    pub fn transitive_depth_limit(&self) -> usize {
+        if self.transitive {
+            self.transitive_depth
+                .unwrap_or(DEFAULT_TRANSITIVE_IMPORT_DEPTH)
+        } else {
+            0
+        }
+    }
+}
+
+/// Local criteria which imported entries may certify, for only some crates or
+/// auditors.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ImportScope {
+    /// The criteria which covered entries may certify (along with the criteria
+    /// they imply).
+    #[serde(with = "serialization::string_or_vec")]
+    pub criteria: Vec<Spanned<CriteriaName>>,
+    /// If non-empty, only entries for crates matching one of these patterns
+    /// are covered. Patterns may contain `*` wildcards.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub crates: Vec<String>,
+    /// The auditors whose audits are covered. If non-empty, trusted entries,
+    /// which have no auditor, aren't covered.
+    #[serde(skip_serializing_if = "WhoFilter::is_empty")]
+    #[serde(default)]
+    pub who: WhoFilter,
+}
+
+/// Lists of auditors whose imported audits are allowed or denied. Patterns may
+/// contain `*` wildcards, and match either the whole `who` of an audit, or
+/// just the email address within it.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct WhoFilter {
+    /// If non-empty, only audits by at least one of these auditors are
+    /// imported.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Audits by any of these auditors aren't imported.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl WhoFilter {
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
 }
 
 /// Translations of foreign criteria to local criteria.
@@ -873,6 +1336,12 @@ pub struct ExemptedDependency {
     #[serde(default = "get_default_exemptions_suggest")]
     #[serde(skip_serializing_if = "is_default_exemptions_suggest")]
     pub suggest: bool,
+    /// The workspace which needs this exemption, if the store governs several
+    /// workspaces and only one of them uses this version. Maintained
+    /// automatically when exemptions are pruned or regenerated.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<Spanned<WorkspaceName>>,
     /// Freeform notes, put whatever you want here. Just more stable/reliable than comments.
     pub notes: Option<String>,
 }
@@ -1009,6 +1478,15 @@ pub struct ImportsFile {
     #[serde(default)]
     #[serde(skip_serializing_if = "SortedMap::is_empty")]
     pub audits: SortedMap<ImportName, AuditsFile>,
+    /// Pinned copies of the base configs fetched from URLs in `extends`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "SortedMap::is_empty")]
+    pub extends: SortedMap<String, BaseConfigFile>,
+    /// Entries from each import which were rejected with `import --review`,
+    /// and so are never imported.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "SortedMap::is_empty")]
+    pub rejected: SortedMap<ImportName, AuditsFile>,
 }
 
 /// Information about who published a specific version of a crate to be cached
@@ -1070,6 +1548,10 @@ pub enum DiffCache {
     #[serde(rename = "2")]
     V2 {
         diffs: SortedMap<PackageName, SortedMap<Delta, DiffStat>>,
+        /// Diffs which only count shipped files (see `DiffScope::Shipped`).
+        #[serde(skip_serializing_if = "SortedMap::is_empty")]
+        #[serde(default)]
+        shipped_diffs: SortedMap<PackageName, SortedMap<Delta, DiffStat>>,
     },
 }
 
@@ -1077,6 +1559,36 @@ impl Default for DiffCache {
     fn default() -> Self {
         DiffCache::V2 {
             diffs: SortedMap::new(),
+            shipped_diffs: SortedMap::new(),
+        }
+    }
+}
+
+impl DiffCache {
+    /// The cached diffs computed with `scope`.
+    pub fn diffs(&self, scope: DiffScope) -> &SortedMap<PackageName, SortedMap<Delta, DiffStat>> {
+        let DiffCache::V2 {
+            diffs,
+            shipped_diffs,
+        } = self;
+        match scope {
+            DiffScope::All => diffs,
+            DiffScope::Shipped => shipped_diffs,
+        }
+    }
+
+    /// The cached diffs computed with `scope`, for recording new ones.
+    pub fn diffs_mut(
+        &mut self,
+        scope: DiffScope,
+    ) -> &mut SortedMap<PackageName, SortedMap<Delta, DiffStat>> {
+        let DiffCache::V2 {
+            diffs,
+            shipped_diffs,
+        } = self;
+        match scope {
+            DiffScope::All => diffs,
+            DiffScope::Shipped => shipped_diffs,
         }
     }
 }
@@ -1107,6 +1619,30 @@ impl fmt::Display for DiffStat {
     }
 }
 
+/// The result of comparing the published contents of a package with the
+/// commit it was published from.
+#[derive(Debug, Clone)]
+pub struct SourceComparison {
+    /// The commit recorded in the package's `.cargo_vcs_info.json`.
+    pub commit: String,
+    /// The path of the package within the repository.
+    pub path_in_vcs: String,
+    /// Whether the package was published from a working tree with
+    /// uncommitted changes.
+    pub dirty: bool,
+    /// Published files which aren't in the repository.
+    pub added: Vec<PathBuf>,
+    /// Published files which differ from the repository.
+    pub modified: Vec<PathBuf>,
+}
+
+impl SourceComparison {
+    /// Whether every published file is explained by the repository.
+    pub fn matches(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty()
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////////
 //                                                                                //
 //                                                                                //
@@ -1275,6 +1811,55 @@ pub struct RegistryEntry {
     pub url: Vec<String>,
 }
 
+/// Files downloaded from the registry, including the audits of each peer in
+/// it, so that they can be browsed without fetching them every time.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct RegistryCache {
+    pub files: SortedMap<String, RegistryCacheEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RegistryCacheEntry {
+    pub last_fetched: chrono::DateTime<chrono::Utc>,
+    pub source: String,
+}
+
+/// A searchable index of the audits in every audits file which has been
+/// fetched, from imported peers and from peers in the registry.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct AuditIndex {
+    pub sources: SortedMap<ImportName, AuditIndexSource>,
+}
+
+/// The audits indexed from one peer, which are replaced whenever the peer's
+/// audits are fetched again.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AuditIndexSource {
+    pub last_updated: chrono::DateTime<chrono::Utc>,
+    pub audits: SortedMap<PackageName, Vec<IndexedAudit>>,
+}
+
+/// An audit in the `AuditIndex`. Its criteria are mapped into the local
+/// namespace, as they would be if the audit were imported.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IndexedAudit {
+    pub kind: AuditKind,
+    pub criteria: Vec<CriteriaName>,
+    pub who: Vec<String>,
+    pub notes: Option<String>,
+}
+
+impl From<&AuditEntry> for IndexedAudit {
+    fn from(entry: &AuditEntry) -> Self {
+        IndexedAudit {
+            kind: entry.kind.clone(),
+            criteria: entry.criteria.iter().map(|c| c.to_string()).collect(),
+            who: entry.who.iter().map(|who| who.to_string()).collect(),
+            notes: entry.notes.clone(),
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////////
 //                                                                                //
 //                                                                                //
@@ -1297,6 +1882,30 @@ pub struct RegistryEntry {
 pub struct JsonReport {
     #[serde(flatten)]
     pub conclusion: JsonReportConclusion,
+    /// Violation conflicts which were resolved by the `violation-conflicts`
+    /// policy, rather than failing
+    // FIXME(SCHEMA): we probably shouldn't expose this internal type
+    #[serde(skip_serializing_if = "SortedMap::is_empty")]
+    #[serde(default)]
+    pub resolved_violation_conflicts: SortedMap<PackageAndVersion, Vec<ResolvedViolationConflict>>,
+}
+
+/// cargo-vet's `--output-format=json` for `check` when vetting the feature
+/// profiles from config.toml. Each profile gets its own complete report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonProfilesReport {
+    /// Whether every profile passed
+    pub conclusion: JsonProfilesConclusion,
+    /// The report for each profile which was vetted
+    pub profiles: SortedMap<ProfileName, JsonReport>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum JsonProfilesConclusion {
+    #[serde(rename = "success")]
+    Success,
+    #[serde(rename = "fail")]
+    Fail,
 }
 
 /// The conclusion of running `check` or `suggest`
@@ -1350,6 +1959,11 @@ pub struct JsonSuggest {
     /// The same set of suggestions but grouped by the criteria (lists) needed to audit them
     // FIXME(SCHEMA): this is kinda redundant? do consumers want this?
     pub suggest_by_criteria: SortedMap<String, Vec<JsonSuggestItem>>,
+    /// Already-audited versions which failing packages could be updated to
+    /// (only present with `--prefer-audited-versions`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub audited_versions: Vec<JsonAuditedVersion>,
     /// The total number of lines you would need to review to resolve this
     pub total_lines: u64,
 }
@@ -1363,6 +1977,16 @@ pub struct JsonVetFailure {
     pub version: VetVersion,
     /// The missing criteria
     pub missing_criteria: Vec<CriteriaName>,
+    /// Whether the package is only used at build time (by build scripts or
+    /// proc-macros), so its requirements came through `build-criteria`
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub build_only: bool,
+    /// The target platforms the package was required for (only present when
+    /// vetting for specific targets)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<String>,
 }
 
 /// We recommend auditing the following package
@@ -1380,6 +2004,19 @@ pub struct JsonSuggestItem {
     pub suggested_diff: DiffRecommendation,
 }
 
+/// An already-audited version which a failing package could be updated to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonAuditedVersion {
+    /// The name of the package
+    pub name: PackageName,
+    /// The version of the package currently in the graph
+    pub version: VetVersion,
+    /// The already-audited version to update to
+    pub audited_version: semver::Version,
+    /// The command which would update the lockfile to that version
+    pub command: String,
+}
+
 /// A string of the form "package:version"
 pub type PackageAndVersion = String;
 
@@ -1390,6 +2027,246 @@ pub struct JsonPackage {
     pub name: PackageName,
     /// Version of the package
     pub version: VetVersion,
+    /// The target platforms the package was required for (only present when
+    /// vetting for specific targets)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<String>,
+}
+
+////////////////////////////////////////////////////////////////////////////////////
+//                                                                                //
+//                                                                                //
+//                                                                                //
+//                             <json explain output>                              //
+//                                                                                //
+//                                                                                //
+//                                                                                //
+////////////////////////////////////////////////////////////////////////////////////
+
+/// cargo-vet's `--output-format=json` for `explain`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonExplain {
+    /// Name of the package
+    pub name: PackageName,
+    /// Version of the package
+    pub version: VetVersion,
+    /// The criteria the package was required to satisfy
+    pub required_criteria: Vec<CriteriaName>,
+    /// The first-party crates which (directly or through other third-party
+    /// crates) depend on the package, and so placed the requirements on it
+    pub required_by: Vec<PackageName>,
+    /// How each of the required criteria was satisfied
+    pub criteria: Vec<JsonExplainCriteria>,
+}
+
+/// The audit chain which satisfied a single criteria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonExplainCriteria {
+    /// The criteria being explained
+    pub criteria: CriteriaName,
+    /// Whether a chain of audits satisfying the criteria was found
+    pub satisfied: bool,
+    /// The edges of the chain, starting from the root (a full audit)
+    /// and ending at the package's version
+    pub path: Vec<JsonExplainEdge>,
+}
+
+/// A single edge in an audit chain, along with the entry it came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonExplainEdge {
+    /// What kind of entry this edge came from
+    pub kind: JsonExplainEdgeKind,
+    /// The version this edge starts at (`None` for full audits)
+    pub from: Option<VetVersion>,
+    /// The version this edge ends at
+    pub to: VetVersion,
+    /// The peer this entry was imported from (`None` for local entries)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub import: Option<ImportName>,
+    /// Who performed the audit
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub who: Vec<String>,
+    /// The criteria the entry certifies
+    pub criteria: Vec<CriteriaName>,
+    /// The crates.io user who published the version (for wildcard audits and
+    /// trusted entries)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    /// When the version was published (for wildcard audits and trusted entries)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<chrono::NaiveDate>,
+    /// The start of the period the entry applies to (for wildcard audits and
+    /// trusted entries)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<chrono::NaiveDate>,
+    /// The end of the period the entry applies to (for wildcard audits and
+    /// trusted entries)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<chrono::NaiveDate>,
+    /// Notes attached to the entry
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Chain of sources the entry was aggregated from
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aggregated_from: Vec<String>,
+}
+
+/// The kind of entry an edge in an audit chain came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum JsonExplainEdgeKind {
+    /// A full or delta audit
+    Audit,
+    /// A wildcard audit for versions published by a specific user
+    WildcardAudit,
+    /// A trusted entry for versions published by a specific user
+    Trusted,
+    /// An exemption from `config.toml`
+    Exemption,
+    /// An unpublished version audited as a published one
+    Unpublished,
+    /// A newly-suggested exemption
+    FreshExemption,
+}
+
+////////////////////////////////////////////////////////////////////////////////////
+//                                                                                //
+//                                                                                //
+//                                                                                //
+//                               <json why output>                                //
+//                                                                                //
+//                                                                                //
+//                                                                                //
+////////////////////////////////////////////////////////////////////////////////////
+
+/// cargo-vet's `--output-format=json` for `why`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonWhy {
+    /// Each version of the package in the dependency graph
+    pub packages: Vec<JsonWhyPackage>,
+}
+
+/// Why a single version of a package is required to satisfy its criteria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonWhyPackage {
+    /// Name of the package
+    pub name: PackageName,
+    /// Version of the package
+    pub version: VetVersion,
+    /// The criteria the package is required to satisfy (the union of the
+    /// criteria required along every path)
+    pub required_criteria: Vec<CriteriaName>,
+    /// Every path from a root crate to the package
+    pub paths: Vec<Vec<JsonWhyHop>>,
+    /// Whether some paths were omitted due to `--max-paths`
+    pub truncated: bool,
+}
+
+/// A single package on a path from a root crate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonWhyHop {
+    /// Name of the package
+    pub name: PackageName,
+    /// Version of the package
+    pub version: VetVersion,
+    /// The kind of dependency edge leading to this package (`None` for the
+    /// root crate at the start of the path)
+    pub dependency_kind: Option<JsonWhyDependencyKind>,
+    /// The criteria required of the package along this path
+    pub criteria: Vec<CriteriaName>,
+    /// Why the package requires those criteria along this path
+    pub reason: JsonWhyReason,
+}
+
+/// The kind of a dependency edge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum JsonWhyDependencyKind {
+    Normal,
+    Build,
+    Dev,
+}
+
+/// Where the criteria required at a hop came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+#[serde(rename_all = "kebab-case")]
+pub enum JsonWhyReason {
+    /// The package is a root crate, and requires the default criteria
+    DefaultRootCriteria,
+    /// The criteria were inherited from the parent package
+    Inherited,
+    /// The package is a dev-dependency, and requires the default dev-criteria
+    DefaultDevCriteria,
+    /// The `criteria` of the package's own policy entry
+    PolicyCriteria { policy: String },
+    /// The `dev-criteria` of the parent's policy entry
+    PolicyDevCriteria { policy: String },
+    /// The `build-criteria` of the parent's policy entry
+    PolicyBuildCriteria { policy: String },
+    /// The `dependency-criteria` of the parent's policy entry
+    PolicyDependencyCriteria { policy: String },
+}
+
+////////////////////////////////////////////////////////////////////////////////////
+//                                                                                //
+//                                                                                //
+//                                                                                //
+//                             <json what-if output>                              //
+//                                                                                //
+//                                                                                //
+//                                                                                //
+////////////////////////////////////////////////////////////////////////////////////
+
+/// cargo-vet's `--output-format=json` for `what-if`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonWhatIf {
+    /// A description of the hypothetical change
+    pub change: String,
+    /// Third-party packages which would be added to the dependency graph
+    pub added: Vec<JsonWhatIfPackage>,
+    /// Third-party packages which would no longer be in the dependency graph
+    pub removed: Vec<JsonPackage>,
+    /// The total number of lines you would need to review to cover the added
+    /// packages
+    pub total_lines: u64,
+    /// Any problems encountered while estimating the audits
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// The audit cost of a single package added by a hypothetical change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonWhatIfPackage {
+    /// Name of the package
+    pub name: PackageName,
+    /// Version of the package
+    pub version: VetVersion,
+    /// Whether existing audits, imports, trust entries and exemptions already
+    /// satisfy every criteria the package would be required to satisfy
+    pub vetted: bool,
+    /// The criteria the package would be required to satisfy
+    pub required_criteria: Vec<CriteriaName>,
+    /// How each of the required criteria would be satisfied
+    pub criteria: Vec<JsonExplainCriteria>,
+    /// The criteria we recommend auditing the package for
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suggested_criteria: Vec<CriteriaName>,
+    /// The diff (or full version) we recommend auditing
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_diff: Option<DiffRecommendation>,
 }
 
 #[cfg(test)]
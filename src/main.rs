@@ -5,9 +5,13 @@ use std::panic::panic_any;
 use std::process::Stdio;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime};
-use std::{fs::File, io, panic, path::PathBuf};
+use std::{
+    fs::File,
+    io, panic,
+    path::{Path, PathBuf},
+};
 
-use cargo_metadata::{Metadata, Package};
+use cargo_metadata::{Metadata, Package, PackageId};
 use clap::{CommandFactory, Parser};
 use console::Term;
 use errors::{
@@ -15,10 +19,13 @@ use errors::{
     AggregateCriteriaImplies, AggregateError, AggregateErrors, AggregateImpliesMismatchError,
     AuditAsError, AuditAsErrors, CacheAcquireError, CertifyError, CratePolicyError,
     CratePolicyErrors, FetchAuditError, LoadTomlError, NeedsAuditAsErrors,
-    NeedsPolicyVersionErrors, PackageError, ShouldntBeAuditAsErrors, TomlParseError,
-    UnusedAuditAsErrors, UnusedPolicyVersionErrors, UserInfoError,
+    NeedsPolicyVersionErrors, PackageError, ProfileError, ShouldntBeAuditAsErrors, SigningError,
+    TomlParseError, UnusedAuditAsErrors, UnusedPolicyVersionErrors, UserInfoError, WorkspaceError,
+};
+use format::{
+    CriteriaName, CriteriaStr, FastMap, FastSet, FeatureProfile, PackageName, Policy, PolicyEntry,
+    SortedSet, VetVersion, WorkspaceName,
 };
-use format::{CriteriaName, CriteriaStr, PackageName, Policy, PolicyEntry, SortedSet, VetVersion};
 use futures_util::future::{join_all, try_join_all};
 use indicatif::ProgressDrawTarget;
 use lazy_static::lazy_static;
@@ -35,11 +42,13 @@ use tracing::{error, info, trace, warn};
 use crate::cli::*;
 use crate::criteria::CriteriaMapper;
 use crate::errors::{
-    CommandError, DownloadError, FetchAndDiffError, FetchError, MetadataAcquireError, SourceFile,
+    CommandError, DiffSourceError, DownloadError, FetchAndDiffError, FetchError,
+    MetadataAcquireError, SourceFile,
 };
 use crate::format::{
-    AuditEntry, AuditKind, AuditsFile, ConfigFile, CratesUserId, CriteriaEntry, ExemptedDependency,
-    FetchCommand, MetaConfig, MetaConfigInstance, PackageStr, SortedMap, StoreInfo, TrustEntry,
+    AuditEntry, AuditKind, AuditsFile, ConfigFile, CratesUserId, CriteriaEntry, DiffScope,
+    DiffStat, ExemptedDependency, FetchCommand, ImportName, MetaConfig, MetaConfigInstance,
+    PackageStr, RegistryConfig, SortedMap, SourceComparison, StoreInfo, TrustEntry, UnpackConfig,
     WildcardEntry,
 };
 use crate::git_tool::Pager;
@@ -48,6 +57,7 @@ use crate::storage::{Cache, Store};
 
 mod cli;
 mod criteria;
+mod db_dump;
 pub mod errors;
 mod flock;
 pub mod format;
@@ -56,6 +66,8 @@ pub mod network;
 mod out;
 pub mod resolver;
 mod serialization;
+mod shipped;
+mod signing;
 pub mod storage;
 mod string_format;
 #[cfg(test)]
@@ -67,10 +79,21 @@ pub struct Config {
     pub metacfg: MetaConfig,
     /// `cargo metadata`
     pub metadata: Metadata,
+    /// The workspaces governed by the store, if it governs several of them.
+    /// In that case `metadata` is the merged dependency graph of them all.
+    pub workspaces: SortedMap<WorkspaceName, Workspace>,
     /// Freestanding configuration values
     _rest: PartialConfig,
 }
 
+/// A Cargo workspace governed by a store which is shared between several.
+pub struct Workspace {
+    /// Path to the workspace's root Cargo.toml
+    pub manifest: PathBuf,
+    /// The packages in the workspace's dependency graph
+    pub packages: FastSet<PackageId>,
+}
+
 /// Configuration vars that are available in a free-standing situation
 /// (no actual cargo-vet instance to load/query).
 pub struct PartialConfig {
@@ -82,12 +105,25 @@ pub struct PartialConfig {
     pub cache_dir: PathBuf,
     /// Whether we should mock the global cache (for unit testing)
     pub mock_cache: bool,
+    /// The store's `[registry]` config, which is read before the store itself.
+    pub registry: RegistryConfig,
+    /// The store's `[unpack]` config, which is read before the store itself.
+    pub unpack: UnpackConfig,
 }
 
 impl PartialConfig {
     pub fn today(&self) -> chrono::NaiveDate {
         self.now.date_naive()
     }
+
+    /// The URL (or path) of the registry of peers.
+    pub fn registry_url(&self) -> &str {
+        self.cli
+            .registry_url
+            .as_deref()
+            .or(self.registry.peers.as_deref())
+            .unwrap_or(storage::REGISTRY_URL)
+    }
 }
 
 // Makes it a bit easier to have both a "partial" and "full" config
@@ -339,11 +375,13 @@ fn real_main() -> Result<(), miette::Report> {
     let now = cli
         .current_time
         .unwrap_or_else(|| chrono::DateTime::from(SystemTime::now()));
-    let partial_cfg = PartialConfig {
+    let mut partial_cfg = PartialConfig {
         cli,
         now,
         cache_dir,
         mock_cache: false,
+        registry: RegistryConfig::default(),
+        unpack: UnpackConfig::default(),
     };
 
     match &partial_cfg.cli.command {
@@ -360,43 +398,20 @@ fn real_main() -> Result<(), miette::Report> {
     ///////////////////////////////////////////////////
 
     let cli = &partial_cfg.cli;
-    let cargo_path = std::env::var_os(CARGO_ENV).expect("Cargo failed to set $CARGO, how?");
+    let color = !using_log_file
+        && cli.output_format == OutputFormat::Human
+        && console::colors_enabled_stderr();
 
-    let mut cmd = cargo_metadata::MetadataCommand::new();
-    cmd.cargo_path(cargo_path);
-    if let Some(manifest_path) = &cli.manifest_path {
-        cmd.manifest_path(manifest_path);
-    }
-    if !cli.no_all_features {
-        cmd.features(cargo_metadata::CargoOpt::AllFeatures);
-    }
-    if cli.no_default_features {
-        cmd.features(cargo_metadata::CargoOpt::NoDefaultFeatures);
-    }
-    if !cli.features.is_empty() {
-        cmd.features(cargo_metadata::CargoOpt::SomeFeatures(cli.features.clone()));
-    }
-    // We never want cargo-vet to update the Cargo.lock.
-    // For frozen runs we also don't want to touch the network.
-    let mut other_options = Vec::new();
-    if cli.frozen {
-        other_options.push("--frozen".to_string());
+    // ERRORS: immediate fatal diagnostic
+    let metadata = if let Some(metadata_file) = &cli.metadata_file {
+        info!("Reading metadata from: {}", metadata_file.display());
+        load_metadata_file(metadata_file)?
     } else {
-        other_options.push("--locked".to_string());
-    }
-    if !using_log_file
-        && cli.output_format == OutputFormat::Human
-        && console::colors_enabled_stderr()
-    {
-        other_options.push("--color=always".to_string());
-    }
-    other_options.extend(cli.cargo_arg.iter().cloned());
-    cmd.other_options(other_options);
+        let mut cmd = metadata_command(cli, color);
+        apply_cli_features(&mut cmd, cli);
 
-    info!("Running: {:#?}", cmd.cargo_command());
+        info!("Running: {:#?}", cmd.cargo_command());
 
-    // ERRORS: immediate fatal diagnostic
-    let metadata = {
         let _spinner = indeterminate_spinner("Running", "`cargo metadata`");
         cmd.exec().map_err(MetadataAcquireError::from)?
     };
@@ -493,9 +508,39 @@ fn real_main() -> Result<(), miette::Report> {
         ));
     }
 
+    // If the store is shared between several workspaces, vet all of their
+    // dependency graphs together.
+    // ERRORS: immediate fatal diagnostic
+    let (metadata, workspaces) = if init {
+        load_workspaces(cli, color, &metacfg, metadata)?
+    } else {
+        (metadata, SortedMap::new())
+    };
+
+    // The crates.io endpoints and unpacking limits are needed before the store
+    // is loaded, as it may fetch publisher information while loading.
+    // ERRORS: immediate fatal diagnostic
+    if init {
+        let config = Store::read_config_unlocked(&metacfg)?;
+        partial_cfg.registry = config.registry.unwrap_or_default();
+        // A local registry named in config.toml is relative to the store.
+        if let Some(peers) = &mut partial_cfg.registry.peers {
+            if let Err(url::ParseError::RelativeUrlWithoutBase) = Url::parse(peers) {
+                *peers = metacfg
+                    .store_path()
+                    .as_path_unlocked()
+                    .join(&*peers)
+                    .display()
+                    .to_string();
+            }
+        }
+        partial_cfg.unpack = config.unpack.unwrap_or_default();
+    }
+
     let cfg = Config {
         metacfg,
         metadata,
+        workspaces,
         _rest: partial_cfg,
     };
 
@@ -507,14 +552,23 @@ fn real_main() -> Result<(), miette::Report> {
         Some(Certify(sub_args)) => cmd_certify(&out, &cfg, sub_args),
         Some(Import(sub_args)) => cmd_import(&out, &cfg, sub_args),
         Some(Trust(sub_args)) => cmd_trust(&out, &cfg, sub_args),
+        Some(Registry(sub_args)) => cmd_registry(&out, &cfg, sub_args),
+        Some(Search(sub_args)) => cmd_search(&out, &cfg, sub_args),
         Some(AddExemption(sub_args)) => cmd_add_exemption(&out, &cfg, sub_args),
         Some(RecordViolation(sub_args)) => cmd_record_violation(&out, &cfg, sub_args),
         Some(Suggest(sub_args)) => cmd_suggest(&out, &cfg, sub_args),
+        Some(Explain(sub_args)) => cmd_explain(&out, &cfg, sub_args),
+        Some(Why(sub_args)) => cmd_why(&out, &cfg, sub_args),
+        Some(WhatIf(WhatIfSubcommands::Add(sub_args))) => cmd_what_if_add(&out, &cfg, sub_args),
+        Some(WhatIf(WhatIfSubcommands::Upgrade(sub_args))) => {
+            cmd_what_if_upgrade(&out, &cfg, sub_args)
+        }
         Some(Fmt(sub_args)) => cmd_fmt(&out, &cfg, sub_args),
         Some(Prune(sub_args)) => cmd_prune(&out, &cfg, sub_args),
         Some(DumpGraph(sub_args)) => cmd_dump_graph(&out, &cfg, sub_args),
         Some(Inspect(sub_args)) => cmd_inspect(&out, &cfg, sub_args),
         Some(Diff(sub_args)) => cmd_diff(&out, &cfg, sub_args),
+        Some(DiffSource(sub_args)) => cmd_diff_source(&out, &cfg, sub_args),
         Some(Regenerate(Imports(sub_args))) => cmd_regenerate_imports(&out, &cfg, sub_args),
         Some(Regenerate(Exemptions(sub_args))) => cmd_regenerate_exemptions(&out, &cfg, sub_args),
         Some(Regenerate(AuditAsCratesIo(sub_args))) => {
@@ -522,6 +576,7 @@ fn real_main() -> Result<(), miette::Report> {
         }
         Some(Regenerate(Unpublished(sub_args))) => cmd_regenerate_unpublished(&out, &cfg, sub_args),
         Some(Renew(sub_args)) => cmd_renew(&out, &cfg, sub_args),
+        Some(IngestDbDump(sub_args)) => cmd_ingest_db_dump(&out, &cfg, sub_args),
         Some(Aggregate(_)) | Some(HelpMarkdown(_)) | Some(Gc(_)) => unreachable!("handled earlier"),
     }
 }
@@ -849,6 +904,38 @@ fn do_cmd_certify(
     };
 
     let mut notes = sub_args.notes.clone();
+    if let Some(repo) = &sub_args.matches_source {
+        let version = match &kind {
+            CertifyKind::Full { version } => version,
+            CertifyKind::Delta { to, .. } => to,
+            CertifyKind::Wildcard { .. } => {
+                unreachable!("--matches-source conflicts with --wildcard")
+            }
+        };
+        let cache = Cache::acquire(cfg)?;
+        let comparison = tokio::runtime::Handle::current().block_on(async {
+            let published = cache
+                .fetch_package(&cfg.metadata, network, &package, version)
+                .await
+                .map_err(DiffSourceError::from)?;
+            cache.compare_with_source(&published, repo).await
+        })?;
+        if !comparison.matches() {
+            return Err(CertifyError::SourceMismatch {
+                package,
+                version: version.clone(),
+                commit: comparison.commit,
+            });
+        }
+        let finding = format!(
+            "matches-source: the published package matches commit {}",
+            comparison.commit
+        );
+        notes = Some(match notes {
+            Some(notes) => format!("{notes}\n{finding}"),
+            None => finding,
+        });
+    }
     if !sub_args.accept_all {
         // Get all the EULAs at once
         let eulas = tokio::runtime::Handle::current().block_on(join_all(
@@ -922,20 +1009,23 @@ fn do_cmd_certify(
         CertifyKind::Full { version } => {
             let kind = AuditKind::Full { version };
             let importable = kind.default_importable();
+            let mut entry = AuditEntry {
+                kind,
+                criteria,
+                who,
+                importable,
+                notes,
+                aggregated_from: vec![],
+                signature: None,
+                is_fresh_import: false,
+            };
+            sign_audit(cfg, store, &package, &mut entry)?;
             store
                 .audits
                 .audits
                 .entry(package.clone())
                 .or_default()
-                .push(AuditEntry {
-                    kind,
-                    criteria,
-                    who,
-                    importable,
-                    notes,
-                    aggregated_from: vec![],
-                    is_fresh_import: false,
-                });
+                .push(entry);
         }
         CertifyKind::Delta { from, to } => {
             let from_is_git_version = from.git_rev.is_some();
@@ -949,6 +1039,7 @@ fn do_cmd_certify(
                 importable,
                 notes,
                 aggregated_from: vec![],
+                signature: None,
                 is_fresh_import: false,
             };
 
@@ -1013,6 +1104,7 @@ fn do_cmd_certify(
                 }
             }
 
+            sign_audit(cfg, store, &package, &mut entry)?;
             store
                 .audits
                 .audits
@@ -1269,7 +1361,7 @@ async fn prompt_criteria_eulas(
 }
 
 fn cmd_import(
-    _out: &Arc<dyn Out>,
+    out: &Arc<dyn Out>,
     cfg: &Config,
     sub_args: &ImportArgs,
 ) -> Result<(), miette::Report> {
@@ -1277,15 +1369,30 @@ fn cmd_import(
         return Err(miette!("`cargo vet import` cannot be run while frozen"));
     };
 
+    if sub_args.review {
+        return cmd_import_review(out, cfg, &network, sub_args.name.as_ref());
+    }
+    let name = sub_args
+        .name
+        .as_ref()
+        .expect("a name is required unless reviewing");
+
     // Determine the URL for the import, potentially fetching the registry to
-    // find it.
+    // find it. Imports which are being updated keep their existing URLs.
+    let cache = Cache::acquire(cfg)?;
     let registry_file;
-    let import_urls = if sub_args.url.is_empty() {
-        registry_file = tokio::runtime::Handle::current().block_on(fetch_registry(&network))?;
+    let import_urls = if sub_args.update {
+        Vec::new()
+    } else if sub_args.url.is_empty() {
+        registry_file = tokio::runtime::Handle::current().block_on(fetch_registry(
+            Some(&network),
+            &cache,
+            cfg.registry_url(),
+        ))?;
         registry_file
             .registry
-            .get(&sub_args.name)
-            .ok_or_else(|| miette!("no peer named {} found in the registry", &sub_args.name))
+            .get(name)
+            .ok_or_else(|| miette!("no peer named {} found in the registry", name))
             .map(|entry| entry.url.clone())?
     } else {
         sub_args.url.clone()
@@ -1293,17 +1400,28 @@ fn cmd_import(
 
     let mut store = Store::acquire_offline(cfg)?;
 
-    // Insert a new entry for the new import, or update an existing entry to use
-    // the newly specified URLs.
-    store
-        .config
-        .imports
-        .entry(sub_args.name.clone())
-        .or_default()
-        .url = import_urls;
+    let import = if sub_args.update {
+        store
+            .config
+            .imports
+            .get_mut(name)
+            .ok_or_else(|| miette!("no peer named {} is imported", name))?
+    } else {
+        // Insert a new entry for the new import, or update an existing entry to
+        // use the newly specified URLs.
+        let import = store.config.imports.entry(name.clone()).or_default();
+        import.url = import_urls;
+        import
+    };
+
+    // Remove any pin so that the current contents of the import are accepted.
+    // It will be re-pinned to those contents below.
+    let pinned = import.sha256.take().is_some();
+    let import_urls = import.url.clone();
+    let transitive = import.transitive;
+    let old_audits = store.imports.audits.get(name).cloned().unwrap_or_default();
 
     // After adding the new entry, go online, this will fetch the new import.
-    let cache = Cache::acquire(cfg)?;
     tokio::runtime::Handle::current().block_on(store.go_online(cfg, &network, &cache, false))?;
 
     // Update the store state, pruning unnecessary exemptions, audits, and imports.
@@ -1314,11 +1432,368 @@ fn cmd_import(
         prune_imports: true,
     });
 
+    if sub_args.update {
+        let diff = import_diff(&old_audits, &store.imports.audits[name]);
+        if diff.is_empty() {
+            writeln!(out, "No changes to the audits imported from {}", name);
+        } else {
+            writeln!(out, "Changes to the audits imported from {}:", name);
+            writeln!(out);
+            write!(out, "{diff}");
+        }
+    }
+
+    if (pinned || sub_args.update) && transitive {
+        // The sha256 wouldn't cover the audits imported through the peer.
+        warn!("The import '{name}' is transitive, so it won't be pinned to a sha256");
+    } else if pinned || sub_args.update {
+        let root = cfg.metacfg.store_path();
+        let sha256 = tokio::runtime::Handle::current().block_on(storage::fetch_import_sha256(
+            Some(&network),
+            Some(root.as_path_unlocked()),
+            name,
+            &import_urls,
+        ))?;
+        store.config.imports.get_mut(name).unwrap().sha256 = Some(sha256);
+    }
+
     store.commit()?;
 
     Ok(())
 }
 
+fn cmd_import_review(
+    out: &Arc<dyn Out>,
+    cfg: &Config,
+    network: &Network,
+    name: Option<&ImportName>,
+) -> Result<(), miette::Report> {
+    let mut store = Store::acquire_offline(cfg)?;
+    if let Some(name) = name {
+        if !store.config.imports.contains_key(name) {
+            return Err(miette!("no peer named {} is imported", name));
+        }
+    }
+
+    // Fetch new entries from the imports being reviewed, even if they would
+    // otherwise be held back until reviewed.
+    let mut require_review = Vec::new();
+    for (import_name, import) in &mut store.config.imports {
+        if import.require_review && name.map_or(true, |name| name == import_name) {
+            import.require_review = false;
+            require_review.push(import_name.clone());
+        }
+    }
+
+    let cache = Cache::acquire(cfg)?;
+    tokio::runtime::Handle::current().block_on(store.go_online(cfg, network, &cache, false))?;
+
+    let accepted = review_imports(out, &mut store, name)?;
+
+    // Restore `require-review` before updating the store, so that accepted
+    // trusted entries are recorded in imports.lock.
+    for import_name in require_review {
+        store
+            .config
+            .imports
+            .get_mut(&import_name)
+            .unwrap()
+            .require_review = true;
+    }
+
+    import_reviewed_entries(cfg, &mut store, accepted);
+
+    store.commit()?;
+
+    Ok(())
+}
+
+/// The update mode used to import entries once they've been reviewed.
+const IMPORT_REVIEW_MODE: resolver::UpdateMode = resolver::UpdateMode {
+    search_mode: resolver::SearchMode::PreferFreshImports,
+    prune_exemptions: true,
+    prune_non_importable_audits: true,
+    prune_imports: true,
+};
+
+/// An entry from an import which is being reviewed.
+#[derive(Clone, PartialEq, Eq)]
+enum ImportedEntry {
+    Audit(AuditEntry),
+    Wildcard(WildcardEntry),
+    Trusted(TrustEntry),
+}
+
+/// An entry which was accepted when reviewing an import.
+type AcceptedEntry = (ImportName, PackageName, ImportedEntry);
+
+/// Prompt the user to accept or reject each fresh entry in the live imports
+/// from `name` (or from every import), including violations and entries which
+/// aren't needed to vet the current graph. Rejected entries are removed from
+/// the store's live imports, and recorded in imports.lock so they won't be
+/// offered again. The accepted entries are returned, to be passed to
+/// `import_reviewed_entries`.
+fn review_imports(
+    out: &Arc<dyn Out>,
+    store: &mut Store,
+    name: Option<&ImportName>,
+) -> Result<Vec<AcceptedEntry>, miette::Report> {
+    let mut pending = Vec::new();
+    for (import_name, live_audits) in store.imported_audits() {
+        if name.map_or(false, |name| name != import_name) {
+            continue;
+        }
+        for (package, audits) in &live_audits.audits {
+            for audit in audits.iter().filter(|audit| audit.is_fresh_import) {
+                let entry = ImportedEntry::Audit(audit.clone());
+                pending.push((import_name.clone(), package.clone(), entry));
+            }
+        }
+        for (package, audits) in &live_audits.wildcard_audits {
+            for audit in audits.iter().filter(|audit| audit.is_fresh_import) {
+                let entry = ImportedEntry::Wildcard(audit.clone());
+                pending.push((import_name.clone(), package.clone(), entry));
+            }
+        }
+        // Trusted entries don't track freshness, so are compared with those
+        // which were accepted before.
+        let old_audits = store.imports.audits.get(import_name);
+        for (package, entries) in &live_audits.trusted {
+            let old = old_audits.and_then(|old| old.trusted.get(package));
+            for entry in entries {
+                if old.map_or(false, |old| old.iter().any(|e| e.same_trust_as(entry))) {
+                    continue;
+                }
+                let entry = ImportedEntry::Trusted(entry.clone());
+                pending.push((import_name.clone(), package.clone(), entry));
+            }
+        }
+    }
+
+    let mut accepted = Vec::new();
+    for (import_name, package, entry) in pending {
+        writeln!(out);
+        write_imported_entry(out, &import_name, &package, &entry);
+        if prompt_accept(out)? {
+            accepted.push((import_name, package, entry));
+            continue;
+        }
+
+        let rejected = store
+            .imports
+            .rejected
+            .entry(import_name.clone())
+            .or_default();
+        match entry {
+            ImportedEntry::Audit(audit) => rejected.audits.entry(package).or_default().push(audit),
+            ImportedEntry::Wildcard(audit) => rejected
+                .wildcard_audits
+                .entry(package)
+                .or_default()
+                .push(audit),
+            ImportedEntry::Trusted(entry) => {
+                rejected.trusted.entry(package).or_default().push(entry)
+            }
+        }
+        let live_imports = store.live_imports.as_mut().unwrap();
+        storage::remove_rejected_entries(
+            live_imports.audits.get_mut(&import_name).unwrap(),
+            &store.imports.rejected[&import_name],
+        );
+    }
+    Ok(accepted)
+}
+
+/// Update the store state after reviewing imports, pulling in the accepted
+/// entries. Every accepted entry is recorded in imports.lock, even if it isn't
+/// needed to vet the current graph, so that it isn't offered for review again.
+/// As elsewhere, trusted entries are only recorded for imports which require
+/// review.
+fn import_reviewed_entries(cfg: &Config, store: &mut Store, accepted: Vec<AcceptedEntry>) {
+    resolver::update_store(cfg, store, |_| IMPORT_REVIEW_MODE);
+
+    for (import_name, package, entry) in accepted {
+        let require_review = store
+            .config
+            .imports
+            .get(&import_name)
+            .map_or(false, |import| import.require_review);
+        if matches!(entry, ImportedEntry::Trusted(_)) && !require_review {
+            continue;
+        }
+        let audits_file = store.imports.audits.entry(import_name).or_default();
+        match entry {
+            ImportedEntry::Audit(mut audit) => {
+                let audits = audits_file.audits.entry(package).or_default();
+                if !audits.iter().any(|a| a.same_audit_as(&audit)) {
+                    audit.is_fresh_import = false;
+                    audits.push(audit);
+                }
+            }
+            ImportedEntry::Wildcard(mut audit) => {
+                let audits = audits_file.wildcard_audits.entry(package).or_default();
+                if !audits.iter().any(|a| a.same_audit_as(&audit)) {
+                    audit.is_fresh_import = false;
+                    audits.push(audit);
+                }
+            }
+            ImportedEntry::Trusted(entry) => {
+                let entries = audits_file.trusted.entry(package).or_default();
+                if !entries.iter().any(|e| e.same_trust_as(&entry)) {
+                    entries.push(entry);
+                }
+            }
+        }
+    }
+}
+
+/// Describe an entry from an import which is being reviewed.
+fn write_imported_entry(
+    out: &Arc<dyn Out>,
+    import_name: &str,
+    package: PackageStr<'_>,
+    entry: &ImportedEntry,
+) {
+    let criteria = |criteria: &[Spanned<CriteriaName>]| {
+        criteria
+            .iter()
+            .map(|criteria| criteria.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let (what, who, notes) = match entry {
+        ImportedEntry::Audit(audit) => {
+            let what = match &audit.kind {
+                AuditKind::Full { version } => format!("audit of {package}:{version}"),
+                AuditKind::Delta { from, to } => {
+                    format!("audit of {package}:{from} -> {to}")
+                }
+                AuditKind::Violation { violation } => {
+                    format!("violation against {package}:{violation}")
+                }
+            };
+            (
+                format!("{what} for {}", criteria(&audit.criteria)),
+                &audit.who[..],
+                &audit.notes,
+            )
+        }
+        ImportedEntry::Wildcard(audit) => (
+            format!(
+                "wildcard audit of {package} for {}, for versions published by user {} from {} to {}",
+                criteria(&audit.criteria),
+                audit.user_id,
+                *audit.start,
+                *audit.end
+            ),
+            &audit.who[..],
+            &audit.notes,
+        ),
+        ImportedEntry::Trusted(entry) => (
+            format!(
+                "trust of {package} for {}, for versions published by user {} from {} to {}",
+                criteria(&entry.criteria),
+                entry.user_id,
+                *entry.start,
+                *entry.end
+            ),
+            &[][..],
+            &entry.notes,
+        ),
+    };
+
+    writeln!(out, "{}: {what}", out.style().bold().apply_to(import_name));
+    if !who.is_empty() {
+        let who = who.iter().map(|who| who.to_string()).collect::<Vec<_>>();
+        writeln!(out, "  who: {}", who.join(", "));
+    }
+    if let Some(notes) = notes {
+        writeln!(out, "  notes: {notes}");
+    }
+}
+
+/// Ask the user whether to accept the entry which was just described.
+fn prompt_accept(out: &Arc<dyn Out>) -> Result<bool, miette::Report> {
+    loop {
+        let input = out
+            .read_line_with_prompt("accept? (y/n) ")
+            .into_diagnostic()?;
+        match input.trim() {
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => writeln!(out, "error: please answer y or n"),
+        }
+    }
+}
+
+/// Describe the changes between two copies of the audits imported from a peer,
+/// listing each entry which was removed or added.
+fn import_diff(old: &AuditsFile, new: &AuditsFile) -> String {
+    fn render<'a, T: serde::Serialize + 'a>(
+        entries: impl Iterator<Item = (&'a String, &'a T)>,
+    ) -> SortedMap<&'a String, Vec<String>> {
+        let mut rendered = SortedMap::<_, Vec<_>>::new();
+        for (key, entry) in entries {
+            let entry = toml::to_string(entry).expect("entries are always serializable");
+            rendered.entry(key).or_default().push(entry);
+        }
+        rendered
+    }
+    fn flatten<T>(map: &SortedMap<String, Vec<T>>) -> impl Iterator<Item = (&String, &T)> {
+        map.iter()
+            .flat_map(|(key, entries)| entries.iter().map(move |entry| (key, entry)))
+    }
+
+    let sections = [
+        (
+            "criteria",
+            render(old.criteria.iter()),
+            render(new.criteria.iter()),
+        ),
+        (
+            "wildcard-audits",
+            render(flatten(&old.wildcard_audits)),
+            render(flatten(&new.wildcard_audits)),
+        ),
+        (
+            "audits",
+            render(flatten(&old.audits)),
+            render(flatten(&new.audits)),
+        ),
+        (
+            "trusted",
+            render(flatten(&old.trusted)),
+            render(flatten(&new.trusted)),
+        ),
+    ];
+
+    let mut diff = String::new();
+    for (section, old, new) in sections {
+        let keys = old.keys().chain(new.keys()).collect::<SortedSet<_>>();
+        for key in keys {
+            let old = old.get(key).map(|v| &v[..]).unwrap_or(&[]);
+            let new = new.get(key).map(|v| &v[..]).unwrap_or(&[]);
+            let removed = old.iter().filter(|entry| !new.contains(entry));
+            let added = new.iter().filter(|entry| !old.contains(entry));
+            let changes = removed
+                .map(|entry| ('-', entry))
+                .chain(added.map(|entry| ('+', entry)))
+                .collect::<Vec<_>>();
+            if changes.is_empty() {
+                continue;
+            }
+            diff.push_str(&format!("[{section}.{key}]\n"));
+            for (sign, entry) in changes {
+                for line in entry.lines() {
+                    diff.push_str(&format!("{sign} {line}\n"));
+                }
+            }
+            diff.push('\n');
+        }
+    }
+    diff
+}
+
 fn cmd_trust(out: &Arc<dyn Out>, cfg: &Config, sub_args: &TrustArgs) -> Result<(), miette::Report> {
     // Certify that you have reviewed a crate's source for some version / delta
     let network = Network::acquire(cfg);
@@ -1579,45 +2054,289 @@ fn apply_cmd_trust(
     Ok(())
 }
 
-fn cmd_record_violation(
+fn cmd_registry(
     out: &Arc<dyn Out>,
     cfg: &Config,
-    sub_args: &RecordViolationArgs,
+    sub_args: &RegistrySubcommands,
 ) -> Result<(), miette::Report> {
-    // Mark a package as a violation
-    let mut store = Store::acquire_offline(cfg)?;
-
-    let kind = AuditKind::Violation {
-        violation: sub_args.versions.clone(),
-    };
-
-    let (_username, who) = if sub_args.who.is_empty() {
-        let user_info = get_user_info()?;
-        let who = format!("{} <{}>", user_info.username, user_info.email);
-        (user_info.username, vec![Spanned::from(who)])
-    } else {
-        (
-            sub_args.who.join(", "),
-            sub_args
-                .who
-                .iter()
-                .map(|w| Spanned::from(w.clone()))
-                .collect(),
-        )
-    };
+    // The registry is cached, so it can still be explored while frozen.
+    let network = Network::acquire(cfg);
+    let store = Store::acquire_offline(cfg)?;
+    let cache = Cache::acquire(cfg)?;
 
-    let notes = sub_args.notes.clone();
+    do_cmd_registry(out, cfg, sub_args, &store, network.as_ref(), &cache)
+}
 
-    let criteria = if sub_args.criteria.is_empty() {
-        // TODO: provide an interactive prompt for this
-        vec![store.config.default_criteria.clone().into()]
-    } else {
-        sub_args
-            .criteria
-            .iter()
-            .map(|s| s.to_owned().into())
-            .collect()
-    };
+fn do_cmd_registry(
+    out: &Arc<dyn Out>,
+    cfg: &Config,
+    sub_args: &RegistrySubcommands,
+    store: &Store,
+    network: Option<&Network>,
+    cache: &Cache,
+) -> Result<(), miette::Report> {
+    let registry_file = tokio::runtime::Handle::current().block_on(fetch_registry(
+        network,
+        cache,
+        cfg.registry_url(),
+    ))?;
+
+    if let RegistrySubcommands::List(_) = sub_args {
+        for (name, entry) in &registry_file.registry {
+            if store.config.imports.contains_key(name) {
+                writeln!(out, "{name} (imported)");
+            } else {
+                writeln!(out, "{name}");
+            }
+            for url in &entry.url {
+                writeln!(out, "  {url}");
+            }
+        }
+        return Ok(());
+    }
+
+    // Download the audits of every peer, skipping any which can't be read.
+    let local_criteria_mapper = &CriteriaMapper::new(&store.audits.criteria);
+    let peers =
+        tokio::runtime::Handle::current().block_on(join_all(registry_file.registry.iter().map(
+            |(name, entry)| async move {
+                match storage::fetch_registry_peer_audits(
+                    network,
+                    cache,
+                    local_criteria_mapper,
+                    name,
+                    entry,
+                )
+                .await
+                {
+                    Ok(audits_files) => Some((name, audits_files)),
+                    Err(error) => {
+                        warn!("{:?}", miette::Report::new(error));
+                        None
+                    }
+                }
+            },
+        )));
+
+    match sub_args {
+        RegistrySubcommands::List(_) => unreachable!("handled above"),
+        RegistrySubcommands::Search(RegistrySearchArgs { package }) => {
+            for (name, audits_files) in peers.iter().flatten() {
+                let entries = describe_registry_entries(audits_files, package, None);
+                if entries.is_empty() {
+                    writeln!(out, "{name}: no audits of {package}");
+                    continue;
+                }
+                writeln!(out, "{name}:");
+                for entry in entries {
+                    writeln!(out, "  {entry}");
+                }
+            }
+        }
+        RegistrySubcommands::Coverage(_) => {
+            let dependencies: Vec<&Package> = cfg
+                .metadata
+                .packages
+                .iter()
+                .filter(|package| package.is_third_party(&store.config.policy))
+                .collect();
+            for (name, audits_files) in peers.iter().flatten() {
+                let covered: Vec<_> = dependencies
+                    .iter()
+                    .map(|package| {
+                        let version = package.vet_version();
+                        let entries =
+                            describe_registry_entries(audits_files, &package.name, Some(&version));
+                        (package, version, entries)
+                    })
+                    .filter(|(_, _, entries)| !entries.is_empty())
+                    .collect();
+                writeln!(
+                    out,
+                    "{name} has entries for {} of {} dependencies",
+                    covered.len(),
+                    dependencies.len()
+                );
+                for (package, version, entries) in covered {
+                    for entry in entries {
+                        writeln!(out, "  {}:{version}: {entry}", package.name);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Describe a peer's entries for `package` in the registry, with their
+/// criteria. If `version` is given, only entries which could vet that version
+/// are described.
+fn describe_registry_entries(
+    audits_files: &[AuditsFile],
+    package: PackageStr<'_>,
+    version: Option<&VetVersion>,
+) -> Vec<String> {
+    let mut entries = Vec::new();
+    for audits_file in audits_files {
+        let criteria = |criteria: &[Spanned<CriteriaName>]| {
+            criteria
+                .iter()
+                .map(|criteria| criteria.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        for audit in audits_file.audits.get(package).into_iter().flatten() {
+            let matches = version.map_or(true, |version| match &audit.kind {
+                AuditKind::Full { version: audited } => version == audited,
+                AuditKind::Delta { to, .. } => version == to,
+                AuditKind::Violation { violation } => violation.matches(version),
+            });
+            if matches {
+                entries.push(format!(
+                    "{} for {}",
+                    describe_audit_kind(&audit.kind),
+                    criteria(&audit.criteria)
+                ));
+            }
+        }
+        for entry in audits_file
+            .wildcard_audits
+            .get(package)
+            .into_iter()
+            .flatten()
+        {
+            entries.push(format!(
+                "wildcard audit of versions published by user {} for {}",
+                entry.user_id,
+                criteria(&entry.criteria)
+            ));
+        }
+        for entry in audits_file.trusted.get(package).into_iter().flatten() {
+            entries.push(format!(
+                "trusts versions published by user {} for {}",
+                entry.user_id,
+                criteria(&entry.criteria)
+            ));
+        }
+    }
+    entries
+}
+
+fn describe_audit_kind(kind: &AuditKind) -> String {
+    match kind {
+        AuditKind::Full { version } => format!("full audit of {version}"),
+        AuditKind::Delta { from, to } => format!("delta audit from {from} to {to}"),
+        AuditKind::Violation { violation } => format!("violation of {violation}"),
+    }
+}
+
+fn cmd_search(
+    out: &Arc<dyn Out>,
+    cfg: &Config,
+    sub_args: &SearchArgs,
+) -> Result<(), miette::Report> {
+    let cache = Cache::acquire(cfg)?;
+    do_cmd_search(out, sub_args, &cache);
+    Ok(())
+}
+
+fn do_cmd_search(out: &Arc<dyn Out>, sub_args: &SearchArgs, cache: &Cache) {
+    let package = &sub_args.package;
+    let audits: Vec<_> = cache
+        .search_audit_index(package)
+        .into_iter()
+        .filter(|(_, audit)| match (&audit.kind, &sub_args.version) {
+            (_, None) | (AuditKind::Violation { .. }, _) => true,
+            (AuditKind::Full { version }, Some(req)) => req.matches(version),
+            (AuditKind::Delta { to, .. }, Some(req)) => req.matches(to),
+        })
+        .collect();
+    if audits.is_empty() {
+        writeln!(out, "No audits of {package} found in the audit index");
+        writeln!(
+            out,
+            "The index is updated when imports or the registry are fetched, such as by `cargo vet check`"
+        );
+        return;
+    }
+
+    for (source, audit) in audits {
+        let mut line = format!(
+            "{source}: {package} {} for {}",
+            describe_audit_kind(&audit.kind),
+            audit.criteria.join(", ")
+        );
+        if !audit.who.is_empty() {
+            line.push_str(&format!(" by {}", audit.who.join(", ")));
+        }
+        writeln!(out, "{line}");
+        if let Some(notes) = &audit.notes {
+            for notes_line in notes.lines() {
+                writeln!(out, "    {notes_line}");
+            }
+        }
+    }
+}
+
+/// Sign a newly recorded audit with the store's signing key, if it has one.
+fn sign_audit(
+    cfg: &Config,
+    store: &Store,
+    package: PackageStr<'_>,
+    entry: &mut AuditEntry,
+) -> Result<(), SigningError> {
+    if let Some(signing) = &store.config.signing {
+        let payload = signing::audit_payload(package, entry);
+        let store_path = cfg.metacfg.store_path();
+        entry.signature = Some(tokio::runtime::Handle::current().block_on(signing::sign(
+            signing,
+            store_path.as_path_unlocked(),
+            &payload,
+        ))?);
+    }
+    Ok(())
+}
+
+fn cmd_record_violation(
+    out: &Arc<dyn Out>,
+    cfg: &Config,
+    sub_args: &RecordViolationArgs,
+) -> Result<(), miette::Report> {
+    // Mark a package as a violation
+    let mut store = Store::acquire_offline(cfg)?;
+
+    let kind = AuditKind::Violation {
+        violation: sub_args.versions.clone(),
+    };
+
+    let (_username, who) = if sub_args.who.is_empty() {
+        let user_info = get_user_info()?;
+        let who = format!("{} <{}>", user_info.username, user_info.email);
+        (user_info.username, vec![Spanned::from(who)])
+    } else {
+        (
+            sub_args.who.join(", "),
+            sub_args
+                .who
+                .iter()
+                .map(|w| Spanned::from(w.clone()))
+                .collect(),
+        )
+    };
+
+    let notes = sub_args.notes.clone();
+
+    let criteria = if sub_args.criteria.is_empty() {
+        // TODO: provide an interactive prompt for this
+        vec![store.config.default_criteria.clone().into()]
+    } else {
+        sub_args
+            .criteria
+            .iter()
+            .map(|s| s.to_owned().into())
+            .collect()
+    };
 
     // FIXME: can/should we check if the version makes sense..?
     if !sub_args.force
@@ -1634,15 +2353,17 @@ fn cmd_record_violation(
     }
 
     // Ok! Ready to commit the audit!
-    let new_entry = AuditEntry {
+    let mut new_entry = AuditEntry {
         kind,
         criteria,
         who,
         importable: true,
         notes,
         aggregated_from: vec![],
+        signature: None,
         is_fresh_import: false,
     };
+    sign_audit(cfg, &store, &sub_args.package, &mut new_entry)?;
 
     store
         .audits
@@ -1701,6 +2422,7 @@ fn cmd_add_exemption(
         notes,
         version: sub_args.version.clone(),
         suggest,
+        workspace: None,
     };
 
     store
@@ -1718,7 +2440,7 @@ fn cmd_add_exemption(
 fn cmd_suggest(
     out: &Arc<dyn Out>,
     cfg: &Config,
-    _sub_args: &SuggestArgs,
+    sub_args: &SuggestArgs,
 ) -> Result<(), miette::Report> {
     // Run the checker to validate that the current set of deps is covered by the current cargo vet store
     trace!("suggesting...");
@@ -1727,7 +2449,10 @@ fn cmd_suggest(
 
     // DO THE THING!!!!
     let report = resolver::resolve(&cfg.metadata, cfg.cli.filter_graph.as_ref(), &suggest_store);
-    let suggest = report.compute_suggest(cfg, &suggest_store, network.as_ref())?;
+    let mut suggest = report.compute_suggest(cfg, &suggest_store, network.as_ref())?;
+    if let (true, Some(suggest)) = (sub_args.prefer_audited_versions, suggest.as_mut()) {
+        report.suggest_audited_versions(cfg, &suggest_store, network.as_ref(), suggest)?;
+    }
     match cfg.cli.output_format {
         OutputFormat::Human => report
             .print_suggest_human(out, cfg, suggest.as_ref())
@@ -2035,15 +2760,103 @@ fn cmd_regenerate_exemptions(
     Ok(())
 }
 
+fn cmd_diff_source(
+    out: &Arc<dyn Out>,
+    cfg: &Config,
+    sub_args: &DiffSourceArgs,
+) -> Result<(), miette::Report> {
+    let package = &*sub_args.package;
+    let version = &sub_args.version;
+
+    let network = Network::acquire(cfg);
+    let cache = Cache::acquire(cfg)?;
+    let comparison = tokio::runtime::Handle::current().block_on(async {
+        let published = cache
+            .fetch_package(&cfg.metadata, network.as_ref(), package, version)
+            .await?;
+        cache.compare_with_source(&published, &sub_args.repo).await
+    })?;
+    print_source_comparison(out, package, version, &sub_args.repo, &comparison);
+    Ok(())
+}
+
+fn print_source_comparison(
+    out: &Arc<dyn Out>,
+    package: PackageStr<'_>,
+    version: &VetVersion,
+    repo: &Path,
+    comparison: &SourceComparison,
+) {
+    let path = if comparison.path_in_vcs.is_empty() {
+        String::new()
+    } else {
+        format!(" (in {})", comparison.path_in_vcs)
+    };
+    writeln!(
+        out,
+        "Comparing {package}:{version} with commit {}{path} of {}",
+        comparison.commit,
+        repo.display()
+    );
+    if comparison.dirty {
+        writeln!(
+            out,
+            "NOTE: {package}:{version} was published from a working tree with uncommitted changes"
+        );
+    }
+
+    if comparison.matches() {
+        writeln!(out, "Every published file matches the repository");
+        return;
+    }
+    if !comparison.added.is_empty() {
+        writeln!(out, "\nPublished files which aren't in the repository:");
+        for path in &comparison.added {
+            writeln!(out, "  {}", path.display());
+        }
+    }
+    if !comparison.modified.is_empty() {
+        writeln!(out, "\nPublished files which differ from the repository:");
+        for path in &comparison.modified {
+            writeln!(out, "  {}", path.display());
+        }
+    }
+}
+
+/// List the changed files which were left out of a diff as they aren't shipped,
+/// so that they aren't missed entirely.
+fn print_unshipped_changes(out: &Arc<dyn Out>, unshipped: &[(PathBuf, DiffStat)]) {
+    if unshipped.is_empty() {
+        return;
+    }
+    writeln!(
+        out,
+        "Skipped {} changed files which aren't shipped:",
+        unshipped.len()
+    );
+    for (path, diffstat) in unshipped {
+        writeln!(
+            out,
+            "  {} (+{} -{})",
+            path.display(),
+            diffstat.insertions,
+            diffstat.deletions
+        );
+    }
+}
+
 fn cmd_diff(out: &Arc<dyn Out>, cfg: &Config, sub_args: &DiffArgs) -> Result<(), miette::Report> {
     let version1 = &sub_args.version1;
     let version2 = &sub_args.version2;
     let package = &*sub_args.package;
 
-    let to_compare = {
+    let (to_compare, unshipped, scope) = {
         let network = Network::acquire(cfg);
         let store = Store::acquire(cfg, network.as_ref(), false)?;
         let cache = Cache::acquire(cfg)?;
+        let scope = sub_args
+            .scope
+            .unwrap_or_else(|| store.config.diff_scope(package));
 
         // Record this command for magic in `vet certify`
         cache.set_last_fetch(FetchCommand::Diff {
@@ -2081,7 +2894,7 @@ fn cmd_diff(out: &Arc<dyn Out>, cfg: &Config, sub_args: &DiffArgs) -> Result<(),
             return Ok(());
         }
 
-        tokio::runtime::Handle::current().block_on(async {
+        let (to_compare, unshipped) = tokio::runtime::Handle::current().block_on(async {
             // NOTE: don't `try_join` everything as we don't want to abort the
             // prompt to the user if the download fails while it is being shown, as
             // that could be disorienting.
@@ -2091,14 +2904,15 @@ fn cmd_diff(out: &Arc<dyn Out>, cfg: &Config, sub_args: &DiffArgs) -> Result<(),
                         cache.fetch_package(&cfg.metadata, network.as_ref(), package, version1),
                         cache.fetch_package(&cfg.metadata, network.as_ref(), package, version2)
                     )?;
-                    let (_, to_compare) = cache
+                    let (_, to_compare, unshipped) = cache
                         .diffstat_package(
                             &pkg1,
                             &pkg2,
                             version1.git_rev.is_some() || version2.git_rev.is_some(),
+                            scope,
                         )
                         .await?;
-                    Ok::<_, FetchAndDiffError>(to_compare)
+                    Ok::<_, FetchAndDiffError>((to_compare, unshipped))
                 },
                 prompt_criteria_eulas(
                     out,
@@ -2113,10 +2927,18 @@ fn cmd_diff(out: &Arc<dyn Out>, cfg: &Config, sub_args: &DiffArgs) -> Result<(),
             );
             eulas.into_diagnostic()?;
             to_compare.into_diagnostic()
-        })?
+        })?;
+        (to_compare, unshipped, scope)
     };
 
     writeln!(out);
+    if scope == DiffScope::Shipped {
+        writeln!(
+            out,
+            "Only showing files shipped in {package}, use --scope=all to show every file"
+        );
+        print_unshipped_changes(out, &unshipped);
+    }
 
     // Start a pager to show the output from our diff invocations. This will
     // fall back to just printing to `stdout` if no pager is available or we're
@@ -2151,11 +2973,99 @@ fn cmd_diff(out: &Arc<dyn Out>, cfg: &Config, sub_args: &DiffArgs) -> Result<(),
     Ok(())
 }
 
-fn cmd_check(
+fn cmd_explain(
     out: &Arc<dyn Out>,
     cfg: &Config,
-    _sub_args: &CheckArgs,
+    sub_args: &ExplainArgs,
 ) -> Result<(), miette::Report> {
+    // Explain how a single package was vetted
+    trace!("explaining...");
+
+    let network = Network::acquire(cfg);
+    let store = Store::acquire(cfg, network.as_ref(), false)?;
+
+    let report = resolver::resolve(&cfg.metadata, cfg.cli.filter_graph.as_ref(), &store);
+    let explain = report.explain(&store, &sub_args.package, &sub_args.version)?;
+
+    match cfg.cli.output_format {
+        OutputFormat::Human => resolver::print_explain_human(out, &explain).into_diagnostic()?,
+        OutputFormat::Json => serde_json::to_writer_pretty(&**out, &explain).into_diagnostic()?,
+    }
+
+    Ok(())
+}
+
+fn cmd_why(out: &Arc<dyn Out>, cfg: &Config, sub_args: &WhyArgs) -> Result<(), miette::Report> {
+    // Explain how criteria requirements reached a package
+    trace!("explaining requirements...");
+
+    // Only the policy is needed, so don't bother fetching imports
+    let store = Store::acquire_offline(cfg)?;
+
+    let why = resolver::why(
+        &cfg.metadata,
+        cfg.cli.filter_graph.as_ref(),
+        &store,
+        &sub_args.package,
+        sub_args.max_paths,
+    )?;
+
+    match cfg.cli.output_format {
+        OutputFormat::Human => resolver::print_why_human(out, &why).into_diagnostic()?,
+        OutputFormat::Json => serde_json::to_writer_pretty(&**out, &why).into_diagnostic()?,
+    }
+
+    Ok(())
+}
+
+fn cmd_what_if_add(
+    out: &Arc<dyn Out>,
+    cfg: &Config,
+    sub_args: &WhatIfAddArgs,
+) -> Result<(), miette::Report> {
+    let change = resolver::WhatIfChange::Add {
+        package: &sub_args.package.package,
+        version: &sub_args.package.version,
+        dependent: sub_args.to.as_deref(),
+    };
+    what_if(out, cfg, &change)
+}
+
+fn cmd_what_if_upgrade(
+    out: &Arc<dyn Out>,
+    cfg: &Config,
+    sub_args: &WhatIfUpgradeArgs,
+) -> Result<(), miette::Report> {
+    let change = resolver::WhatIfChange::Upgrade {
+        package: &sub_args.package,
+        from: sub_args.from.as_ref(),
+        version: &sub_args.version,
+    };
+    what_if(out, cfg, &change)
+}
+
+fn what_if(
+    out: &Arc<dyn Out>,
+    cfg: &Config,
+    change: &resolver::WhatIfChange<'_>,
+) -> Result<(), miette::Report> {
+    // Estimate the audit cost of a hypothetical change to the graph
+    trace!("estimating {}...", change);
+
+    let network = Network::acquire(cfg);
+    let store = Store::acquire(cfg, network.as_ref(), false)?;
+
+    let what_if = resolver::what_if(cfg, &store, network.as_ref(), change)?;
+
+    match cfg.cli.output_format {
+        OutputFormat::Human => resolver::print_what_if_human(out, &what_if).into_diagnostic()?,
+        OutputFormat::Json => serde_json::to_writer_pretty(&**out, &what_if).into_diagnostic()?,
+    }
+
+    Ok(())
+}
+
+fn cmd_check(out: &Arc<dyn Out>, cfg: &Config, sub_args: &CheckArgs) -> Result<(), miette::Report> {
     // Run the checker to validate that the current set of deps is covered by the current cargo vet store
     trace!("vetting...");
 
@@ -2176,6 +3086,11 @@ fn cmd_check(
         ))?;
     }
 
+    let profiles = selected_profiles(cfg, &store, sub_args)?;
+    if !profiles.is_empty() {
+        return check_profiles(out, cfg, store, network.as_ref(), &profiles);
+    }
+
     // DO THE THING!!!!
     let report = resolver::resolve(&cfg.metadata, cfg.cli.filter_graph.as_ref(), &store);
 
@@ -2271,6 +3186,471 @@ fn cmd_check(
     Ok(())
 }
 
+/// Determine which feature profiles from config.toml `check` should vet. This
+/// is every profile unless `--profile` was passed, and none if feature flags
+/// or a metadata file were passed on the command line instead.
+fn selected_profiles(
+    cfg: &Config,
+    store: &Store,
+    sub_args: &CheckArgs,
+) -> Result<Vec<(String, FeatureProfile)>, ProfileError> {
+    let cli_features = !cfg.cli.features.is_empty()
+        || cfg.cli.no_default_features
+        || cfg.cli.no_all_features
+        || cfg.cli.metadata_file.is_some();
+    if sub_args.profile.is_empty() {
+        if cli_features && !store.config.profiles.is_empty() {
+            info!(
+                "the dependency graph was specified on the command line, ignoring feature profiles"
+            );
+            return Ok(Vec::new());
+        }
+        return Ok(store
+            .config
+            .profiles
+            .iter()
+            .map(|(name, profile)| (name.clone(), profile.clone()))
+            .collect());
+    }
+    if cli_features {
+        return Err(ProfileError::ConflictingFeatures);
+    }
+    sub_args
+        .profile
+        .iter()
+        .map(|name| {
+            store
+                .config
+                .profiles
+                .get(name)
+                .map(|profile| (name.clone(), profile.clone()))
+                .ok_or_else(|| ProfileError::UnknownProfile {
+                    name: name.clone(),
+                    available: if store.config.profiles.is_empty() {
+                        "(none)".to_owned()
+                    } else {
+                        store
+                            .config
+                            .profiles
+                            .keys()
+                            .cloned()
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    },
+                })
+        })
+        .collect()
+}
+
+/// Vet each of the given feature profiles against its own `cargo metadata`,
+/// only failing for packages which are unvetted in a profile they're active in.
+fn check_profiles(
+    out: &Arc<dyn Out>,
+    cfg: &Config,
+    mut store: Store,
+    network: Option<&Network>,
+    profiles: &[(String, FeatureProfile)],
+) -> Result<(), miette::Report> {
+    let mut metadatas = Vec::with_capacity(profiles.len());
+    for (name, profile) in profiles {
+        let metadata = profile_metadata(cfg, profile).map_err(|error| ProfileError::Metadata {
+            name: name.to_owned(),
+            error,
+        })?;
+        let profile_targets = profile
+            .targets
+            .iter()
+            .map(|triple| resolver::TargetPlatform::query(triple))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| ProfileError::TargetPlatform {
+                name: name.to_owned(),
+                error,
+            })?;
+        // Targets passed on the command line take precedence over both the
+        // profile's and the store's targets when vetting, but the store is
+        // always updated for every target the profile is vetted for.
+        let targets = if !cfg.cli.target.is_empty() || profile_targets.is_empty() {
+            store.target_platforms.clone()
+        } else {
+            profile_targets.clone()
+        };
+        let store_targets = if profile_targets.is_empty() {
+            store.store_target_platforms.clone()
+        } else {
+            profile_targets
+        };
+        metadatas.push((&name[..], metadata, targets, store_targets));
+    }
+
+    let mut reports = Vec::with_capacity(profiles.len());
+    for (name, metadata, targets, _) in &metadatas {
+        let report =
+            resolver::resolve_for_targets(metadata, cfg.cli.filter_graph.as_ref(), &store, targets);
+        // Bare `cargo vet` shouldn't suggest in CI
+        let suggest = if !cfg.cli.locked {
+            report.compute_suggest(cfg, &store, network)?
+        } else {
+            None
+        };
+        reports.push(resolver::ProfileReport {
+            name,
+            report,
+            suggest,
+        });
+    }
+
+    match cfg.cli.output_format {
+        OutputFormat::Human => {
+            resolver::print_profiles_human(out, cfg, &reports).into_diagnostic()?
+        }
+        OutputFormat::Json => resolver::print_profiles_json(out, &reports)?,
+    }
+
+    if reports.iter().any(|profile| profile.report.has_errors()) {
+        panic_any(ExitPanic(-1));
+    }
+
+    if !cfg.cli.locked {
+        // Pull in any imports which are needed by any of the profiles,
+        // without otherwise touching exemptions or imports.
+        let graphs: Vec<_> = metadatas
+            .iter()
+            .map(|(_, metadata, _, store_targets)| (metadata, &store_targets[..]))
+            .collect();
+        resolver::update_store_for_graphs(cfg, &mut store, &graphs, |_| resolver::UpdateMode {
+            search_mode: resolver::SearchMode::PreferExemptions,
+            prune_exemptions: false,
+            prune_non_importable_audits: false,
+            prune_imports: false,
+        });
+    }
+    store.commit()?;
+
+    Ok(())
+}
+
+/// Build the `cargo metadata` invocation shared by every run, without any
+/// feature flags.
+fn metadata_command(cli: &Cli, color: bool) -> cargo_metadata::MetadataCommand {
+    let cargo_path = std::env::var_os(CARGO_ENV).expect("Cargo failed to set $CARGO, how?");
+
+    let mut cmd = cargo_metadata::MetadataCommand::new();
+    cmd.cargo_path(cargo_path);
+    if let Some(manifest_path) = &cli.manifest_path {
+        cmd.manifest_path(manifest_path);
+    }
+    // We never want cargo-vet to update the Cargo.lock.
+    // For frozen runs we also don't want to touch the network.
+    let mut other_options = Vec::new();
+    if cli.frozen {
+        other_options.push("--frozen".to_string());
+    } else {
+        other_options.push("--locked".to_string());
+    }
+    if color {
+        other_options.push("--color=always".to_string());
+    }
+    other_options.extend(cli.cargo_arg.iter().cloned());
+    cmd.other_options(other_options);
+    cmd
+}
+
+/// Load the output of `cargo metadata` from the given file, or from stdin if
+/// the path is `-`.
+fn load_metadata_file(path: &Path) -> Result<Metadata, MetadataAcquireError> {
+    let json = if path == Path::new("-") {
+        let mut json = String::new();
+        io::Read::read_to_string(&mut io::stdin(), &mut json).map(|_| json)
+    } else {
+        std::fs::read_to_string(path)
+    }
+    .map_err(|error| MetadataAcquireError::ReadFile {
+        path: path.to_owned(),
+        error,
+    })?;
+    let metadata = parse_metadata(&json)?;
+    let current_dir = std::env::current_dir().map_err(MetadataAcquireError::CurrentDir)?;
+    check_metadata_workspace(&metadata, &current_dir)?;
+    Ok(metadata)
+}
+
+/// Check that metadata read from a file describes the workspace containing
+/// `current_dir`, as the store is found relative to its `workspace_root`.
+fn check_metadata_workspace(
+    metadata: &Metadata,
+    current_dir: &Path,
+) -> Result<(), MetadataAcquireError> {
+    let workspace_root = metadata.workspace_root.as_std_path();
+    let mismatch = || MetadataAcquireError::WorkspaceMismatch {
+        workspace_root: workspace_root.to_owned(),
+        current_dir: current_dir.to_owned(),
+    };
+    let (Ok(workspace_root), Ok(current_dir)) =
+        (workspace_root.canonicalize(), current_dir.canonicalize())
+    else {
+        return Err(mismatch());
+    };
+    if !workspace_root.join("Cargo.toml").is_file() || !current_dir.starts_with(&workspace_root) {
+        return Err(mismatch());
+    }
+    Ok(())
+}
+
+/// Parse and validate JSON produced by `cargo metadata --format-version 1`.
+///
+/// As the metadata may have been produced by another build system, check that
+/// it contains everything the resolver expects rather than panicking later.
+fn parse_metadata(json: &str) -> Result<Metadata, MetadataAcquireError> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(MetadataAcquireError::InvalidJson)?;
+    let version = value.get("version").cloned().unwrap_or_default();
+    if version != 1 {
+        return Err(MetadataAcquireError::UnsupportedVersion(version));
+    }
+    let metadata: Metadata =
+        serde_json::from_value(value).map_err(MetadataAcquireError::InvalidJson)?;
+
+    let package_ids: FastSet<_> = metadata.packages.iter().map(|p| &p.id).collect();
+    let unknown_package = |id: &cargo_metadata::PackageId| {
+        if package_ids.contains(id) {
+            Ok(())
+        } else {
+            Err(MetadataAcquireError::UnknownPackage(id.repr.clone()))
+        }
+    };
+
+    if metadata.workspace_members.is_empty() {
+        return Err(MetadataAcquireError::NoWorkspaceMembers);
+    }
+    for member in &metadata.workspace_members {
+        unknown_package(member)?;
+    }
+    let resolve = metadata
+        .resolve
+        .as_ref()
+        .ok_or(MetadataAcquireError::MissingResolve)?;
+    for node in &resolve.nodes {
+        unknown_package(&node.id)?;
+        for dep in &node.deps {
+            unknown_package(&dep.pkg)?;
+        }
+    }
+    let resolved: FastSet<_> = resolve.nodes.iter().map(|node| &node.id).collect();
+    if let Some(package) = metadata.packages.iter().find(|p| !resolved.contains(&p.id)) {
+        return Err(MetadataAcquireError::UnresolvedPackage(
+            package.id.repr.clone(),
+        ));
+    }
+
+    // Check that the file has the maximal build graph which we'd get from
+    // running `cargo metadata` ourselves. With every feature of the workspace
+    // members enabled and no platform filter, every dependency they declare
+    // is resolved.
+    let package_by_id: FastMap<_, _> = metadata.packages.iter().map(|p| (&p.id, p)).collect();
+    for node in &resolve.nodes {
+        if !metadata.workspace_members.contains(&node.id) {
+            continue;
+        }
+        let package = package_by_id[&node.id];
+        if package
+            .features
+            .keys()
+            .any(|feature| !node.features.contains(feature))
+        {
+            return Err(MetadataAcquireError::NotAllFeatures(package.name.clone()));
+        }
+        for dep in &package.dependencies {
+            if node
+                .deps
+                .iter()
+                .any(|resolved| package_by_id[&resolved.pkg].name == dep.name)
+            {
+                continue;
+            }
+            return Err(if dep.optional {
+                MetadataAcquireError::NotAllFeatures(package.name.clone())
+            } else {
+                MetadataAcquireError::MissingDependency {
+                    package: package.name.clone(),
+                    dependency: dep.name.clone(),
+                }
+            });
+        }
+    }
+
+    Ok(metadata)
+}
+
+/// Pass the feature flags from the command line through to `cargo metadata`.
+fn apply_cli_features(cmd: &mut cargo_metadata::MetadataCommand, cli: &Cli) {
+    if !cli.no_all_features {
+        cmd.features(cargo_metadata::CargoOpt::AllFeatures);
+    }
+    if cli.no_default_features {
+        cmd.features(cargo_metadata::CargoOpt::NoDefaultFeatures);
+    }
+    if !cli.features.is_empty() {
+        cmd.features(cargo_metadata::CargoOpt::SomeFeatures(cli.features.clone()));
+    }
+}
+
+/// Run `cargo metadata` with the features of the given profile. If the store
+/// governs several workspaces, the graphs of all of them are merged.
+fn profile_metadata(
+    cfg: &Config,
+    profile: &FeatureProfile,
+) -> Result<Metadata, MetadataAcquireError> {
+    let run = |manifest: Option<&Path>| -> Result<Metadata, MetadataAcquireError> {
+        let mut cmd = metadata_command(&cfg.cli, false);
+        if let Some(manifest) = manifest {
+            cmd.manifest_path(manifest);
+        }
+        if profile.all_features {
+            cmd.features(cargo_metadata::CargoOpt::AllFeatures);
+        }
+        if !profile.default_features {
+            cmd.features(cargo_metadata::CargoOpt::NoDefaultFeatures);
+        }
+        if !profile.features.is_empty() {
+            cmd.features(cargo_metadata::CargoOpt::SomeFeatures(
+                profile.features.clone(),
+            ));
+        }
+
+        info!("Running: {:#?}", cmd.cargo_command());
+
+        let _spinner = indeterminate_spinner("Running", "`cargo metadata`");
+        Ok(cmd.exec()?)
+    };
+
+    let mut metadata = run(None)?;
+    for workspace in cfg.workspaces.values() {
+        if workspace.packages.contains(&metadata.workspace_members[0]) {
+            // This is the current workspace, which we've already run.
+            continue;
+        }
+        let other = run(Some(&workspace.manifest))?;
+        merge_metadata(&mut metadata, other);
+    }
+    Ok(metadata)
+}
+
+/// If the store governs several workspaces, run `cargo metadata` for each of
+/// the other workspaces and merge their dependency graphs into `metadata`.
+fn load_workspaces(
+    cli: &Cli,
+    color: bool,
+    metacfg: &MetaConfig,
+    mut metadata: Metadata,
+) -> Result<(Metadata, SortedMap<WorkspaceName, Workspace>), WorkspaceError> {
+    let manifests = Store::read_workspaces(metacfg)?;
+    if manifests.is_empty() {
+        return Ok((metadata, SortedMap::new()));
+    }
+    if cli.metadata_file.is_some() {
+        return Err(WorkspaceError::MetadataFile);
+    }
+
+    let current_root = metadata.workspace_root.as_std_path().canonicalize().ok();
+    let mut found_current = false;
+    let mut workspaces = SortedMap::new();
+    for (name, manifest) in manifests {
+        let root = manifest.parent().and_then(|dir| dir.canonicalize().ok());
+        let packages = if current_root.is_some() && root == current_root {
+            found_current = true;
+            metadata.packages.iter().map(|p| p.id.clone()).collect()
+        } else {
+            let mut cmd = metadata_command(cli, color);
+            cmd.manifest_path(&manifest);
+            apply_cli_features(&mut cmd, cli);
+
+            info!("Running: {:#?}", cmd.cargo_command());
+
+            let other = {
+                let _spinner =
+                    indeterminate_spinner("Running", format!("`cargo metadata` for {name}"));
+                cmd.exec().map_err(|error| WorkspaceError::Metadata {
+                    name: name.clone(),
+                    error: error.into(),
+                })?
+            };
+            let packages = other.packages.iter().map(|p| p.id.clone()).collect();
+            merge_metadata(&mut metadata, other);
+            packages
+        };
+        workspaces.insert(name, Workspace { manifest, packages });
+    }
+
+    if !found_current {
+        return Err(WorkspaceError::NotGoverned {
+            root: metadata.workspace_root.to_string(),
+        });
+    }
+    Ok((metadata, workspaces))
+}
+
+/// Merge the dependency graph of another workspace into `metadata`, so that
+/// the workspaces are vetted together. Packages which appear in both graphs
+/// keep the union of their dependencies.
+fn merge_metadata(metadata: &mut Metadata, other: Metadata) {
+    let known: FastSet<PackageId> = metadata.packages.iter().map(|p| p.id.clone()).collect();
+    metadata.packages.extend(
+        other
+            .packages
+            .into_iter()
+            .filter(|package| !known.contains(&package.id)),
+    );
+    for member in other.workspace_members {
+        if !metadata.workspace_members.contains(&member) {
+            metadata.workspace_members.push(member);
+        }
+    }
+
+    let (Some(resolve), Some(other_resolve)) = (&mut metadata.resolve, other.resolve) else {
+        return;
+    };
+    // The merged graph doesn't have a single root crate.
+    resolve.root = None;
+    let mut node_index: FastMap<PackageId, usize> = resolve
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(idx, node)| (node.id.clone(), idx))
+        .collect();
+    for node in other_resolve.nodes {
+        let Some(&idx) = node_index.get(&node.id) else {
+            node_index.insert(node.id.clone(), resolve.nodes.len());
+            resolve.nodes.push(node);
+            continue;
+        };
+        let existing = &mut resolve.nodes[idx];
+        for dep in node.deps {
+            let Some(existing_dep) = existing.deps.iter_mut().find(|d| d.pkg == dep.pkg) else {
+                existing.deps.push(dep);
+                continue;
+            };
+            for dep_kind in dep.dep_kinds {
+                let target = dep_kind.target.as_ref().map(|t| t.to_string());
+                if !existing_dep.dep_kinds.iter().any(|k| {
+                    k.kind == dep_kind.kind && k.target.as_ref().map(|t| t.to_string()) == target
+                }) {
+                    existing_dep.dep_kinds.push(dep_kind);
+                }
+            }
+        }
+        for dependency in node.dependencies {
+            if !existing.dependencies.contains(&dependency) {
+                existing.dependencies.push(dependency);
+            }
+        }
+        for feature in node.features {
+            if !existing.features.contains(&feature) {
+                existing.features.push(feature);
+            }
+        }
+    }
+}
+
 #[derive(Default)]
 struct WildcardAuditRenewal<'a> {
     // the bool indicates whether the entry for that user id is already expired (true) or will
@@ -2564,7 +3944,14 @@ fn cmd_dump_graph(
     // Dump a mermaid-js graph
     trace!("dumping...");
 
-    let graph = resolver::DepGraph::new(&cfg.metadata, cfg.cli.filter_graph.as_ref(), None);
+    let targets = cfg
+        .cli
+        .target
+        .iter()
+        .map(|triple| resolver::TargetPlatform::query(triple))
+        .collect::<Result<Vec<_>, _>>()?;
+    let graph =
+        resolver::DepGraph::new(&cfg.metadata, cfg.cli.filter_graph.as_ref(), None, &targets);
     match cfg.cli.output_format {
         OutputFormat::Human => graph.print_mermaid(out, sub_args).into_diagnostic()?,
         OutputFormat::Json => {
@@ -2714,6 +4101,49 @@ fn cmd_help_md(
     Ok(())
 }
 
+fn cmd_ingest_db_dump(
+    out: &Arc<dyn Out>,
+    cfg: &Config,
+    sub_args: &IngestDbDumpArgs,
+) -> Result<(), miette::Report> {
+    let cache = Cache::acquire(cfg)?;
+    do_cmd_ingest_db_dump(out, cfg, sub_args, &cache)
+}
+
+fn do_cmd_ingest_db_dump(
+    out: &Arc<dyn Out>,
+    cfg: &Config,
+    sub_args: &IngestDbDumpArgs,
+    cache: &Cache,
+) -> Result<(), miette::Report> {
+    let packages: Option<FastSet<PackageName>> = (!sub_args.all).then(|| {
+        cfg.metadata
+            .packages
+            .iter()
+            .map(|package| package.name.clone())
+            .collect()
+    });
+
+    let dump = {
+        let _spinner = indeterminate_spinner("Reading", "crates.io database dump");
+        db_dump::read(&sub_args.path, packages.as_ref(), cfg.now)?
+    };
+    let (crates, versions) = cache.ingest_db_dump(dump.crates);
+
+    match dump.timestamp {
+        Some(timestamp) => writeln!(
+            out,
+            "Loaded {crates} crates ({versions} versions) from the crates.io database dump of {}",
+            timestamp.format("%Y-%m-%d")
+        ),
+        None => writeln!(
+            out,
+            "Loaded {crates} crates ({versions} versions) from the crates.io database dump"
+        ),
+    }
+    Ok(())
+}
+
 fn cmd_gc(
     out: &Arc<dyn Out>,
     cfg: &PartialConfig,
@@ -10,6 +10,9 @@
 //! * [`resolve`] is the main entry point, Validating and Searching and producing a [`ResolveReport`]
 //! * [`ResolveReport::compute_suggest`] does Suggesting and produces a [`Suggest`]
 //! * various methods on [`ResolveReport`] and [`Suggest`] handle printing
+//! * [`ResolveReport::explain`] describes the audits which vetted a single package
+//! * [`why`] describes how criteria requirements propagated to a single package
+//! * [`what_if`] estimates the audit cost of a hypothetical change to the dependency graph
 //! * [`update_store`] handles automatically minimizing and generating exemptions and imports
 //!
 //! # Low-level Design
@@ -40,30 +43,39 @@
 //!   existing set of criteria, to suggest the best audit and criteria which could
 //!   be used to allow the crate to vet successfully.
 
-use cargo_metadata::{DependencyKind, Metadata, Node, PackageId};
+use cargo_metadata::{semver, DepKindInfo, DependencyKind, Metadata, Node, NodeDep, PackageId};
+use cargo_platform::{Cfg, Platform};
 use futures_util::future::join_all;
 use miette::IntoDiagnostic;
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
+use std::fmt;
 use std::sync::Arc;
 use tracing::{trace, trace_span, warn};
 
 use crate::cli::{DumpGraphArgs, GraphFilter, GraphFilterProperty, GraphFilterQuery, OutputFormat};
 use crate::criteria::{CriteriaMapper, CriteriaSet};
-use crate::errors::SuggestError;
+use crate::errors::{
+    CommandError, ExplainError, IndexResolveError, SuggestError, TargetPlatformError, WhatIfError,
+    WhyError,
+};
 use crate::format::{
     self, AuditEntry, AuditKind, AuditsFile, CratesCacheUser, CratesPublisher, CriteriaName, Delta,
-    DiffStat, ExemptedDependency, FastMap, FastSet, ImportName, ImportsFile, JsonPackage,
-    JsonReport, JsonReportConclusion, JsonReportFailForVet, JsonReportFailForViolationConflict,
-    JsonReportSuccess, JsonSuggest, JsonSuggestItem, JsonVetFailure, PackageName, PackageStr,
-    Policy, UnpublishedEntry, VetVersion, WildcardEntry,
+    DiffScope, DiffStat, ExemptedDependency, FastMap, FastSet, ImportName, ImportsFile,
+    JsonAuditedVersion, JsonExplain, JsonExplainCriteria, JsonExplainEdge, JsonExplainEdgeKind,
+    JsonPackage, JsonProfilesConclusion, JsonProfilesReport, JsonReport, JsonReportConclusion,
+    JsonReportFailForVet, JsonReportFailForViolationConflict, JsonReportSuccess, JsonSuggest,
+    JsonSuggestItem, JsonVetFailure, JsonWhatIf, JsonWhatIfPackage, JsonWhy, JsonWhyDependencyKind,
+    JsonWhyHop, JsonWhyPackage, JsonWhyReason, PackageName, PackagePolicyEntry, PackageStr, Policy,
+    UnpublishedEntry, VetVersion, ViolationConflictPolicy, WildcardEntry,
 };
 use crate::format::{SortedMap, SortedSet};
 use crate::network::Network;
 use crate::out::{progress_bar, IncProgressOnDrop, Out};
-use crate::storage::Cache;
+use crate::serialization::spanned::Spanned;
+use crate::storage::{exact_version, Cache};
 use crate::string_format::FormatShortList;
 use crate::{Config, PackageExt, Store};
 
@@ -76,6 +88,10 @@ pub struct ResolveReport<'a> {
     /// Mappings between criteria names and CriteriaSets/Indices.
     pub criteria_mapper: CriteriaMapper,
 
+    /// The criteria each package is required to satisfy, indexed by
+    /// [`PackageIdx`][].
+    pub requirements: Vec<CriteriaSet>,
+
     /// Low-level results for each package's individual criteria resolving
     /// analysis, indexed by [`PackageIdx`][]. Will be `None` for first-party
     /// crates or crates with violation conflicts.
@@ -83,6 +99,10 @@ pub struct ResolveReport<'a> {
 
     /// The final conclusion of our analysis.
     pub conclusion: Conclusion,
+
+    /// Violation conflicts which were resolved by the `violation-conflicts`
+    /// policy rather than failing, for each package.
+    pub resolved_conflicts: Vec<(PackageIdx, Vec<ResolvedViolationConflict>)>,
 }
 
 #[derive(Debug)]
@@ -132,14 +152,48 @@ pub enum ViolationConflict {
     },
 }
 
+/// A violation conflict which was resolved by the `violation-conflicts` policy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedViolationConflict {
+    pub conflict: ViolationConflict,
+    pub resolution: ViolationConflictResolution,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ViolationConflictResolution {
+    /// The violation was ignored, and the audit or exemption was used.
+    IgnoredViolation,
+    /// The audit or exemption was ignored.
+    IgnoredAudit,
+}
+
 #[derive(Debug, Default)]
 pub struct Suggest {
     pub suggestions: Vec<SuggestItem>,
     pub suggestions_by_criteria: SortedMap<CriteriaName, Vec<SuggestItem>>,
+    pub audited_versions: Vec<AuditedVersion>,
     pub total_lines: u64,
     pub warnings: Vec<String>,
 }
 
+/// A published version of a failing package which is already audited for
+/// everything the package requires, which satisfies every semver requirement
+/// on the package in the dependency graph, and whose dependencies are all
+/// already in the graph.
+#[derive(Debug, Clone)]
+pub struct AuditedVersion {
+    pub package: PackageIdx,
+    pub version: semver::Version,
+}
+
+/// The results of vetting a single feature profile from config.toml.
+pub struct ProfileReport<'a> {
+    pub name: &'a str,
+    pub report: ResolveReport<'a>,
+    pub suggest: Option<Suggest>,
+}
+
 #[derive(Debug, Clone)]
 pub struct TrustHint {
     trusted_by: Vec<String>,
@@ -157,6 +211,7 @@ pub struct SuggestItem {
     pub trust_hint: Option<TrustHint>,
     pub is_sole_publisher: bool,
     pub registry_suggestion: Vec<RegistrySuggestion>,
+    pub index_hint: Vec<IndexHint>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
@@ -173,6 +228,14 @@ pub struct RegistrySuggestion {
     pub diff: DiffRecommendation,
 }
 
+/// A peer which isn't imported, but which the audit index says has fully
+/// audited the suggested version for the failing criteria.
+#[derive(Debug, Clone)]
+pub struct IndexHint {
+    pub name: ImportName,
+    pub version: VetVersion,
+}
+
 /// An "interned" cargo PackageId which is used to uniquely identify packages throughout
 /// the code. This is simpler and faster than actually using PackageIds (strings) or name+version.
 /// In the current implementation it can be used to directly index into the `graph` or `results`.
@@ -212,6 +275,75 @@ pub struct PackageNode<'a> {
     pub is_root: bool,
     /// Whether this package only shows up in dev (test/bench) builds
     pub is_dev_only: bool,
+    /// Whether this package is a proc-macro, which only runs at build time
+    #[serde(skip)]
+    pub is_proc_macro: bool,
+    /// Whether this package is only used at build time, by build scripts or
+    /// proc-macros, and never linked into a root crate
+    #[serde(skip)]
+    pub is_build_only: bool,
+    /// The target platforms this package is built for, if vetting was
+    /// restricted to specific targets (empty otherwise)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<String>,
+}
+
+/// A target platform which dependencies are being vetted for, along with the
+/// `cfg` values which are set when building for it.
+#[derive(Debug, Clone)]
+pub struct TargetPlatform {
+    /// The target triple (e.g. `x86_64-unknown-linux-gnu`)
+    pub triple: String,
+    /// The `cfg` values which are set for this target
+    pub cfg: Vec<Cfg>,
+}
+
+impl TargetPlatform {
+    /// Query `rustc` for the `cfg` values which are set for the given target
+    /// triple.
+    pub fn query(triple: &str) -> Result<Self, TargetPlatformError> {
+        let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+        let out = std::process::Command::new(rustc)
+            .arg("--print")
+            .arg("cfg")
+            .arg("--target")
+            .arg(triple)
+            .output()
+            .map_err(|error| TargetPlatformError::Command {
+                target: triple.to_owned(),
+                error: CommandError::CommandFailed(error),
+            })?;
+        if !out.status.success() {
+            return Err(TargetPlatformError::Command {
+                target: triple.to_owned(),
+                error: CommandError::BadStatus(out.status.code().unwrap_or(-1)),
+            });
+        }
+        let stdout =
+            String::from_utf8(out.stdout).map_err(|error| TargetPlatformError::Command {
+                target: triple.to_owned(),
+                error: CommandError::BadOutput(error),
+            })?;
+
+        let cfg = stdout
+            .lines()
+            .map(|line| line.parse::<Cfg>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| TargetPlatformError::InvalidCfg {
+                target: triple.to_owned(),
+                error,
+            })?;
+        Ok(TargetPlatform {
+            triple: triple.to_owned(),
+            cfg,
+        })
+    }
+
+    /// Whether a dependency with the given platform restriction is enabled
+    /// when building for this target.
+    pub fn matches(&self, platform: &Platform) -> bool {
+        platform.matches(&self.triple, &self.cfg)
+    }
 }
 
 /// The dependency graph in a form we can use more easily.
@@ -274,6 +406,7 @@ type DirectedAuditGraph<'a> = SortedMap<Option<&'a VetVersion>, Vec<DeltaEdge<'a
 pub struct AuditGraph<'a> {
     forward_audits: DirectedAuditGraph<'a>,
     backward_audits: DirectedAuditGraph<'a>,
+    resolved_conflicts: Vec<ResolvedViolationConflict>,
 }
 
 /// The precise origin of an edge in the audit graph.
@@ -401,6 +534,7 @@ impl<'a> DepGraph<'a> {
         metadata: &'a Metadata,
         filter_graph: Option<&Vec<GraphFilter>>,
         policy: Option<&Policy>,
+        targets: &[TargetPlatform],
     ) -> Self {
         let default_policy = Policy::default();
         let policy = policy.unwrap_or(&default_policy);
@@ -444,6 +578,12 @@ impl<'a> DepGraph<'a> {
                 is_workspace_member: false,
                 is_root: false,
                 is_dev_only: true,
+                is_proc_macro: package
+                    .targets
+                    .iter()
+                    .any(|target| target.kind.iter().any(|kind| kind == "proc-macro")),
+                is_build_only: false,
+                targets: vec![],
             });
         }
 
@@ -483,6 +623,7 @@ impl<'a> DepGraph<'a> {
                     &interner_by_pkgid,
                     &resolve_index_by_pkgid,
                     resolve_list,
+                    targets,
                     node_idx,
                 );
             }
@@ -498,6 +639,29 @@ impl<'a> DepGraph<'a> {
                 node.is_root = node.reverse_deps.is_empty();
             }
 
+            // Anything in the normal build graph which can't be reached from a
+            // workspace member through normal non-proc-macro deps is only used
+            // at build time.
+            let mut linked = FastSet::new();
+            let mut queue: Vec<_> = metadata
+                .workspace_members
+                .iter()
+                .map(|pkgid| interner_by_pkgid[pkgid])
+                .collect();
+            while let Some(node_idx) = queue.pop() {
+                if linked.insert(node_idx) {
+                    queue.extend(
+                        nodes[node_idx]
+                            .normal_deps
+                            .iter()
+                            .filter(|&&dep| !nodes[dep].is_proc_macro),
+                    );
+                }
+            }
+            for (&node_idx, ()) in &visited {
+                nodes[node_idx].is_build_only = !linked.contains(&node_idx);
+            }
+
             // And finally visit workspace-members' dev-deps, safe in the knowledge that
             // we know what all the roots are now.
             for pkgid in &metadata.workspace_members {
@@ -507,6 +671,7 @@ impl<'a> DepGraph<'a> {
                     resolve_node,
                     &[DependencyKind::Development],
                     &interner_by_pkgid,
+                    targets,
                 );
 
                 // Now visit all the dev deps
@@ -518,6 +683,7 @@ impl<'a> DepGraph<'a> {
                         &interner_by_pkgid,
                         &resolve_index_by_pkgid,
                         resolve_list,
+                        targets,
                         child,
                     );
                     // Note that these edges do not change whether something is a "root"
@@ -527,6 +693,45 @@ impl<'a> DepGraph<'a> {
                 let node = &mut nodes[node_idx];
                 node.dev_deps = dev_deps;
             }
+
+            // If we're only vetting for specific targets, record which of those
+            // targets each package is actually built for by walking the graph
+            // once for each target.
+            for target in targets {
+                let mut visited = FastSet::new();
+                let mut queue: Vec<_> = metadata
+                    .workspace_members
+                    .iter()
+                    .map(|pkgid| interner_by_pkgid[pkgid])
+                    .collect();
+                while let Some(node_idx) = queue.pop() {
+                    if !visited.insert(node_idx) {
+                        continue;
+                    }
+                    let node = &mut nodes[node_idx];
+                    node.targets.push(target.triple.clone());
+
+                    // Like above, dev-deps are only followed for workspace members.
+                    let kinds: &[_] = if node.is_workspace_member {
+                        &[
+                            DependencyKind::Normal,
+                            DependencyKind::Build,
+                            DependencyKind::Development,
+                        ]
+                    } else {
+                        &[DependencyKind::Normal, DependencyKind::Build]
+                    };
+                    let resolve_node = &resolve_list[resolve_index_by_pkgid[node.package_id]];
+                    queue.extend(deps(
+                        resolve_node,
+                        kinds,
+                        &interner_by_pkgid,
+                        std::slice::from_ref(target),
+                    ));
+                }
+            }
+
+            #[allow(clippy::too_many_arguments)]
             fn visit_node<'a>(
                 nodes: &mut Vec<PackageNode<'a>>,
                 topo_index: &mut Vec<PackageIdx>,
@@ -534,6 +739,7 @@ impl<'a> DepGraph<'a> {
                 interner_by_pkgid: &SortedMap<&'a PackageId, PackageIdx>,
                 resolve_index_by_pkgid: &SortedMap<&'a PackageId, usize>,
                 resolve_list: &'a [cargo_metadata::Node],
+                targets: &[TargetPlatform],
                 normal_idx: PackageIdx,
             ) {
                 // Don't revisit a node we've already seen
@@ -544,19 +750,41 @@ impl<'a> DepGraph<'a> {
                         &resolve_list[resolve_index_by_pkgid[nodes[normal_idx].package_id]];
 
                     // Compute the different kinds of dependencies
-                    let all_deps = resolve_node
-                        .dependencies
-                        .iter()
-                        .map(|pkgid| interner_by_pkgid[pkgid])
-                        .collect::<Vec<_>>();
-                    let build_deps =
-                        deps(resolve_node, &[DependencyKind::Build], interner_by_pkgid);
-                    let normal_deps =
-                        deps(resolve_node, &[DependencyKind::Normal], interner_by_pkgid);
+                    let all_deps = if targets.is_empty() {
+                        resolve_node
+                            .dependencies
+                            .iter()
+                            .map(|pkgid| interner_by_pkgid[pkgid])
+                            .collect::<Vec<_>>()
+                    } else {
+                        deps(
+                            resolve_node,
+                            &[
+                                DependencyKind::Normal,
+                                DependencyKind::Build,
+                                DependencyKind::Development,
+                            ],
+                            interner_by_pkgid,
+                            targets,
+                        )
+                    };
+                    let build_deps = deps(
+                        resolve_node,
+                        &[DependencyKind::Build],
+                        interner_by_pkgid,
+                        targets,
+                    );
+                    let normal_deps = deps(
+                        resolve_node,
+                        &[DependencyKind::Normal],
+                        interner_by_pkgid,
+                        targets,
+                    );
                     let normal_and_build_deps = deps(
                         resolve_node,
                         &[DependencyKind::Normal, DependencyKind::Build],
                         interner_by_pkgid,
+                        targets,
                     );
 
                     // Now visit all the normal and build deps
@@ -568,6 +796,7 @@ impl<'a> DepGraph<'a> {
                             interner_by_pkgid,
                             resolve_index_by_pkgid,
                             resolve_list,
+                            targets,
                             child,
                         );
                         nodes[child].reverse_deps.insert(normal_idx);
@@ -590,17 +819,26 @@ impl<'a> DepGraph<'a> {
                 resolve_node: &Node,
                 kinds: &[DependencyKind],
                 interner_by_pkgid: &SortedMap<&PackageId, PackageIdx>,
+                targets: &[TargetPlatform],
             ) -> Vec<PackageIdx> {
-                // Note that dep_kinds has target cfg info. If we want to handle targets
-                // we should gather those up with filter/fold instead of just 'any'.
-                // TODO: map normal-deps that whose package has a "proc-macro" target to be build-deps
+                // A dependency is only enabled if one of its dep_kinds has a
+                // matching kind, and is either unconditional or has a `cfg()`
+                // which matches one of the targets we're vetting for (if any).
+                // NOTE: proc-macros are still reported as normal-deps here, see
+                // `DepGraph::dep_kinds` for how they're treated as build-deps.
                 resolve_node
                     .deps
                     .iter()
                     .filter(|dep| {
-                        dep.dep_kinds
-                            .iter()
-                            .any(|dep_kind| kinds.contains(&dep_kind.kind))
+                        dep.dep_kinds.iter().any(|dep_kind| {
+                            kinds.contains(&dep_kind.kind)
+                                && match &dep_kind.target {
+                                    Some(platform) if !targets.is_empty() => {
+                                        targets.iter().any(|target| target.matches(platform))
+                                    }
+                                    _ => true,
+                                }
+                        })
                     })
                     .map(|dep| interner_by_pkgid[&dep.pkg])
                     .collect()
@@ -613,14 +851,27 @@ impl<'a> DepGraph<'a> {
             topo_index,
         };
 
-        // Now apply filters, if any
-        if let Some(filters) = filter_graph {
-            result.filter(filters)
-        } else {
-            result
+        // Now apply filters, if any. If we're only vetting for specific
+        // targets, this also drops packages which aren't built for any of them.
+        match filter_graph {
+            Some(filters) => result.filter(filters),
+            None if !targets.is_empty() => result.filter(&[]),
+            None => result,
         }
     }
 
+    /// Classify the normal or build dependency edge from `parent` to `dep`,
+    /// returning whether `dep` is linked into `parent`, and whether it is only
+    /// run at build time (as a build-dependency or proc-macro). Both are true
+    /// if `dep` is both a normal and a build dependency of `parent`.
+    pub fn dep_kinds(&self, parent: PackageIdx, dep: PackageIdx) -> (bool, bool) {
+        let parent = &self.nodes[parent];
+        let is_proc_macro = self.nodes[dep].is_proc_macro;
+        let is_linked = !is_proc_macro && parent.normal_deps.contains(&dep);
+        let is_build = is_proc_macro || parent.build_deps.contains(&dep);
+        (is_linked, is_build)
+    }
+
     pub fn filter(self, filters: &[GraphFilter]) -> Self {
         use GraphFilter::*;
         use GraphFilterProperty::*;
@@ -714,6 +965,9 @@ impl<'a> DepGraph<'a> {
                 is_third_party: package.is_third_party,
                 is_root: package.is_root,
                 is_dev_only: package.is_dev_only,
+                is_proc_macro: package.is_proc_macro,
+                is_build_only: package.is_build_only,
+                targets: package.targets.clone(),
             });
             interner_by_pkgid.insert(package.package_id, new_idx);
         }
@@ -846,121 +1100,1353 @@ impl<'a> DepGraph<'a> {
                 );
             }
         }
-        writeln!(out, "    end");
+        writeln!(out, "    end");
+
+        for &idx in &nodes_with_children {
+            let package = &self.nodes[idx];
+            for &dep_idx in &package.all_deps {
+                if visible_nodes.contains(&dep_idx) {
+                    writeln!(out, "    node{idx} --> node{dep_idx}");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn resolve<'a>(
+    metadata: &'a Metadata,
+    filter_graph: Option<&Vec<GraphFilter>>,
+    store: &Store,
+) -> ResolveReport<'a> {
+    resolve_for_targets(metadata, filter_graph, store, &store.target_platforms)
+}
+
+/// Like [`resolve`][], but vets for the given target platforms rather than
+/// those configured for the store. Used to vet feature profiles which specify
+/// their own targets.
+pub fn resolve_for_targets<'a>(
+    metadata: &'a Metadata,
+    filter_graph: Option<&Vec<GraphFilter>>,
+    store: &Store,
+    targets: &[TargetPlatform],
+) -> ResolveReport<'a> {
+    // A large part of our algorithm is unioning and intersecting criteria, so we map all
+    // the criteria into indexed boolean sets (*whispers* an integer with lots of bits).
+    let graph = DepGraph::new(metadata, filter_graph, Some(&store.config.policy), targets);
+    // trace!("built DepGraph: {:#?}", graph);
+    trace!("built DepGraph!");
+
+    let criteria_mapper = CriteriaMapper::new(&store.audits.criteria);
+    trace!("built CriteriaMapper!");
+
+    let requirements = resolve_requirements(&graph, &store.config.policy, &criteria_mapper);
+
+    let (results, conclusion, resolved_conflicts) =
+        resolve_audits(&graph, store, &criteria_mapper, &requirements);
+
+    ResolveReport {
+        graph,
+        criteria_mapper,
+        requirements,
+        results,
+        conclusion,
+        resolved_conflicts,
+    }
+}
+
+fn resolve_requirements(
+    graph: &DepGraph<'_>,
+    policy: &Policy,
+    criteria_mapper: &CriteriaMapper,
+) -> Vec<CriteriaSet> {
+    let _resolve_requirements = trace_span!("resolve_requirements").entered();
+
+    let mut requirements = vec![criteria_mapper.no_criteria(); graph.nodes.len()];
+    // The criteria required of each package's build-dependencies and
+    // proc-macros. These are propagated along every edge, so that build-time
+    // dependencies of third-party crates are held to the `build-criteria` of
+    // the first-party crates which pull them in.
+    let mut build_requirements = vec![criteria_mapper.no_criteria(); graph.nodes.len()];
+
+    // For any packages which have dev-dependencies, apply policy-specified
+    // dependency-criteria or dev-criteria to those dependencies. These don't
+    // depend on the requirements of the package itself.
+    let no_criteria = criteria_mapper.no_criteria();
+    for (pkgidx, package) in graph.nodes.iter().enumerate() {
+        for &depidx in &package.dev_deps {
+            let (criteria, build_criteria, _) = edge_requirements(
+                graph,
+                policy,
+                criteria_mapper,
+                (pkgidx, depidx, true),
+                &no_criteria,
+                &no_criteria,
+            );
+            requirements[depidx].unioned_with(&criteria);
+            build_requirements[depidx].unioned_with(&build_criteria);
+        }
+    }
+
+    // Walk the topo graph in reverse, so that we visit each package before any
+    // dependencies.
+    for &pkgidx in graph.topo_index.iter().rev() {
+        let package = &graph.nodes[pkgidx];
+        let (criteria, build_criteria, _) = package_requirements(
+            policy,
+            criteria_mapper,
+            package,
+            requirements[pkgidx].clone(),
+            build_requirements[pkgidx].clone(),
+        );
+
+        for &depidx in &package.normal_and_build_deps {
+            let (dep_criteria, dep_build_criteria, _) = edge_requirements(
+                graph,
+                policy,
+                criteria_mapper,
+                (pkgidx, depidx, false),
+                &criteria,
+                &build_criteria,
+            );
+            requirements[depidx].unioned_with(&dep_criteria);
+            build_requirements[depidx].unioned_with(&dep_build_criteria);
+        }
+        requirements[pkgidx] = criteria;
+    }
+
+    requirements
+}
+
+/// The part of the policy which determined the criteria a dependency edge
+/// places on a package, as computed by [`edge_requirements`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EdgeSource {
+    /// The parent's `dependency-criteria` for the package.
+    DependencyCriteria,
+    /// The parent's `dev-criteria`.
+    DevCriteria,
+    /// The default dev-criteria, as the parent has no `dev-criteria`.
+    DefaultDevCriteria,
+    /// The parent's `build-criteria`, for a build-only dependency.
+    BuildCriteria,
+    /// The parent's own requirements.
+    Inherited,
+}
+
+/// Compute the `(criteria, build_criteria, source)` which the dependency edge
+/// `(pkgidx, depidx, is_dev)` places on `depidx`, given the `criteria` and
+/// `build_criteria` required of `pkgidx`.
+fn edge_requirements(
+    graph: &DepGraph<'_>,
+    policy: &Policy,
+    criteria_mapper: &CriteriaMapper,
+    (pkgidx, depidx, is_dev): (PackageIdx, PackageIdx, bool),
+    criteria: &CriteriaSet,
+    build_criteria: &CriteriaSet,
+) -> (CriteriaSet, CriteriaSet, EdgeSource) {
+    let package = &graph.nodes[pkgidx];
+    let policy = policy.get(package.name, &package.version);
+
+    if let Some(c) = policy.and_then(|p| p.dependency_criteria.get(graph.nodes[depidx].name)) {
+        let criteria = criteria_mapper.criteria_from_list(c);
+        return (criteria.clone(), criteria, EdgeSource::DependencyCriteria);
+    }
+
+    if is_dev {
+        let (criteria, source) = match policy.and_then(|p| p.dev_criteria.as_ref()) {
+            Some(c) => (
+                criteria_mapper.criteria_from_list(c),
+                EdgeSource::DevCriteria,
+            ),
+            None => (
+                criteria_mapper.criteria_from_list([format::DEFAULT_POLICY_DEV_CRITERIA]),
+                EdgeSource::DefaultDevCriteria,
+            ),
+        };
+        return (criteria.clone(), criteria, source);
+    }
+
+    let (is_linked, is_build) = graph.dep_kinds(pkgidx, depidx);
+    let mut dep_criteria = criteria_mapper.no_criteria();
+    if is_linked {
+        dep_criteria.unioned_with(criteria);
+    }
+    if is_build {
+        dep_criteria.unioned_with(build_criteria);
+    }
+    let source = if is_build && !is_linked && policy.map_or(false, |p| p.build_criteria.is_some()) {
+        EdgeSource::BuildCriteria
+    } else {
+        EdgeSource::Inherited
+    };
+    (dep_criteria, build_criteria.clone(), source)
+}
+
+/// Compute the `(criteria, build_criteria, overridden)` required of `package`,
+/// given the `criteria` and `build_criteria` placed on it by its
+/// reverse-dependencies. `overridden` is set if the package's own policy
+/// `criteria` replaced them.
+fn package_requirements(
+    policy: &Policy,
+    criteria_mapper: &CriteriaMapper,
+    package: &PackageNode<'_>,
+    mut criteria: CriteriaSet,
+    build_criteria: CriteriaSet,
+) -> (CriteriaSet, CriteriaSet, bool) {
+    let policy = policy.get(package.name, &package.version);
+
+    let overridden = if let Some(c) = policy.and_then(|p| p.criteria.as_ref()) {
+        // If we specify a policy on ourselves, override any requirements we've
+        // had placed on us by reverse-dependencies.
+        criteria = criteria_mapper.criteria_from_list(c);
+        true
+    } else {
+        if package.is_root {
+            // If this is a root crate, it will require at least
+            // `DEFAULT_POLICY_CRITERIA` by default, unless overridden.
+            criteria.unioned_with(
+                &criteria_mapper.criteria_from_list([format::DEFAULT_POLICY_CRITERIA]),
+            );
+        }
+        false
+    };
+
+    // `build-criteria` defaults to `criteria` when a policy specifies one, and
+    // is otherwise inherited from reverse-dependencies.
+    let build_criteria = match policy.and_then(|p| p.build_criteria.as_ref()) {
+        Some(c) => criteria_mapper.criteria_from_list(c),
+        None if package.is_root || overridden => criteria.clone(),
+        None => build_criteria,
+    };
+    (criteria, build_criteria, overridden)
+}
+
+/// A single hop on a path from a root crate, as computed by [`why`].
+#[derive(Clone)]
+struct WhyHop {
+    pkgidx: PackageIdx,
+    kind: Option<JsonWhyDependencyKind>,
+    criteria: CriteriaSet,
+    /// The criteria required of this package's build-dependencies.
+    build_criteria: CriteriaSet,
+    reason: JsonWhyReason,
+}
+
+/// State for enumerating the paths from root crates to a package in [`why`].
+struct WhySearch<'a, 'b> {
+    graph: &'b DepGraph<'a>,
+    policy: &'b Policy,
+    criteria_mapper: &'b CriteriaMapper,
+    /// Packages which can reach the target, the only ones worth visiting.
+    ancestors: FastSet<PackageIdx>,
+    target: PackageIdx,
+    max_paths: usize,
+    paths: Vec<Vec<WhyHop>>,
+    truncated: bool,
+}
+
+impl WhySearch<'_, '_> {
+    fn visit(&mut self, path: &mut Vec<WhyHop>) {
+        let parent = path.last().expect("path cannot be empty").clone();
+        if parent.pkgidx == self.target {
+            if self.paths.len() < self.max_paths {
+                self.paths.push(path.clone());
+            } else {
+                self.truncated = true;
+            }
+            return;
+        }
+
+        let node = &self.graph.nodes[parent.pkgidx];
+        let edges = node
+            .normal_and_build_deps
+            .iter()
+            .map(|&dep| {
+                if node.normal_deps.contains(&dep) {
+                    (dep, JsonWhyDependencyKind::Normal)
+                } else {
+                    (dep, JsonWhyDependencyKind::Build)
+                }
+            })
+            .chain(
+                node.dev_deps
+                    .iter()
+                    .map(|&dep| (dep, JsonWhyDependencyKind::Dev)),
+            );
+        for (depidx, kind) in edges {
+            if self.truncated {
+                return;
+            }
+            // Skip packages which can't reach the target, and avoid following
+            // dev-dependency cycles.
+            if !self.ancestors.contains(&depidx) || path.iter().any(|hop| hop.pkgidx == depidx) {
+                continue;
+            }
+
+            let (criteria, build_criteria, source) = edge_requirements(
+                self.graph,
+                self.policy,
+                self.criteria_mapper,
+                (parent.pkgidx, depidx, kind == JsonWhyDependencyKind::Dev),
+                &parent.criteria,
+                &parent.build_criteria,
+            );
+            let dep_package = &self.graph.nodes[depidx];
+            let (criteria, build_criteria, overridden) = package_requirements(
+                self.policy,
+                self.criteria_mapper,
+                dep_package,
+                criteria,
+                build_criteria,
+            );
+            let parent_table = || policy_table(self.policy, node.name, &node.version);
+            let reason = if overridden {
+                JsonWhyReason::PolicyCriteria {
+                    policy: policy_table(self.policy, dep_package.name, &dep_package.version),
+                }
+            } else {
+                match source {
+                    EdgeSource::DependencyCriteria => JsonWhyReason::PolicyDependencyCriteria {
+                        policy: parent_table(),
+                    },
+                    EdgeSource::DevCriteria => JsonWhyReason::PolicyDevCriteria {
+                        policy: parent_table(),
+                    },
+                    EdgeSource::DefaultDevCriteria => JsonWhyReason::DefaultDevCriteria,
+                    EdgeSource::BuildCriteria => JsonWhyReason::PolicyBuildCriteria {
+                        policy: parent_table(),
+                    },
+                    EdgeSource::Inherited => JsonWhyReason::Inherited,
+                }
+            };
+
+            path.push(WhyHop {
+                pkgidx: depidx,
+                kind: Some(kind),
+                criteria,
+                build_criteria,
+                reason,
+            });
+            self.visit(path);
+            path.pop();
+        }
+    }
+}
+
+/// The name of the table in config.toml holding the policy for a package.
+fn policy_table(policy: &Policy, name: PackageStr<'_>, version: &VetVersion) -> String {
+    match policy.package.get(name) {
+        Some(PackagePolicyEntry::Versioned { .. }) => format!("policy.\"{name}:{version}\""),
+        _ => format!("policy.{name}"),
+    }
+}
+
+/// Explain why each version of a package is required to satisfy the criteria
+/// it does, by enumerating every path from a root crate to it along with how
+/// the required criteria propagated along each hop.
+pub fn why(
+    metadata: &Metadata,
+    filter_graph: Option<&Vec<GraphFilter>>,
+    store: &Store,
+    package: PackageStr<'_>,
+    max_paths: usize,
+) -> Result<JsonWhy, WhyError> {
+    let policy = &store.config.policy;
+    let graph = DepGraph::new(
+        metadata,
+        filter_graph,
+        Some(policy),
+        &store.target_platforms,
+    );
+    let criteria_mapper = CriteriaMapper::new(&store.audits.criteria);
+    let requirements = resolve_requirements(&graph, policy, &criteria_mapper);
+
+    let criteria_names = |criteria: &CriteriaSet| -> Vec<CriteriaName> {
+        criteria_mapper
+            .criteria_names(criteria)
+            .map(|s| s.to_owned())
+            .collect()
+    };
+
+    let mut packages = Vec::new();
+    for (target, node) in graph.nodes.iter().enumerate() {
+        if node.name != package {
+            continue;
+        }
+
+        let mut ancestors = FastSet::new();
+        let mut queue = vec![target];
+        while let Some(idx) = queue.pop() {
+            if ancestors.insert(idx) {
+                queue.extend(graph.nodes[idx].reverse_deps.iter().copied());
+            }
+        }
+
+        let mut search = WhySearch {
+            graph: &graph,
+            policy,
+            criteria_mapper: &criteria_mapper,
+            ancestors,
+            target,
+            max_paths,
+            paths: Vec::new(),
+            truncated: false,
+        };
+        for (root_idx, root) in graph.nodes.iter().enumerate() {
+            if !root.is_root || !search.ancestors.contains(&root_idx) {
+                continue;
+            }
+            let (criteria, build_criteria, overridden) = package_requirements(
+                policy,
+                &criteria_mapper,
+                root,
+                criteria_mapper.no_criteria(),
+                criteria_mapper.no_criteria(),
+            );
+            let reason = if overridden {
+                JsonWhyReason::PolicyCriteria {
+                    policy: policy_table(policy, root.name, &root.version),
+                }
+            } else {
+                JsonWhyReason::DefaultRootCriteria
+            };
+            search.visit(&mut vec![WhyHop {
+                pkgidx: root_idx,
+                kind: None,
+                criteria,
+                build_criteria,
+                reason,
+            }]);
+        }
+
+        packages.push(JsonWhyPackage {
+            name: node.name.to_owned(),
+            version: node.version.clone(),
+            required_criteria: criteria_names(&requirements[target]),
+            paths: search
+                .paths
+                .iter()
+                .map(|path| {
+                    path.iter()
+                        .map(|hop| {
+                            let hop_node = &graph.nodes[hop.pkgidx];
+                            JsonWhyHop {
+                                name: hop_node.name.to_owned(),
+                                version: hop_node.version.clone(),
+                                dependency_kind: hop.kind,
+                                criteria: criteria_names(&hop.criteria),
+                                reason: hop.reason.clone(),
+                            }
+                        })
+                        .collect()
+                })
+                .collect(),
+            truncated: search.truncated,
+        });
+    }
+
+    if packages.is_empty() {
+        return Err(WhyError::NotInGraph {
+            package: package.to_owned(),
+        });
+    }
+    Ok(JsonWhy { packages })
+}
+
+/// Print the human-readable output of `cargo vet why`.
+pub fn print_why_human(out: &Arc<dyn Out>, why: &JsonWhy) -> Result<(), std::io::Error> {
+    let format_criteria = |criteria: &[CriteriaName]| {
+        if criteria.is_empty() {
+            "no criteria".to_owned()
+        } else {
+            criteria.join(", ")
+        }
+    };
+
+    for (idx, package) in why.packages.iter().enumerate() {
+        if idx > 0 {
+            writeln!(out);
+        }
+        writeln!(
+            out,
+            "{}:{} requires {}",
+            package.name,
+            package.version,
+            format_criteria(&package.required_criteria)
+        );
+        if package.paths.is_empty() {
+            writeln!(out);
+            writeln!(out, "  not reachable from any root crate");
+        }
+        for path in &package.paths {
+            writeln!(out);
+            let mut parent = None;
+            for (depth, hop) in path.iter().enumerate() {
+                let reason = match &hop.reason {
+                    JsonWhyReason::DefaultRootCriteria => "root crate, default criteria".to_owned(),
+                    JsonWhyReason::Inherited => {
+                        format!("inherited from {}", parent.unwrap_or_default())
+                    }
+                    JsonWhyReason::DefaultDevCriteria => "default dev-criteria".to_owned(),
+                    JsonWhyReason::PolicyCriteria { policy } => format!("criteria in [{policy}]"),
+                    JsonWhyReason::PolicyDevCriteria { policy } => {
+                        format!("dev-criteria in [{policy}]")
+                    }
+                    JsonWhyReason::PolicyBuildCriteria { policy } => {
+                        format!("build-criteria in [{policy}]")
+                    }
+                    JsonWhyReason::PolicyDependencyCriteria { policy } => {
+                        format!("dependency-criteria in [{policy}]")
+                    }
+                };
+                let kind = match hop.dependency_kind {
+                    Some(JsonWhyDependencyKind::Build) => " [build]",
+                    Some(JsonWhyDependencyKind::Dev) => " [dev]",
+                    Some(JsonWhyDependencyKind::Normal) | None => "",
+                };
+                let arrow = if depth == 0 { "" } else { "-> " };
+                writeln!(
+                    out,
+                    "  {:indent$}{arrow}{}:{}{kind} requires {} ({reason})",
+                    "",
+                    hop.name,
+                    hop.version,
+                    format_criteria(&hop.criteria),
+                    indent = depth.saturating_sub(1) * 3,
+                );
+                parent = Some(&hop.name[..]);
+            }
+        }
+        if package.truncated {
+            writeln!(out);
+            writeln!(
+                out,
+                "  (further paths omitted, use --max-paths to show more)"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// A hypothetical change to the dependency graph, evaluated by [`what_if`].
+pub enum WhatIfChange<'a> {
+    /// Add a dependency on `package` at `version` from crates.io to the
+    /// first-party crate `dependent`, or to every root crate if it is `None`.
+    Add {
+        package: PackageStr<'a>,
+        version: &'a semver::Version,
+        dependent: Option<PackageStr<'a>>,
+    },
+    /// Replace every version of `package` in the graph (or only `from`, if
+    /// specified) with `version` from crates.io.
+    Upgrade {
+        package: PackageStr<'a>,
+        from: Option<&'a VetVersion>,
+        version: &'a semver::Version,
+    },
+}
+
+impl fmt::Display for WhatIfChange<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WhatIfChange::Add {
+                package,
+                version,
+                dependent,
+            } => {
+                write!(f, "add {package}@{version}")?;
+                if let Some(dependent) = dependent {
+                    write!(f, " to {dependent}")?;
+                }
+                Ok(())
+            }
+            WhatIfChange::Upgrade {
+                package,
+                from,
+                version,
+            } => {
+                write!(f, "upgrade {package}")?;
+                if let Some(from) = from {
+                    write!(f, ":{from}")?;
+                }
+                write!(f, " to {version}")
+            }
+        }
+    }
+}
+
+/// Estimate the audit cost of a hypothetical change to the dependency graph.
+///
+/// The change is applied to a copy of the cargo metadata, which is then
+/// resolved against the current store. Each third-party package which is new
+/// in the resulting graph is reported along with the entries which would vet
+/// it, and the audit we'd suggest if it isn't vetted.
+pub fn what_if(
+    cfg: &Config,
+    store: &Store,
+    network: Option<&Network>,
+    change: &WhatIfChange<'_>,
+) -> Result<JsonWhatIf, WhatIfError> {
+    let filter_graph = cfg.cli.filter_graph.as_ref();
+    let before = DepGraph::new(
+        &cfg.metadata,
+        filter_graph,
+        Some(&store.config.policy),
+        &store.target_platforms,
+    );
+    let (mut metadata, new_package) = apply_what_if(&cfg.metadata, &before, change)?;
+    let mut warnings = Vec::new();
+    if let Some((package_id, features)) = new_package {
+        if let Err(error) =
+            resolve_index_dependencies(cfg, network, &mut metadata, package_id, features)
+        {
+            let (package, version) = match *change {
+                WhatIfChange::Add {
+                    package, version, ..
+                }
+                | WhatIfChange::Upgrade {
+                    package, version, ..
+                } => (package, version),
+            };
+            warnings.push(format!(
+                "transitive dependencies of {package}:{version} aren't included, as {error}"
+            ));
+        }
+    }
+    prune_unreachable(&mut metadata);
+    let mut report = resolve(&metadata, filter_graph, store);
+
+    let third_party = |graph: &DepGraph<'_>| -> FastSet<(PackageName, VetVersion)> {
+        graph
+            .nodes
+            .iter()
+            .filter(|node| node.is_third_party)
+            .map(|node| (node.name.to_owned(), node.version.clone()))
+            .collect()
+    };
+    let before_packages = third_party(&before);
+    let after_packages = third_party(&report.graph);
+
+    let added: Vec<PackageIdx> = report
+        .graph
+        .nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| {
+            node.is_third_party
+                && !before_packages.contains(&(node.name.to_owned(), node.version.clone()))
+        })
+        .map(|(idx, _)| idx)
+        .collect();
+    let removed = before
+        .nodes
+        .iter()
+        .filter(|node| {
+            node.is_third_party
+                && !after_packages.contains(&(node.name.to_owned(), node.version.clone()))
+        })
+        .map(|node| JsonPackage {
+            name: node.name.to_owned(),
+            version: node.version.clone(),
+            targets: node.targets.clone(),
+        })
+        .collect();
+
+    // Only suggest audits for the new packages, as existing failures aren't
+    // part of the cost of the change.
+    if let Conclusion::FailForVet(fail) = &mut report.conclusion {
+        fail.failures.retain(|(idx, _)| added.contains(idx));
+    }
+    let suggest = report.compute_suggest(cfg, store, network)?;
+
+    let added = added
+        .iter()
+        .map(|&idx| {
+            let node = &report.graph.nodes[idx];
+            let explain = report.explain(store, node.name, &node.version)?;
+            let suggestion = suggest
+                .as_ref()
+                .and_then(|suggest| suggest.suggestions.iter().find(|s| s.package == idx));
+            Ok(JsonWhatIfPackage {
+                name: explain.name,
+                version: explain.version,
+                vetted: explain.criteria.iter().all(|c| c.satisfied),
+                required_criteria: explain.required_criteria,
+                criteria: explain.criteria,
+                suggested_criteria: suggestion
+                    .map(|s| {
+                        report
+                            .criteria_mapper
+                            .criteria_names(&s.suggested_criteria)
+                            .map(str::to_owned)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                suggested_diff: suggestion.map(|s| s.suggested_diff.clone()),
+            })
+        })
+        .collect::<Result<Vec<_>, WhatIfError>>()?;
+
+    let total_lines = match suggest {
+        Some(suggest) => {
+            warnings.extend(suggest.warnings);
+            suggest.total_lines
+        }
+        None => 0,
+    };
+    Ok(JsonWhatIf {
+        change: change.to_string(),
+        added,
+        removed,
+        total_lines,
+        warnings,
+    })
+}
+
+/// A package added to the graph by [`apply_what_if`], along with the features
+/// it should be built with.
+type NewPackage = (PackageId, Vec<String>);
+
+/// Apply a hypothetical change to a copy of the cargo metadata.
+///
+/// Only the named package is changed, as we can't run cargo's resolver over
+/// the modified manifests. If a version which isn't already in the graph is
+/// added, it is returned along with the features it should be built with, so
+/// that its dependencies can be resolved by [`resolve_index_dependencies`].
+/// Until then, a newly added package has no dependencies of its own, and an
+/// upgraded package keeps the dependencies of the version it replaces.
+fn apply_what_if(
+    metadata: &Metadata,
+    graph: &DepGraph<'_>,
+    change: &WhatIfChange<'_>,
+) -> Result<(Metadata, Option<NewPackage>), WhatIfError> {
+    let mut metadata = metadata.clone();
+    let mut new_package = None;
+    match *change {
+        WhatIfChange::Add {
+            package,
+            version,
+            dependent,
+        } => {
+            let dependents: Vec<PackageId> = match dependent {
+                Some(dependent) => {
+                    let node = graph
+                        .nodes
+                        .iter()
+                        .find(|node| node.name == dependent && !node.is_third_party)
+                        .ok_or_else(|| WhatIfError::NotFirstParty {
+                            package: dependent.to_owned(),
+                        })?;
+                    vec![node.package_id.clone()]
+                }
+                None => graph
+                    .nodes
+                    .iter()
+                    .filter(|node| node.is_root)
+                    .map(|node| node.package_id.clone())
+                    .collect(),
+            };
+
+            // Reuse the package if that exact version is already in the graph.
+            let existing = metadata
+                .packages
+                .iter()
+                .find(|p| p.name == package && &p.version == version && p.is_crates_io());
+            let id = match existing {
+                Some(existing) => existing.id.clone(),
+                None => {
+                    let id = synthesize_package(&mut metadata, package, version);
+                    new_package = Some((id.clone(), vec!["default".to_owned()]));
+                    id
+                }
+            };
+
+            let dep: NodeDep = serde_json::from_value(serde_json::json!({
+                "name": package.replace('-', "_"),
+                "pkg": id.repr,
+                "dep_kinds": [{ "kind": null, "target": null }],
+            }))
+            .expect("invalid synthetic dependency");
+            for node in resolve_nodes(&mut metadata) {
+                if dependents.contains(&node.id) && !node.dependencies.contains(&id) {
+                    node.dependencies.push(id.clone());
+                    node.deps.push(dep.clone());
+                }
+            }
+        }
+        WhatIfChange::Upgrade {
+            package,
+            from,
+            version,
+        } => {
+            let old_nodes: Vec<_> = graph
+                .nodes
+                .iter()
+                .filter(|node| {
+                    node.name == package && (from.is_none() || from == Some(&node.version))
+                })
+                .collect();
+            if old_nodes.is_empty() {
+                return Err(match from {
+                    Some(from) => WhatIfError::VersionNotInGraph {
+                        package: package.to_owned(),
+                        version: from.clone(),
+                    },
+                    None => WhatIfError::NotInGraph {
+                        package: package.to_owned(),
+                    },
+                });
+            }
+            if old_nodes.iter().any(|node| !node.is_third_party) {
+                return Err(WhatIfError::UpgradeFirstParty {
+                    package: package.to_owned(),
+                });
+            }
+            let mut old_ids: Vec<PackageId> = old_nodes
+                .iter()
+                .map(|node| node.package_id.clone())
+                .collect();
+
+            // If the new version isn't already in the graph, the first old
+            // version becomes it in-place. Every other old version is removed,
+            // and references to them are redirected to the new version.
+            let new_id = match metadata
+                .packages
+                .iter()
+                .find(|p| p.name == package && &p.version == version && p.is_crates_io())
+            {
+                Some(existing) => existing.id.clone(),
+                None => {
+                    let new_id = crates_io_id(package, version);
+                    let old_package = metadata
+                        .packages
+                        .iter_mut()
+                        .find(|p| p.id == old_ids[0])
+                        .expect("graph node without package?");
+                    old_package.version = version.clone();
+                    old_package.id = new_id.clone();
+                    old_package.source = Some(cargo_metadata::Source {
+                        repr: CRATES_IO_SOURCE.to_owned(),
+                    });
+                    let old_node = resolve_nodes(&mut metadata)
+                        .iter_mut()
+                        .find(|node| node.id == old_ids[0])
+                        .expect("graph node without resolve node?");
+                    old_node.id = new_id.clone();
+                    new_package = Some((new_id.clone(), old_node.features.clone()));
+                    new_id
+                }
+            };
+            // The new version may itself be one of the old ones being
+            // replaced, in which case it has to stay in the graph.
+            old_ids.retain(|id| *id != new_id);
+            metadata.packages.retain(|p| !old_ids.contains(&p.id));
+            resolve_nodes(&mut metadata).retain(|node| !old_ids.contains(&node.id));
+
+            for node in resolve_nodes(&mut metadata) {
+                let mut seen = FastSet::new();
+                node.dependencies = node
+                    .dependencies
+                    .drain(..)
+                    .map(|id| {
+                        if old_ids.contains(&id) {
+                            new_id.clone()
+                        } else {
+                            id
+                        }
+                    })
+                    .filter(|id| seen.insert(id.clone()))
+                    .collect();
+                let mut deps: Vec<NodeDep> = Vec::new();
+                for mut dep in node.deps.drain(..) {
+                    if old_ids.contains(&dep.pkg) {
+                        dep.pkg = new_id.clone();
+                    }
+                    match deps.iter_mut().find(|d| d.pkg == dep.pkg) {
+                        Some(existing) => existing.dep_kinds.extend(dep.dep_kinds),
+                        None => deps.push(dep),
+                    }
+                }
+                node.deps = deps;
+            }
+        }
+    }
 
-        for &idx in &nodes_with_children {
-            let package = &self.nodes[idx];
-            for &dep_idx in &package.all_deps {
-                if visible_nodes.contains(&dep_idx) {
-                    writeln!(out, "    node{idx} --> node{dep_idx}");
+    Ok((metadata, new_package))
+}
+
+const CRATES_IO_SOURCE: &str = "registry+https://github.com/rust-lang/crates.io-index";
+
+fn crates_io_id(package: PackageStr<'_>, version: &semver::Version) -> PackageId {
+    PackageId {
+        repr: format!("{package} {version} ({CRATES_IO_SOURCE})"),
+    }
+}
+
+/// Add a crates.io package with no dependencies to the cargo metadata.
+fn synthesize_package(
+    metadata: &mut Metadata,
+    package: PackageStr<'_>,
+    version: &semver::Version,
+) -> PackageId {
+    let id = crates_io_id(package, version);
+    metadata.packages.push(
+        serde_json::from_value(serde_json::json!({
+            "name": package,
+            "version": version.to_string(),
+            "id": id.repr,
+            "source": CRATES_IO_SOURCE,
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": "",
+        }))
+        .expect("invalid synthetic package"),
+    );
+    resolve_nodes(metadata).push(
+        serde_json::from_value(serde_json::json!({
+            "id": id.repr,
+            "dependencies": [],
+            "deps": [],
+        }))
+        .expect("invalid synthetic resolve node"),
+    );
+    id
+}
+
+/// Resolve the dependencies of a package added by [`apply_what_if`] from the
+/// crates.io index, adding any packages they pull in to the cargo metadata.
+///
+/// This approximates cargo's resolver: each requirement is satisfied by a
+/// version already in the graph if possible, and otherwise by the newest
+/// version in the index which matches it and isn't yanked. Features are
+/// unified for the packages added here, but the features of packages which
+/// were already in the graph aren't changed.
+fn resolve_index_dependencies(
+    cfg: &Config,
+    network: Option<&Network>,
+    metadata: &mut Metadata,
+    package_id: PackageId,
+    features: Vec<String>,
+) -> Result<(), IndexResolveError> {
+    let network = network.ok_or(IndexResolveError::Offline)?;
+    let cache = Cache::acquire(cfg)?;
+    let mut resolver = IndexResolver {
+        cache: &cache,
+        network,
+        index: FastMap::new(),
+        added: FastMap::new(),
+    };
+    // Only commit the changes if every dependency could be resolved.
+    let mut resolved = metadata.clone();
+    tokio::runtime::Handle::current().block_on(resolver.resolve(
+        &mut resolved,
+        package_id,
+        features,
+    ))?;
+    *metadata = resolved;
+    Ok(())
+}
+
+/// State for [`resolve_index_dependencies`].
+struct IndexResolver<'a> {
+    cache: &'a Cache,
+    network: &'a Network,
+    /// The index entries which have been read so far.
+    index: FastMap<PackageName, crates_index::Crate>,
+    /// The name, version and enabled features of each package added to the
+    /// graph from the index.
+    added: FastMap<PackageId, (PackageName, semver::Version, SortedSet<String>)>,
+}
+
+impl IndexResolver<'_> {
+    async fn resolve(
+        &mut self,
+        metadata: &mut Metadata,
+        package_id: PackageId,
+        features: Vec<String>,
+    ) -> Result<(), IndexResolveError> {
+        let package = metadata
+            .packages
+            .iter()
+            .find(|p| p.id == package_id)
+            .expect("synthetic package missing");
+        self.added.insert(
+            package_id.clone(),
+            (
+                package.name.clone(),
+                package.version.clone(),
+                features.into_iter().collect(),
+            ),
+        );
+
+        // Packages are revisited whenever more of their features are enabled.
+        let mut queue = vec![package_id];
+        while let Some(id) = queue.pop() {
+            let (name, version, features) = self.added[&id].clone();
+            let index_version = exact_version(self.index_crate(&name).await?, &version)
+                .ok_or_else(|| IndexResolveError::MissingVersion {
+                    package: name.clone(),
+                    version: version.clone(),
+                })?
+                .clone();
+            let (enabled_deps, dep_features) = enabled_dependencies(&index_version, &features);
+
+            let mut deps: Vec<NodeDep> = Vec::new();
+            for dep in index_version.dependencies() {
+                if dep.kind() == crates_index::DependencyKind::Dev
+                    || (dep.is_optional() && !enabled_deps.contains(dep.name()))
+                {
+                    continue;
+                }
+
+                let (dep_id, is_new) = self.select_version(metadata, dep).await?;
+                if let Some((_, _, enabled)) = self.added.get_mut(&dep_id) {
+                    let before = enabled.len();
+                    enabled.extend(dep.features().iter().cloned());
+                    if dep.has_default_features() {
+                        enabled.insert("default".to_owned());
+                    }
+                    enabled.extend(dep_features.get(dep.name()).into_iter().flatten().cloned());
+                    if is_new || enabled.len() != before {
+                        queue.push(dep_id.clone());
+                    }
+                }
+
+                let kind = match dep.kind() {
+                    crates_index::DependencyKind::Build => Some("build"),
+                    _ => None,
+                };
+                let dep_kind: DepKindInfo = serde_json::from_value(serde_json::json!({
+                    "kind": kind,
+                    "target": dep.target(),
+                }))
+                .map_err(|_| IndexResolveError::InvalidTarget {
+                    package: name.clone(),
+                    target: dep.target().unwrap_or_default().to_owned(),
+                })?;
+                match deps.iter_mut().find(|d| d.pkg == dep_id) {
+                    Some(existing) => existing.dep_kinds.push(dep_kind),
+                    None => deps.push(
+                        serde_json::from_value(serde_json::json!({
+                            "name": dep.name().replace('-', "_"),
+                            "pkg": dep_id.repr,
+                            "dep_kinds": [dep_kind],
+                        }))
+                        .expect("invalid synthetic dependency"),
+                    ),
                 }
             }
-        }
 
+            let node = resolve_nodes(metadata)
+                .iter_mut()
+                .find(|node| node.id == id)
+                .expect("synthetic resolve node missing");
+            node.dependencies = deps.iter().map(|dep| dep.pkg.clone()).collect();
+            node.deps = deps;
+            node.features = features.into_iter().collect();
+        }
         Ok(())
     }
-}
 
-pub fn resolve<'a>(
-    metadata: &'a Metadata,
-    filter_graph: Option<&Vec<GraphFilter>>,
-    store: &Store,
-) -> ResolveReport<'a> {
-    // A large part of our algorithm is unioning and intersecting criteria, so we map all
-    // the criteria into indexed boolean sets (*whispers* an integer with lots of bits).
-    let graph = DepGraph::new(metadata, filter_graph, Some(&store.config.policy));
-    // trace!("built DepGraph: {:#?}", graph);
-    trace!("built DepGraph!");
+    /// Pick the package which satisfies a dependency, returning whether it
+    /// was newly added to the graph.
+    async fn select_version(
+        &mut self,
+        metadata: &mut Metadata,
+        dep: &crates_index::Dependency,
+    ) -> Result<(PackageId, bool), IndexResolveError> {
+        let name = dep.crate_name();
+        let req = semver::VersionReq::parse(dep.requirement()).map_err(|error| {
+            IndexResolveError::InvalidRequirement {
+                package: name.to_owned(),
+                requirement: dep.requirement().to_owned(),
+                error,
+            }
+        })?;
 
-    let criteria_mapper = CriteriaMapper::new(&store.audits.criteria);
-    trace!("built CriteriaMapper!");
+        // Prefer a version which is already in the graph, as cargo would when
+        // updating a lockfile.
+        let existing = metadata
+            .packages
+            .iter()
+            .filter(|p| p.name == name && p.is_crates_io() && req.matches(&p.version))
+            .max_by(|a, b| a.version.cmp(&b.version));
+        if let Some(existing) = existing {
+            return Ok((existing.id.clone(), false));
+        }
 
-    let requirements = resolve_requirements(&graph, &store.config.policy, &criteria_mapper);
+        let version = self
+            .index_crate(name)
+            .await?
+            .versions()
+            .iter()
+            .filter(|v| !v.is_yanked())
+            .filter_map(|v| v.version().parse::<semver::Version>().ok())
+            .filter(|v| req.matches(v))
+            .max()
+            .ok_or_else(|| IndexResolveError::NoMatchingVersion {
+                package: name.to_owned(),
+                requirement: dep.requirement().to_owned(),
+            })?;
+        let id = synthesize_package(metadata, name, &version);
+        self.added
+            .insert(id.clone(), (name.to_owned(), version, SortedSet::new()));
+        Ok((id, true))
+    }
+
+    async fn index_crate(
+        &mut self,
+        name: PackageStr<'_>,
+    ) -> Result<&crates_index::Crate, IndexResolveError> {
+        if !self.index.contains_key(name) {
+            let index_crate = self
+                .cache
+                .get_index_crate(self.network, name)
+                .await
+                .map_err(|error| IndexResolveError::CrateInfo {
+                    package: name.to_owned(),
+                    error,
+                })?;
+            self.index.insert(name.to_owned(), index_crate);
+        }
+        Ok(&self.index[name])
+    }
+}
 
-    let (results, conclusion) = resolve_audits(&graph, store, &criteria_mapper, &requirements);
+/// Compute which optional dependencies of a version in the index are enabled
+/// by a set of its features, along with the features enabled on each of its
+/// dependencies.
+fn enabled_dependencies(
+    version: &crates_index::Version,
+    features: &SortedSet<String>,
+) -> (FastSet<String>, FastMap<String, SortedSet<String>>) {
+    let optional: FastSet<&str> = version
+        .dependencies()
+        .iter()
+        .filter(|dep| dep.is_optional())
+        .map(|dep| dep.name())
+        .collect();
 
-    ResolveReport {
-        graph,
-        criteria_mapper,
-        results,
-        conclusion,
+    let mut enabled_deps = FastSet::new();
+    let mut dep_features: FastMap<String, SortedSet<String>> = FastMap::new();
+    let mut seen = FastSet::new();
+    let mut queue: Vec<&str> = features.iter().map(String::as_str).collect();
+    while let Some(feature) = queue.pop() {
+        if !seen.insert(feature) {
+            continue;
+        }
+        if let Some(dep) = feature.strip_prefix("dep:") {
+            enabled_deps.insert(dep.to_owned());
+        } else if let Some((dep, dep_feature)) = feature.split_once('/') {
+            // `dep?/feature` doesn't enable the dependency itself.
+            let dep = match dep.strip_suffix('?') {
+                Some(dep) => dep,
+                None => {
+                    if optional.contains(dep) {
+                        enabled_deps.insert(dep.to_owned());
+                    }
+                    dep
+                }
+            };
+            dep_features
+                .entry(dep.to_owned())
+                .or_default()
+                .insert(dep_feature.to_owned());
+        } else if let Some(values) = version.features().get(feature) {
+            queue.extend(values.iter().map(String::as_str));
+        } else if optional.contains(feature) {
+            // An optional dependency which isn't named with `dep:` has an
+            // implicit feature of the same name.
+            enabled_deps.insert(feature.to_owned());
+        }
     }
+    (enabled_deps, dep_features)
 }
 
-fn resolve_requirements(
-    graph: &DepGraph<'_>,
-    policy: &Policy,
-    criteria_mapper: &CriteriaMapper,
-) -> Vec<CriteriaSet> {
-    let _resolve_requirements = trace_span!("resolve_requirements").entered();
+fn resolve_nodes(metadata: &mut Metadata) -> &mut Vec<Node> {
+    &mut metadata
+        .resolve
+        .as_mut()
+        .expect("cargo metadata did not yield resolve!")
+        .nodes
+}
 
-    let mut requirements = vec![criteria_mapper.no_criteria(); graph.nodes.len()];
+/// Remove any packages which are no longer reachable from the workspace after
+/// applying a hypothetical change.
+fn prune_unreachable(metadata: &mut Metadata) {
+    let reachable: FastSet<PackageId> = {
+        let nodes = &metadata
+            .resolve
+            .as_ref()
+            .expect("cargo metadata did not yield resolve!")
+            .nodes;
+        let node_by_id: FastMap<&PackageId, &Node> = nodes.iter().map(|n| (&n.id, n)).collect();
+        let mut reachable = FastSet::new();
+        let mut queue: Vec<&PackageId> = metadata.workspace_members.iter().collect();
+        while let Some(id) = queue.pop() {
+            if !reachable.insert(id) {
+                continue;
+            }
+            if let Some(node) = node_by_id.get(id) {
+                queue.extend(node.deps.iter().map(|dep| &dep.pkg));
+            }
+        }
+        reachable.into_iter().cloned().collect()
+    };
+    metadata.packages.retain(|p| reachable.contains(&p.id));
+    resolve_nodes(metadata).retain(|node| reachable.contains(&node.id));
+}
 
-    // For any packages which have dev-dependencies, apply policy-specified
-    // dependency-criteria or dev-criteria to those dependencies.
-    for package in &graph.nodes {
-        if package.dev_deps.is_empty() {
+/// Print the human-readable output of `cargo vet what-if`.
+pub fn print_what_if_human(out: &Arc<dyn Out>, what_if: &JsonWhatIf) -> Result<(), std::io::Error> {
+    writeln!(out, "what if: {}", what_if.change);
+    writeln!(out);
+
+    if what_if.added.is_empty() {
+        writeln!(out, "no third-party packages would be added");
+    } else {
+        writeln!(out, "added packages:");
+    }
+    for package in &what_if.added {
+        writeln!(out);
+        if package.required_criteria.is_empty() {
+            writeln!(
+                out,
+                "  {}:{} (no criteria required)",
+                package.name, package.version
+            );
             continue;
         }
+        writeln!(
+            out,
+            "  {}:{} requires {}",
+            package.name,
+            package.version,
+            package.required_criteria.join(", ")
+        );
+        for criteria in &package.criteria {
+            if !criteria.satisfied {
+                writeln!(out, "    {}: NOT vetted", criteria.criteria);
+                continue;
+            }
+            writeln!(out, "    {}: vetted by", criteria.criteria);
+            for edge in &criteria.path {
+                writeln!(out, "      {}", describe_explain_edge(edge));
+            }
+        }
+        if let Some(diff) = &package.suggested_diff {
+            let cmd = match &diff.from {
+                Some(from) => format!("cargo vet diff {} {} {}", package.name, from, diff.to),
+                None => format!("cargo vet inspect {} {}", package.name, diff.to),
+            };
+            let diffstat = match &diff.from {
+                Some(_) => format!("{}", diff.diffstat),
+                None => format!("{} lines", diff.diffstat.count()),
+            };
+            writeln!(
+                out,
+                "    recommended audit for {}: {cmd} ({diffstat})",
+                package.suggested_criteria.join(", ")
+            );
+        }
+    }
 
-        let policy = policy.get(package.name, &package.version);
-        let dev_criteria = if let Some(c) = policy.and_then(|p| p.dev_criteria.as_ref()) {
-            criteria_mapper.criteria_from_list(c)
-        } else {
-            criteria_mapper.criteria_from_list([format::DEFAULT_POLICY_DEV_CRITERIA])
-        };
-
-        for &depidx in &package.dev_deps {
-            let dep_package = &graph.nodes[depidx];
-            let dependency_criteria = policy
-                .and_then(|policy| policy.dependency_criteria.get(dep_package.name))
-                .map(|criteria| criteria_mapper.criteria_from_list(criteria));
-            requirements[depidx]
-                .unioned_with(dependency_criteria.as_ref().unwrap_or(&dev_criteria));
+    if !what_if.removed.is_empty() {
+        writeln!(out);
+        writeln!(out, "removed packages:");
+        for package in &what_if.removed {
+            writeln!(out, "  {}:{}", package.name, package.version);
         }
     }
 
-    // Walk the topo graph in reverse, so that we visit each package before any
-    // dependencies.
-    for &pkgidx in graph.topo_index.iter().rev() {
-        let package = &graph.nodes[pkgidx];
-        let policy = policy.get(package.name, &package.version);
+    writeln!(out);
+    writeln!(out, "estimated audit cost: {} lines", what_if.total_lines);
 
-        if let Some(c) = policy.and_then(|p| p.criteria.as_ref()) {
-            // If we specify a policy on ourselves, override any requirements we've
-            // had placed on us by reverse-dependencies.
-            requirements[pkgidx] = criteria_mapper.criteria_from_list(c);
-        } else if package.is_root {
-            // If this is a root crate, it will require at least
-            // `DEFAULT_POLICY_CRITERIA` by default, unless overridden.
-            requirements[pkgidx].unioned_with(
-                &criteria_mapper.criteria_from_list([format::DEFAULT_POLICY_CRITERIA]),
-            );
+    if !what_if.warnings.is_empty() {
+        writeln!(out);
+        for warning in &what_if.warnings {
+            writeln!(out, "WARNING: {warning}");
         }
-        let normal_criteria = requirements[pkgidx].clone();
+    }
 
-        // For each dependency, elaborate the dependency criteria from the configured policy and add it to the dependency requirements.
-        for &depidx in &package.normal_and_build_deps {
-            let dep_package = &graph.nodes[depidx];
-            let dependency_criteria = policy
-                .and_then(|policy| policy.dependency_criteria.get(dep_package.name))
-                .map(|criteria| criteria_mapper.criteria_from_list(criteria));
-            requirements[depidx]
-                .unioned_with(dependency_criteria.as_ref().unwrap_or(&normal_criteria));
+    Ok(())
+}
+
+/// Print the report for each vetted feature profile, followed by a summary
+/// of which profiles failed (if any).
+pub fn print_profiles_human(
+    out: &Arc<dyn Out>,
+    cfg: &Config,
+    profiles: &[ProfileReport<'_>],
+) -> Result<(), std::io::Error> {
+    for (idx, profile) in profiles.iter().enumerate() {
+        if idx != 0 {
+            writeln!(out);
         }
+        writeln!(out, "profile `{}`:", profile.name);
+        profile
+            .report
+            .print_human(out, cfg, profile.suggest.as_ref())?;
     }
 
-    requirements
+    let failed: Vec<_> = profiles
+        .iter()
+        .filter(|profile| profile.report.has_errors())
+        .map(|profile| profile.name)
+        .collect();
+    if !failed.is_empty() {
+        writeln!(out);
+        writeln!(
+            out,
+            "Vetting Failed for {} of {} profiles: {}",
+            failed.len(),
+            profiles.len(),
+            failed.join(", ")
+        );
+    }
+    Ok(())
 }
 
+pub fn print_profiles_json(
+    out: &Arc<dyn Out>,
+    profiles: &[ProfileReport<'_>],
+) -> Result<(), miette::Report> {
+    let result = JsonProfilesReport {
+        conclusion: if profiles.iter().any(|profile| profile.report.has_errors()) {
+            JsonProfilesConclusion::Fail
+        } else {
+            JsonProfilesConclusion::Success
+        },
+        profiles: profiles
+            .iter()
+            .map(|profile| {
+                (
+                    profile.name.to_owned(),
+                    profile.report.to_json(profile.suggest.as_ref()),
+                )
+            })
+            .collect(),
+    };
+    serde_json::to_writer_pretty(&**out, &result).into_diagnostic()?;
+    Ok(())
+}
+
+/// The violation conflicts which were resolved for each package.
+type ResolvedConflicts = Vec<(PackageIdx, Vec<ResolvedViolationConflict>)>;
+
 fn resolve_audits(
     graph: &DepGraph<'_>,
     store: &Store,
     criteria_mapper: &CriteriaMapper,
     requirements: &[CriteriaSet],
-) -> (Vec<Option<ResolveResult>>, Conclusion) {
+) -> (Vec<Option<ResolveResult>>, Conclusion, ResolvedConflicts) {
     let _resolve_audits = trace_span!("resolve_audits").entered();
     let mut violations = Vec::new();
+    let mut resolved_conflicts = Vec::new();
     let mut failures = Vec::new();
     let mut vetted_with_exemptions = Vec::new();
     let mut vetted_partially = Vec::new();
@@ -977,6 +2463,9 @@ fn resolve_audits(
             let audit_graph = AuditGraph::build(store, criteria_mapper, package.name, None)
                 .map_err(|v| violations.push((pkgidx, v)))
                 .ok()?;
+            if !audit_graph.resolved_conflicts.is_empty() {
+                resolved_conflicts.push((pkgidx, audit_graph.resolved_conflicts.clone()));
+            }
 
             // NOTE: We currently always compute all search results even if we
             // only need those in `req_criteria` because some later passes using
@@ -1044,7 +2533,7 @@ fn resolve_audits(
         })
     };
 
-    (results, conclusion)
+    (results, conclusion, resolved_conflicts)
 }
 
 impl<'a> AuditGraph<'a> {
@@ -1282,6 +2771,9 @@ impl<'a> AuditGraph<'a> {
         // Exempted entries are equivalent to full-audits
         if let Some(alloweds) = exemptions {
             for (exemption_index, allowed) in alloweds.iter().enumerate() {
+                if !store.exemption_applies(package, allowed) {
+                    continue;
+                }
                 let from_ver = None;
                 let to_ver = Some(&allowed.version);
                 let criteria = criteria_mapper.criteria_from_list(&allowed.criteria);
@@ -1303,8 +2795,72 @@ impl<'a> AuditGraph<'a> {
             }
         }
 
+        // Decide how to resolve a conflict between a violation and an audit or
+        // exemption, returning `None` if it can't be resolved. Local entries
+        // take precedence over those from any import.
+        let priority = |source: &Option<ImportName>| match source {
+            None => (true, 0),
+            Some(import_name) => (
+                false,
+                store
+                    .config
+                    .imports
+                    .get(import_name)
+                    .map_or(0, |import| import.priority),
+            ),
+        };
+        let resolve_conflict = |violation_source: &Option<ImportName>,
+                                audit_source: &Option<ImportName>| {
+            match store.config.violation_conflicts {
+                ViolationConflictPolicy::Fail => None,
+                ViolationConflictPolicy::PreferViolation => {
+                    Some(ViolationConflictResolution::IgnoredAudit)
+                }
+                ViolationConflictPolicy::Warn => {
+                    Some(ViolationConflictResolution::IgnoredViolation)
+                }
+                ViolationConflictPolicy::PreferHigherPriority => {
+                    match priority(violation_source).cmp(&priority(audit_source)) {
+                        std::cmp::Ordering::Greater => {
+                            Some(ViolationConflictResolution::IgnoredAudit)
+                        }
+                        std::cmp::Ordering::Less => {
+                            Some(ViolationConflictResolution::IgnoredViolation)
+                        }
+                        std::cmp::Ordering::Equal => None,
+                    }
+                }
+            }
+        };
+
         // Reject forbidden packages (violations)
         let mut violations = Vec::new();
+        let mut resolved_conflicts = Vec::new();
+        let mut ignored_origins = Vec::new();
+        let mut add_conflict = |conflict: ViolationConflict, origin: DeltaEdgeOrigin| {
+            let (violation_source, audit_source) = match &conflict {
+                ViolationConflict::UnauditedConflict {
+                    violation_source, ..
+                } => (violation_source, &None),
+                ViolationConflict::AuditConflict {
+                    violation_source,
+                    audit_source,
+                    ..
+                } => (violation_source, audit_source),
+            };
+            match resolve_conflict(violation_source, audit_source) {
+                None => violations.push(conflict),
+                Some(resolution) => {
+                    if resolution == ViolationConflictResolution::IgnoredAudit {
+                        ignored_origins.push(origin);
+                    }
+                    resolved_conflicts.push(ResolvedViolationConflict {
+                        conflict,
+                        resolution,
+                    });
+                }
+            }
+        };
         for (violation_source, violation_entry) in &violation_nodes {
             // Ok this is kind of weird. We want to reject any audits which contain any of these criteria.
             // Normally we would slap all the criteria in this entry into a set and do some kind of set
@@ -1339,7 +2895,7 @@ impl<'a> AuditGraph<'a> {
 
             // Note if this entry conflicts with any exemptions
             if let Some(alloweds) = exemptions {
-                for allowed in alloweds {
+                for (exemption_index, allowed) in alloweds.iter().enumerate() {
                     let audit_criteria = criteria_mapper.criteria_from_list(&allowed.criteria);
                     let has_violation = violation_criterias
                         .iter()
@@ -1348,17 +2904,20 @@ impl<'a> AuditGraph<'a> {
                         continue;
                     }
                     if violation_range.matches(&allowed.version) {
-                        violations.push(ViolationConflict::UnauditedConflict {
-                            violation_source: violation_source.clone(),
-                            violation: (*violation_entry).clone(),
-                            exemptions: allowed.clone(),
-                        });
+                        add_conflict(
+                            ViolationConflict::UnauditedConflict {
+                                violation_source: violation_source.clone(),
+                                violation: (*violation_entry).clone(),
+                                exemptions: allowed.clone(),
+                            },
+                            DeltaEdgeOrigin::Exemption { exemption_index },
+                        );
                     }
                 }
             }
 
             // Note if this entry conflicts with any audits
-            for (namespace, _origin, audit) in all_audits.clone() {
+            for (namespace, origin, audit) in all_audits.clone() {
                 let audit_criteria = criteria_mapper.criteria_from_list(&audit.criteria);
                 let has_violation = violation_criterias
                     .iter()
@@ -1366,30 +2925,26 @@ impl<'a> AuditGraph<'a> {
                 if !has_violation {
                     continue;
                 }
-                match &audit.kind {
-                    AuditKind::Full { version, .. } => {
-                        if violation_range.matches(version) {
-                            violations.push(ViolationConflict::AuditConflict {
-                                violation_source: violation_source.clone(),
-                                violation: (*violation_entry).clone(),
-                                audit_source: namespace.clone(),
-                                audit: audit.clone(),
-                            });
-                        }
-                    }
+                let conflicts = match &audit.kind {
+                    AuditKind::Full { version, .. } => violation_range.matches(version),
                     AuditKind::Delta { from, to, .. } => {
-                        if violation_range.matches(from) || violation_range.matches(to) {
-                            violations.push(ViolationConflict::AuditConflict {
-                                violation_source: violation_source.clone(),
-                                violation: (*violation_entry).clone(),
-                                audit_source: namespace.clone(),
-                                audit: audit.clone(),
-                            });
-                        }
+                        violation_range.matches(from) || violation_range.matches(to)
                     }
                     AuditKind::Violation { .. } => {
                         // don't care
+                        false
                     }
+                };
+                if conflicts {
+                    add_conflict(
+                        ViolationConflict::AuditConflict {
+                            violation_source: violation_source.clone(),
+                            violation: (*violation_entry).clone(),
+                            audit_source: namespace.clone(),
+                            audit: audit.clone(),
+                        },
+                        origin,
+                    );
                 }
             }
         }
@@ -1399,9 +2954,21 @@ impl<'a> AuditGraph<'a> {
             return Err(violations);
         }
 
+        // Remove the edges for any audits or exemptions which were ignored to
+        // resolve a conflict.
+        if !ignored_origins.is_empty() {
+            for edges in forward_audits
+                .values_mut()
+                .chain(backward_audits.values_mut())
+            {
+                edges.retain(|edge| !ignored_origins.contains(&edge.origin));
+            }
+        }
+
         Ok(AuditGraph {
             forward_audits,
             backward_audits,
+            resolved_conflicts,
         })
     }
 
@@ -1759,6 +3326,7 @@ impl<'a> ResolveReport<'a> {
                                 result.search_results[criteria_idx].as_ref().unwrap_err()
                             }),
                         &warnings,
+                        store.config.diff_scope(package.name),
                     )
                     .await
                     else {
@@ -1889,6 +3457,7 @@ impl<'a> ResolveReport<'a> {
                                 target_version,
                                 failures.iter(),
                                 &warnings,
+                                store.config.diff_scope(package.name),
                             )
                             .await?;
 
@@ -1911,6 +3480,38 @@ impl<'a> ResolveReport<'a> {
                     .collect();
                     registry_suggestion.sort_by_key(|suggestion| suggestion.diff.diffstat.count());
 
+                    // Look in the audit index for peers which have audited this
+                    // version before, even if the registry can't be fetched.
+                    let mut index_hint: Vec<IndexHint> = Vec::new();
+                    if !cfg.cli.no_registry_suggestions {
+                        for (name, audit) in cache.search_audit_index(package.name) {
+                            let AuditKind::Full { version } = &audit.kind else {
+                                continue;
+                            };
+                            if version != &package.version
+                                || store.config.imports.contains_key(&name)
+                                || registry_suggestion.iter().any(|s| s.name == name)
+                                || index_hint.iter().any(|hint| hint.name == name)
+                            {
+                                continue;
+                            }
+                            // The index may predate changes to our criteria.
+                            let criteria = self.criteria_mapper.criteria_from_list(
+                                audit.criteria.iter().filter(|criteria| {
+                                    self.criteria_mapper
+                                        .all_criteria_names()
+                                        .any(|name| name == criteria.as_str())
+                                }),
+                            );
+                            if criteria.contains(&audit_failure.criteria_failures) {
+                                index_hint.push(IndexHint {
+                                    name,
+                                    version: version.clone(),
+                                });
+                            }
+                        }
+                    }
+
                     extra_suggested_diff
                         .into_iter()
                         .map(|suggested_diff| SuggestItem {
@@ -1922,6 +3523,7 @@ impl<'a> ResolveReport<'a> {
                             trust_hint: None,
                             is_sole_publisher: false,
                             registry_suggestion: vec![],
+                            index_hint: vec![],
                         })
                         .chain([SuggestItem {
                             package: failure_idx,
@@ -1932,6 +3534,7 @@ impl<'a> ResolveReport<'a> {
                             trust_hint,
                             is_sole_publisher,
                             registry_suggestion,
+                            index_hint,
                         }])
                         .collect()
                 },
@@ -1985,18 +3588,194 @@ impl<'a> ResolveReport<'a> {
                 .collect::<Vec<_>>()
                 .join(", ");
 
-            suggestions_by_criteria
-                .entry(criteria_names)
-                .or_default()
-                .push(s);
-        }
+            suggestions_by_criteria
+                .entry(criteria_names)
+                .or_default()
+                .push(s);
+        }
+
+        Ok(Some(Suggest {
+            suggestions,
+            suggestions_by_criteria,
+            audited_versions: Vec::new(),
+            total_lines,
+            warnings: warnings.into_inner(),
+        }))
+    }
+
+    /// Find already-audited versions which failing packages could be updated
+    /// to instead of being audited, recording them in `suggest`.
+    ///
+    /// Only crates.io versions which satisfy every semver requirement on the
+    /// package in the dependency graph are considered, and the nearest newer
+    /// version is preferred over the nearest older one. The dependencies of
+    /// the new version aren't checked, and may need audits of their own.
+    pub fn suggest_audited_versions(
+        &self,
+        cfg: &Config,
+        store: &Store,
+        network: Option<&Network>,
+        suggest: &mut Suggest,
+    ) -> Result<(), SuggestError> {
+        let Conclusion::FailForVet(fail) = &self.conclusion else {
+            return Ok(());
+        };
+        // The index is needed to tell which versions are yanked, and what
+        // they depend on.
+        let Some(network) = network else {
+            suggest.warnings.push(
+                "already-audited versions can't be suggested while offline, as the crates.io index can't be read".to_owned(),
+            );
+            return Ok(());
+        };
+
+        let cache = Cache::acquire(cfg)?;
+        let package_by_id: FastMap<&PackageId, &cargo_metadata::Package> = cfg
+            .metadata
+            .packages
+            .iter()
+            .map(|package| (&package.id, package))
+            .collect();
+        let features_by_id: FastMap<&PackageId, SortedSet<String>> = cfg
+            .metadata
+            .resolve
+            .iter()
+            .flat_map(|resolve| &resolve.nodes)
+            .map(|node| (&node.id, node.features.iter().cloned().collect()))
+            .collect();
+        // Whether a dependency of a candidate version is satisfied by a
+        // version which is already in the graph, so that switching to the
+        // candidate doesn't pull in anything new.
+        let in_graph = |dep: &crates_index::Dependency| {
+            semver::VersionReq::parse(dep.requirement()).map_or(false, |req| {
+                package_by_id.values().any(|package| {
+                    package.name == dep.crate_name()
+                        && package.is_crates_io()
+                        && req.matches(&package.version)
+                })
+            })
+        };
+
+        let warnings = RefCell::new(Vec::new());
+        let mut audited_versions = tokio::runtime::Handle::current()
+            .block_on(join_all(fail.failures.iter().map(|&(failure_idx, _)| {
+                let cache = &cache;
+                let package_by_id = &package_by_id;
+                let features_by_id = &features_by_id;
+                let in_graph = &in_graph;
+                let warnings = &warnings;
+                async move {
+                    let node = &self.graph.nodes[failure_idx];
+                    if node.version.git_rev.is_some()
+                        || !package_by_id[node.package_id].is_crates_io()
+                    {
+                        return None;
+                    }
+
+                    // Collect the requirements which selected this version from
+                    // each of the packages depending on it.
+                    let reqs: Vec<&semver::VersionReq> = node
+                        .reverse_deps
+                        .iter()
+                        .flat_map(|&parent| {
+                            &package_by_id[self.graph.nodes[parent].package_id].dependencies
+                        })
+                        .filter(|dep| {
+                            dep.name == node.name && dep.req.matches(&node.version.semver)
+                        })
+                        .map(|dep| &dep.req)
+                        .collect();
+
+                    let index_crate = match cache.get_index_crate(network, node.name).await {
+                        Ok(index_crate) => index_crate,
+                        Err(error) => {
+                            warnings
+                                .borrow_mut()
+                                .push(format!("error fetching versions of {}: {error}", node.name));
+                            return None;
+                        }
+                    };
+
+                    let audit_graph =
+                        AuditGraph::build(store, &self.criteria_mapper, node.name, None).ok()?;
+                    let required = &self.requirements[failure_idx];
+                    let is_audited = |version: &semver::Version| {
+                        let version = VetVersion {
+                            semver: version.clone(),
+                            git_rev: None,
+                        };
+                        self.criteria_mapper
+                            .minimal_indices(required)
+                            .all(|criteria_idx| {
+                                audit_graph
+                                    .search(criteria_idx, &version, SearchMode::PreferExemptions)
+                                    .is_ok()
+                            })
+                    };
+
+                    // Only suggest versions which don't depend on any packages
+                    // which aren't already in the graph, given the features
+                    // the package is currently built with.
+                    let features = &features_by_id[node.package_id];
+                    let has_new_deps = |index_version: &crates_index::Version| {
+                        let (enabled_deps, _) = enabled_dependencies(index_version, features);
+                        index_version.dependencies().iter().any(|dep| {
+                            dep.kind() != crates_index::DependencyKind::Dev
+                                && (!dep.is_optional() || enabled_deps.contains(dep.name()))
+                                && !in_graph(dep)
+                        })
+                    };
+
+                    let current = &node.version.semver;
+                    let candidates: Vec<_> = index_crate
+                        .versions()
+                        .iter()
+                        .filter(|v| !v.is_yanked())
+                        .filter_map(|v| Some((v.version().parse::<semver::Version>().ok()?, v)))
+                        .filter(|(v, _)| v != current && reqs.iter().all(|req| req.matches(v)))
+                        .filter(|(v, index_version)| is_audited(v) && !has_new_deps(index_version))
+                        .map(|(v, _)| v)
+                        .collect();
+                    let newer = candidates.iter().filter(|&v| v > current).min();
+                    let older = candidates.iter().filter(|&v| v < current).max();
+                    newer.or(older).map(|version| AuditedVersion {
+                        package: failure_idx,
+                        version: version.clone(),
+                    })
+                }
+            })))
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        audited_versions.sort_by_key(|a| {
+            let node = &self.graph.nodes[a.package];
+            (node.name, &node.version)
+        });
+
+        suggest.audited_versions = audited_versions;
+        suggest.warnings.extend(warnings.into_inner());
+        Ok(())
+    }
 
-        Ok(Some(Suggest {
-            suggestions,
-            suggestions_by_criteria,
-            total_lines,
-            warnings: warnings.into_inner(),
-        }))
+    /// The `cargo update` command which would switch a failing package to an
+    /// already-audited version.
+    fn audited_version_command(&self, audited: &AuditedVersion) -> String {
+        let node = &self.graph.nodes[audited.package];
+        // Only disambiguate the package spec if there are multiple versions
+        // of the package in the graph.
+        let spec = if self
+            .graph
+            .nodes
+            .iter()
+            .filter(|other| other.name == node.name)
+            .count()
+            > 1
+        {
+            format!("{}@{}", node.name, node.version.semver)
+        } else {
+            node.name.to_owned()
+        };
+        format!("cargo update -p {spec} --precise {}", audited.version)
     }
 
     /// Given a package name and a delta to be certified, determine the set of
@@ -2062,6 +3841,9 @@ impl<'a> ResolveReport<'a> {
         cfg: &Config,
         suggest: Option<&Suggest>,
     ) -> Result<(), std::io::Error> {
+        if !self.resolved_conflicts.is_empty() {
+            print_resolved_violation_conflicts(out, self)?;
+        }
         match &self.conclusion {
             Conclusion::Success(res) => res.print_human(out, self, cfg),
             Conclusion::FailForViolationConflict(res) => res.print_human(out, self, cfg),
@@ -2091,7 +3873,13 @@ impl<'a> ResolveReport<'a> {
         out: &Arc<dyn Out>,
         suggest: Option<&Suggest>,
     ) -> Result<(), miette::Report> {
-        let result = JsonReport {
+        let result = self.to_json(suggest);
+        serde_json::to_writer_pretty(&**out, &result).into_diagnostic()?;
+        Ok(())
+    }
+
+    fn to_json(&self, suggest: Option<&Suggest>) -> JsonReport {
+        JsonReport {
             conclusion: match &self.conclusion {
                 Conclusion::Success(success) => {
                     let json_package = |pkgidx: &PackageIdx| {
@@ -2099,6 +3887,7 @@ impl<'a> ResolveReport<'a> {
                         JsonPackage {
                             name: package.name.to_owned(),
                             version: package.version.clone(),
+                            targets: package.targets.clone(),
                         }
                     };
                     JsonReportConclusion::Success(JsonReportSuccess {
@@ -2159,6 +3948,8 @@ impl<'a> ResolveReport<'a> {
                                         .criteria_names(&audit_fail.criteria_failures)
                                         .map(|s| s.to_owned())
                                         .collect(),
+                                    build_only: package.is_build_only,
+                                    targets: package.targets.clone(),
                                 }
                             })
                             .collect(),
@@ -2178,17 +3969,377 @@ impl<'a> ResolveReport<'a> {
                                     )
                                 })
                                 .collect(),
+                            audited_versions: suggest
+                                .audited_versions
+                                .iter()
+                                .map(|audited| {
+                                    let package = &self.graph.nodes[audited.package];
+                                    JsonAuditedVersion {
+                                        name: package.name.to_owned(),
+                                        version: package.version.clone(),
+                                        audited_version: audited.version.clone(),
+                                        command: self.audited_version_command(audited),
+                                    }
+                                })
+                                .collect(),
                             total_lines: suggest.total_lines,
                         }),
                     })
                 }
             },
-        };
+            resolved_violation_conflicts: self
+                .resolved_conflicts
+                .iter()
+                .map(|(pkgidx, resolved_conflicts)| {
+                    let package = &self.graph.nodes[*pkgidx];
+                    let key = format!("{}:{}", package.name, package.version);
+                    (key, resolved_conflicts.clone())
+                })
+                .collect(),
+        }
+    }
 
-        serde_json::to_writer_pretty(&**out, &result).into_diagnostic()?;
+    /// Explain how the given package was vetted, by re-running the audit
+    /// graph search for each of its required criteria and describing every
+    /// entry on the winning path.
+    pub fn explain(
+        &self,
+        store: &Store,
+        package: PackageStr<'_>,
+        version: &VetVersion,
+    ) -> Result<JsonExplain, ExplainError> {
+        let pkgidx = self
+            .graph
+            .nodes
+            .iter()
+            .position(|node| node.name == package && &node.version == version)
+            .ok_or_else(|| ExplainError::NotInGraph {
+                package: package.to_owned(),
+                version: version.clone(),
+            })?;
+        let node = &self.graph.nodes[pkgidx];
+        if !node.is_third_party {
+            return Err(ExplainError::FirstParty {
+                package: package.to_owned(),
+                version: version.clone(),
+            });
+        }
 
-        Ok(())
+        let audit_graph =
+            AuditGraph::build(store, &self.criteria_mapper, package, None).map_err(|_| {
+                ExplainError::ViolationConflict {
+                    package: package.to_owned(),
+                    version: version.clone(),
+                }
+            })?;
+
+        let required = &self.requirements[pkgidx];
+        let criteria = self
+            .criteria_mapper
+            .minimal_indices(required)
+            .map(|criteria_idx| {
+                let search_result =
+                    audit_graph.search(criteria_idx, version, SearchMode::PreferExemptions);
+                JsonExplainCriteria {
+                    criteria: self.criteria_mapper.criteria_name(criteria_idx).to_owned(),
+                    satisfied: search_result.is_ok(),
+                    // The search walks backwards from the package's version,
+                    // so reverse the path to start from the root.
+                    path: search_result
+                        .unwrap_or_default()
+                        .iter()
+                        .rev()
+                        .map(|origin| {
+                            explain_edge(
+                                store,
+                                &self.criteria_mapper,
+                                package,
+                                criteria_idx,
+                                origin,
+                            )
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        // Walk up the reverse dependencies through third-party crates to find
+        // the first-party crates which the requirements came from.
+        let mut required_by = SortedSet::new();
+        let mut visited = FastSet::new();
+        let mut queue = vec![pkgidx];
+        while let Some(idx) = queue.pop() {
+            if !visited.insert(idx) {
+                continue;
+            }
+            for &parent in &self.graph.nodes[idx].reverse_deps {
+                let parent_node = &self.graph.nodes[parent];
+                if parent_node.is_third_party {
+                    queue.push(parent);
+                } else {
+                    required_by.insert(parent_node.name.to_owned());
+                }
+            }
+        }
+
+        Ok(JsonExplain {
+            name: package.to_owned(),
+            version: version.clone(),
+            required_criteria: self
+                .criteria_mapper
+                .criteria_names(required)
+                .map(|s| s.to_owned())
+                .collect(),
+            required_by: required_by.into_iter().collect(),
+            criteria,
+        })
+    }
+}
+
+/// Describe the store entry which a single edge of an audit graph search
+/// result came from.
+fn explain_edge(
+    store: &Store,
+    criteria_mapper: &CriteriaMapper,
+    package: PackageStr<'_>,
+    criteria_idx: usize,
+    origin: &DeltaEdgeOrigin,
+) -> JsonExplainEdge {
+    fn strings<T: ToString>(list: &[Spanned<T>]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+    let imported = |import_index: usize| {
+        store
+            .imported_audits()
+            .iter()
+            .nth(import_index)
+            .expect("invalid import index")
+    };
+    let publisher = |publisher_index: usize| &store.publishers()[package][publisher_index];
+    let edge = |kind, from: Option<&VetVersion>, to: &VetVersion| JsonExplainEdge {
+        kind,
+        from: from.cloned(),
+        to: to.clone(),
+        import: None,
+        who: vec![],
+        criteria: vec![],
+        publisher: None,
+        published: None,
+        start: None,
+        end: None,
+        notes: None,
+        aggregated_from: vec![],
+    };
+
+    match origin {
+        DeltaEdgeOrigin::StoredLocalAudit { audit_index, .. }
+        | DeltaEdgeOrigin::ImportedAudit { audit_index, .. } => {
+            let (import, audits_file) = match origin {
+                DeltaEdgeOrigin::ImportedAudit { import_index, .. } => {
+                    let (name, audits_file) = imported(*import_index);
+                    (Some(name.clone()), audits_file)
+                }
+                _ => (None, &store.audits),
+            };
+            let audit = &audits_file.audits[package][*audit_index];
+            let (from, to) = match &audit.kind {
+                AuditKind::Full { version } => (None, version),
+                AuditKind::Delta { from, to } => (Some(from), to),
+                AuditKind::Violation { .. } => unreachable!("violations aren't audit graph edges"),
+            };
+            JsonExplainEdge {
+                import,
+                who: strings(&audit.who),
+                criteria: strings(&audit.criteria),
+                notes: audit.notes.clone(),
+                aggregated_from: strings(&audit.aggregated_from),
+                ..edge(JsonExplainEdgeKind::Audit, from, to)
+            }
+        }
+        DeltaEdgeOrigin::WildcardAudit {
+            import_index,
+            audit_index,
+            publisher_index,
+        } => {
+            let (import, audits_file) = match import_index {
+                Some(import_index) => {
+                    let (name, audits_file) = imported(*import_index);
+                    (Some(name.clone()), audits_file)
+                }
+                None => (None, &store.audits),
+            };
+            let audit = &audits_file.wildcard_audits[package][*audit_index];
+            let publisher = publisher(*publisher_index);
+            JsonExplainEdge {
+                import,
+                who: strings(&audit.who),
+                criteria: strings(&audit.criteria),
+                publisher: Some(publisher.user_login.clone()),
+                published: Some(publisher.when),
+                start: Some(*audit.start),
+                end: Some(*audit.end),
+                notes: audit.notes.clone(),
+                aggregated_from: strings(&audit.aggregated_from),
+                ..edge(JsonExplainEdgeKind::WildcardAudit, None, &publisher.version)
+            }
+        }
+        DeltaEdgeOrigin::Trusted { publisher_index } => {
+            let publisher = publisher(*publisher_index);
+            // The origin doesn't record which trusted entry was used, so find
+            // the first one which applies to this publisher and criteria,
+            // preferring our own entries over imported ones.
+            let trusted = std::iter::once((None, &store.audits))
+                .chain(
+                    store
+                        .imported_audits()
+                        .iter()
+                        .map(|(name, audits_file)| (Some(name), audits_file)),
+                )
+                .find_map(|(import, audits_file)| {
+                    let entry = audits_file.trusted.get(package)?.iter().find(|entry| {
+                        entry.user_id == publisher.user_id
+                            && *entry.start <= publisher.when
+                            && publisher.when < *entry.end
+                            && criteria_mapper
+                                .criteria_from_list(&entry.criteria)
+                                .has_criteria(criteria_idx)
+                    })?;
+                    Some((import.cloned(), entry))
+                });
+            let (import, trusted) = match trusted {
+                Some((import, trusted)) => (import, Some(trusted)),
+                None => (None, None),
+            };
+            let mut result = JsonExplainEdge {
+                import,
+                publisher: Some(publisher.user_login.clone()),
+                published: Some(publisher.when),
+                ..edge(JsonExplainEdgeKind::Trusted, None, &publisher.version)
+            };
+            if let Some(trusted) = trusted {
+                result.criteria = strings(&trusted.criteria);
+                result.start = Some(*trusted.start);
+                result.end = Some(*trusted.end);
+                result.notes = trusted.notes.clone();
+                result.aggregated_from = strings(&trusted.aggregated_from);
+            }
+            result
+        }
+        DeltaEdgeOrigin::Exemption { exemption_index } => {
+            let exemption = &store.config.exemptions[package][*exemption_index];
+            JsonExplainEdge {
+                criteria: strings(&exemption.criteria),
+                notes: exemption.notes.clone(),
+                ..edge(JsonExplainEdgeKind::Exemption, None, &exemption.version)
+            }
+        }
+        DeltaEdgeOrigin::Unpublished { unpublished_index } => {
+            let unpublished = &store.unpublished()[package][*unpublished_index];
+            edge(
+                JsonExplainEdgeKind::Unpublished,
+                Some(&unpublished.audited_as),
+                &unpublished.version,
+            )
+        }
+        DeltaEdgeOrigin::FreshExemption { version } => {
+            edge(JsonExplainEdgeKind::FreshExemption, None, version)
+        }
+    }
+}
+
+/// Print the human-readable output of `cargo vet explain`.
+pub fn print_explain_human(
+    out: &Arc<dyn Out>,
+    explain: &JsonExplain,
+) -> Result<(), std::io::Error> {
+    writeln!(out, "{}:{}", explain.name, explain.version);
+    writeln!(out);
+    if explain.required_criteria.is_empty() {
+        writeln!(out, "  no criteria are required for this package");
+        return Ok(());
+    }
+    writeln!(
+        out,
+        "  required criteria: {}",
+        explain.required_criteria.join(", ")
+    );
+    writeln!(
+        out,
+        "  required by: {}",
+        FormatShortList::new(explain.required_by.clone())
+    );
+
+    for criteria in &explain.criteria {
+        writeln!(out);
+        if !criteria.satisfied {
+            writeln!(out, "  {}: NOT satisfied", criteria.criteria);
+            continue;
+        }
+        writeln!(out, "  {}: satisfied by", criteria.criteria);
+        for edge in &criteria.path {
+            writeln!(out, "    {}", describe_explain_edge(edge));
+            if !edge.who.is_empty() {
+                writeln!(out, "      who: {}", edge.who.join(", "));
+            }
+            if !edge.criteria.is_empty() {
+                writeln!(out, "      criteria: {}", edge.criteria.join(", "));
+            }
+            if let (Some(start), Some(end)) = (edge.start, edge.end) {
+                writeln!(out, "      valid: {start} to {end}");
+            }
+            if !edge.aggregated_from.is_empty() {
+                writeln!(
+                    out,
+                    "      aggregated from: {}",
+                    edge.aggregated_from.join(", ")
+                );
+            }
+            if let Some(notes) = &edge.notes {
+                writeln!(out, "      notes: {notes}");
+            }
+        }
     }
+
+    Ok(())
+}
+
+/// Describe a single edge of an audit chain, along with the file or import
+/// its entry came from.
+fn describe_explain_edge(edge: &JsonExplainEdge) -> String {
+    let source = match (&edge.import, edge.kind) {
+        (Some(import), _) => format!("imported from {import}"),
+        (None, JsonExplainEdgeKind::Exemption | JsonExplainEdgeKind::FreshExemption) => {
+            "config.toml".to_owned()
+        }
+        (None, JsonExplainEdgeKind::Unpublished) => "imports.lock".to_owned(),
+        (None, _) => "audits.toml".to_owned(),
+    };
+    let publisher = edge.publisher.as_deref().unwrap_or("UNKNOWN");
+    let published = edge.published.map(|d| d.to_string()).unwrap_or_default();
+    let description = match (edge.kind, &edge.from) {
+        (JsonExplainEdgeKind::Audit, None) => format!("full audit of {}", edge.to),
+        (JsonExplainEdgeKind::Audit, Some(from)) => {
+            format!("delta audit {from} -> {}", edge.to)
+        }
+        (JsonExplainEdgeKind::WildcardAudit, _) => format!(
+            "wildcard audit of {} (published by {publisher} on {published})",
+            edge.to
+        ),
+        (JsonExplainEdgeKind::Trusted, _) => format!(
+            "trusted publisher {publisher} (published {} on {published})",
+            edge.to
+        ),
+        (JsonExplainEdgeKind::Exemption, _) => format!("exemption for {}", edge.to),
+        (JsonExplainEdgeKind::Unpublished, from) => format!(
+            "unpublished {} audited as {}",
+            edge.to,
+            from.as_ref().map(|v| v.to_string()).unwrap_or_default()
+        ),
+        (JsonExplainEdgeKind::FreshExemption, _) => {
+            format!("new exemption for {}", edge.to)
+        }
+    };
+    format!("{description} ({source})")
 }
 
 impl Success {
@@ -2336,6 +4487,22 @@ impl Suggest {
                             }),
                     );
                 }
+                for hint in &item.index_hint {
+                    writeln!(
+                        out,
+                        "      {} {} {} {}",
+                        dim.clone().apply_to("NOTE:"),
+                        dim.clone().apply_to(format_args!(
+                            "{} has audited {}, so",
+                            hint.name, hint.version
+                        )),
+                        dim.clone()
+                            .cyan()
+                            .bold()
+                            .apply_to(format_args!("cargo vet import {}", hint.name)),
+                        dim.clone().apply_to("would cover this"),
+                    );
+                }
                 if let Some(hint) = &item.trust_hint {
                     let trust = if hint.trusted_by.len() == 1 {
                         "trusts"
@@ -2376,6 +4543,28 @@ impl Suggest {
             writeln!(out);
         }
 
+        if !self.audited_versions.is_empty() {
+            writeln!(
+                out,
+                "already-audited versions which satisfy your dependency requirements:"
+            );
+            for audited in &self.audited_versions {
+                let package = &report.graph.nodes[audited.package];
+                writeln!(
+                    out,
+                    "    {}  {}",
+                    out.style()
+                        .cyan()
+                        .bold()
+                        .apply_to(report.audited_version_command(audited)),
+                    out.style()
+                        .dim()
+                        .apply_to(format_args!("(currently {})", package.version)),
+                );
+            }
+            writeln!(out);
+        }
+
         writeln!(out, "estimated audit backlog: {} lines", self.total_lines);
 
         if !self.warnings.is_empty() {
@@ -2447,86 +4636,145 @@ impl FailForViolationConflict {
             let package = &report.graph.nodes[*pkgidx];
             writeln!(out, "  {}:{}", package.name, package.version);
             for violation in violations {
-                match violation {
-                    ViolationConflict::UnauditedConflict {
-                        violation_source,
-                        violation,
-                        exemptions,
-                    } => {
-                        write!(out, "    the ");
-                        print_exemption(out, exemptions)?;
-                        write!(out, "    conflicts with ");
-                        print_entry(out, violation_source, violation)?;
-                    }
-                    ViolationConflict::AuditConflict {
-                        violation_source,
-                        violation,
-                        audit_source,
-                        audit,
-                    } => {
-                        write!(out, "    the ");
-                        print_entry(out, audit_source, audit)?;
-                        write!(out, "    conflicts with ");
-                        print_entry(out, violation_source, violation)?;
-                    }
-                }
+                print_violation_conflict(out, violation)?;
                 writeln!(out);
             }
         }
 
-        fn print_exemption(
-            out: &Arc<dyn Out>,
-            entry: &ExemptedDependency,
-        ) -> Result<(), std::io::Error> {
-            writeln!(out, "exemption {}", entry.version);
-            writeln!(out, "      criteria: {:?}", entry.criteria);
-            if let Some(notes) = &entry.notes {
-                writeln!(out, "      notes: {notes}");
-            }
-            Ok(())
-        }
+        Ok(())
+    }
+}
 
-        fn print_entry(
-            out: &Arc<dyn Out>,
-            source: &Option<ImportName>,
-            entry: &AuditEntry,
-        ) -> Result<(), std::io::Error> {
-            match source {
-                None => write!(out, "own "),
-                Some(name) => write!(out, "foreign ({name}) "),
-            }
-            match &entry.kind {
-                AuditKind::Full { version, .. } => {
-                    writeln!(out, "audit {version}");
+fn print_resolved_violation_conflicts(
+    out: &Arc<dyn Out>,
+    report: &ResolveReport<'_>,
+) -> Result<(), std::io::Error> {
+    writeln!(
+        out,
+        "Violation conflicts resolved by the violation-conflicts policy:"
+    );
+
+    for (pkgidx, resolved_conflicts) in &report.resolved_conflicts {
+        let package = &report.graph.nodes[*pkgidx];
+        writeln!(out, "  {}:{}", package.name, package.version);
+        for resolved in resolved_conflicts {
+            print_violation_conflict(out, &resolved.conflict)?;
+            match (&resolved.resolution, &resolved.conflict) {
+                (ViolationConflictResolution::IgnoredViolation, _) => {
+                    writeln!(out, "    (the violation was ignored)");
                 }
-                AuditKind::Delta { from, to, .. } => {
-                    writeln!(out, "audit {from} -> {to}");
+                (
+                    ViolationConflictResolution::IgnoredAudit,
+                    ViolationConflict::UnauditedConflict { .. },
+                ) => {
+                    writeln!(out, "    (the exemption was ignored)");
                 }
-                AuditKind::Violation { violation } => {
-                    writeln!(out, "violation against {violation}");
+                (
+                    ViolationConflictResolution::IgnoredAudit,
+                    ViolationConflict::AuditConflict { .. },
+                ) => {
+                    writeln!(out, "    (the audit was ignored)");
                 }
             }
-            writeln!(out, "      criteria: {:?}", entry.criteria);
-            for (idx, who) in entry.who.iter().enumerate() {
-                if idx == 0 {
-                    write!(out, "      who: {who}");
-                } else {
-                    write!(out, ", {who}");
-                }
+            writeln!(out);
+        }
+    }
+    Ok(())
+}
+
+fn print_violation_conflict(
+    out: &Arc<dyn Out>,
+    violation: &ViolationConflict,
+) -> Result<(), std::io::Error> {
+    match violation {
+        ViolationConflict::UnauditedConflict {
+            violation_source,
+            violation,
+            exemptions,
+        } => {
+            write!(out, "    the ");
+            print_exemption(out, exemptions)?;
+            write!(out, "    conflicts with ");
+            print_entry(out, violation_source, violation)?;
+        }
+        ViolationConflict::AuditConflict {
+            violation_source,
+            violation,
+            audit_source,
+            audit,
+        } => {
+            write!(out, "    the ");
+            print_entry(out, audit_source, audit)?;
+            write!(out, "    conflicts with ");
+            print_entry(out, violation_source, violation)?;
+        }
+    }
+
+    fn print_exemption(
+        out: &Arc<dyn Out>,
+        entry: &ExemptedDependency,
+    ) -> Result<(), std::io::Error> {
+        writeln!(out, "exemption {}", entry.version);
+        writeln!(out, "      criteria: {:?}", entry.criteria);
+        if let Some(notes) = &entry.notes {
+            writeln!(out, "      notes: {notes}");
+        }
+        Ok(())
+    }
+
+    fn print_entry(
+        out: &Arc<dyn Out>,
+        source: &Option<ImportName>,
+        entry: &AuditEntry,
+    ) -> Result<(), std::io::Error> {
+        match source {
+            None => write!(out, "own "),
+            Some(name) => write!(out, "foreign ({name}) "),
+        }
+        match &entry.kind {
+            AuditKind::Full { version, .. } => {
+                writeln!(out, "audit {version}");
             }
-            if !entry.who.is_empty() {
-                writeln!(out);
+            AuditKind::Delta { from, to, .. } => {
+                writeln!(out, "audit {from} -> {to}");
             }
-            if let Some(notes) = &entry.notes {
-                writeln!(out, "      notes: {notes}");
+            AuditKind::Violation { violation } => {
+                writeln!(out, "violation against {violation}");
             }
-            Ok(())
         }
-
+        writeln!(out, "      criteria: {:?}", entry.criteria);
+        for (idx, who) in entry.who.iter().enumerate() {
+            if idx == 0 {
+                write!(out, "      who: {who}");
+            } else {
+                write!(out, ", {who}");
+            }
+        }
+        if !entry.who.is_empty() {
+            writeln!(out);
+        }
+        if !entry.aggregated_from.is_empty() {
+            writeln!(
+                out,
+                "      aggregated from: {}",
+                entry
+                    .aggregated_from
+                    .iter()
+                    .map(|source| source.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        if let Some(notes) = &entry.notes {
+            writeln!(out, "      notes: {notes}");
+        }
         Ok(())
     }
+
+    Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn suggest_delta(
     metadata: &cargo_metadata::Metadata,
     network: Option<&Network>,
@@ -2535,6 +4783,7 @@ async fn suggest_delta(
     package_version: &VetVersion,
     failures: impl Iterator<Item = &SearchFailure>,
     warnings: &RefCell<Vec<String>>,
+    scope: DiffScope,
 ) -> Option<(DiffRecommendation, Option<DiffRecommendation>)> {
     // Fetch the set of known versions from crates.io so we know which versions
     // we'll have sources for.
@@ -2668,7 +4917,7 @@ async fn suggest_delta(
 
     let do_fetch_and_diffstat = |delta| async move {
         match cache
-            .fetch_and_diffstat_package(metadata, network, package_name, &delta)
+            .fetch_and_diffstat_package(metadata, network, package_name, &delta, scope)
             .await
         {
             Ok(diffstat) => Some(DiffRecommendation {
@@ -2841,34 +5090,92 @@ pub fn update_store(
     get_store_updates(cfg, store, mode).apply(store);
 }
 
+/// Like [`update_store`], but keeping the entries required by any of several
+/// dependency graphs, each given as the metadata and target platforms to
+/// build it from. This is used when vetting feature profiles, as each profile
+/// may enable packages which aren't in the default graph.
+pub fn update_store_for_graphs(
+    cfg: &Config,
+    store: &mut Store,
+    graphs: &[(&Metadata, &[TargetPlatform])],
+    mode: impl FnMut(PackageStr<'_>) -> UpdateMode,
+) {
+    get_store_updates_for_graphs(cfg, store, graphs, mode).apply(store);
+}
+
 /// The non-mutating core of `update_store` for use in non-mutating situations.
 pub(crate) fn get_store_updates(
     cfg: &Config,
     store: &Store,
-    mut mode: impl FnMut(PackageStr<'_>) -> UpdateMode,
+    mode: impl FnMut(PackageStr<'_>) -> UpdateMode,
 ) -> StoreUpdates {
     // Compute the set of required entries from the store for all packages in
-    // the dependency graph.
-    let graph = DepGraph::new(
-        &cfg.metadata,
-        cfg.cli.filter_graph.as_ref(),
-        Some(&store.config.policy),
-    );
+    // the dependency graph, on every platform the store is maintained for.
+    get_store_updates_for_graphs(
+        cfg,
+        store,
+        &[(&cfg.metadata, &store.store_target_platforms)],
+        mode,
+    )
+}
+
+fn get_store_updates_for_graphs(
+    cfg: &Config,
+    store: &Store,
+    graphs: &[(&Metadata, &[TargetPlatform])],
+    mut mode: impl FnMut(PackageStr<'_>) -> UpdateMode,
+) -> StoreUpdates {
+    let graphs: Vec<DepGraph<'_>> = graphs
+        .iter()
+        .map(|&(metadata, targets)| {
+            DepGraph::new(
+                metadata,
+                cfg.cli.filter_graph.as_ref(),
+                Some(&store.config.policy),
+                targets,
+            )
+        })
+        .collect();
     let criteria_mapper = CriteriaMapper::new(&store.audits.criteria);
-    let requirements = resolve_requirements(&graph, &store.config.policy, &criteria_mapper);
+    let requirements: Vec<Vec<CriteriaSet>> = graphs
+        .iter()
+        .map(|graph| resolve_requirements(graph, &store.config.policy, &criteria_mapper))
+        .collect();
 
+    // A package needs the union of the entries it needs in each graph, and
+    // is failing if it fails in any of them.
+    let package_names: SortedSet<PackageStr<'_>> = graphs
+        .iter()
+        .flat_map(|graph| graph.nodes.iter().map(|package| package.name))
+        .collect();
     let mut required_entries = SortedMap::new();
-    for package in &graph.nodes {
-        required_entries.entry(package.name).or_insert_with(|| {
-            resolve_package_required_entries(
-                &graph,
+    for package_name in package_names {
+        let search_mode = mode(package_name).search_mode;
+        let mut entries: Option<SortedMap<RequiredEntry, CriteriaSet>> = Some(SortedMap::new());
+        for (graph, requirements) in graphs.iter().zip(&requirements) {
+            let Some(entries_so_far) = &mut entries else {
+                break;
+            };
+            match resolve_package_required_entries(
+                graph,
                 &criteria_mapper,
-                &requirements,
+                requirements,
                 store,
-                package.name,
-                mode(package.name).search_mode,
-            )
-        });
+                package_name,
+                search_mode,
+            ) {
+                Some(graph_entries) => {
+                    for (entry, criteria) in graph_entries {
+                        entries_so_far
+                            .entry(entry)
+                            .or_insert_with(|| criteria_mapper.no_criteria())
+                            .unioned_with(&criteria);
+                    }
+                }
+                None => entries = None,
+            }
+        }
+        required_entries.insert(package_name, entries);
     }
 
     // Remove unused non-importable audits.
@@ -2902,6 +5209,15 @@ pub(crate) fn get_store_updates(
         unpublished: SortedMap::new(),
         publisher: SortedMap::new(),
         audits: SortedMap::new(),
+        extends: store.imports.extends.clone(),
+        // Remember rejected entries for as long as their import is configured.
+        rejected: store
+            .imports
+            .rejected
+            .iter()
+            .filter(|(import_name, _)| store.config.imports.contains_key(*import_name))
+            .map(|(import_name, rejected)| (import_name.clone(), rejected.clone()))
+            .collect(),
     };
 
     // Determine which live imports to keep in the imports.lock file.
@@ -3002,8 +5318,23 @@ pub(crate) fn get_store_updates(
                 })
                 .collect(),
 
-            // We never import trusted entries in imports.lock.
-            trusted: SortedMap::new(),
+            // Trusted entries are only recorded in imports.lock for imports
+            // which require review, to remember which have been accepted.
+            trusted: if store
+                .config
+                .imports
+                .get(import_name)
+                .map_or(false, |import| import.require_review)
+            {
+                live_audits_file
+                    .trusted
+                    .iter()
+                    .filter(|(pkgname, _)| required_entries.contains_key(&pkgname[..]))
+                    .map(|(pkgname, entries)| (pkgname.clone(), entries.clone()))
+                    .collect()
+            } else {
+                SortedMap::new()
+            },
         };
         new_imports
             .audits
@@ -3082,6 +5413,22 @@ pub(crate) fn get_store_updates(
         }
     }
 
+    // If the store governs multiple workspaces, find the workspace which needs
+    // a given exemption, if only one of them uses that version.
+    let exemption_workspace = |pkgname: PackageStr<'_>, version: &VetVersion| {
+        let mut using = cfg.workspaces.iter().filter(|(_, workspace)| {
+            graphs.iter().flat_map(|graph| &graph.nodes).any(|node| {
+                node.name == pkgname
+                    && &node.version == version
+                    && workspace.packages.contains(node.package_id)
+            })
+        });
+        match (using.next(), using.next()) {
+            (Some((name, _)), None) => Some(Spanned::from(name.clone())),
+            _ => None,
+        }
+    };
+
     let mut all_new_exemptions = SortedMap::new();
 
     // Enumerate existing exemptions to check for criteria changes.
@@ -3129,6 +5476,7 @@ pub(crate) fn get_store_updates(
                         .map(|n| n.to_owned().into())
                         .collect(),
                     suggest: true,
+                    workspace: exemption_workspace(pkgname, &entry.version),
                     notes: None,
                 });
                 useful_criteria = original_criteria;
@@ -3142,6 +5490,13 @@ pub(crate) fn get_store_updates(
                     .map(|n| n.to_owned().into())
                     .collect(),
                 suggest: entry.suggest,
+                // Only re-evaluate which workspace needs the exemption when
+                // pruning, to avoid unnecessary churn.
+                workspace: if prune_exemptions {
+                    exemption_workspace(pkgname, &entry.version)
+                } else {
+                    entry.workspace.clone()
+                },
                 notes: entry.notes.clone(),
             });
         }
@@ -3175,6 +5530,7 @@ pub(crate) fn get_store_updates(
                         .map(|n| n.to_owned().into())
                         .collect(),
                     suggest: true,
+                    workspace: exemption_workspace(pkgname, version),
                     notes: None,
                 });
         }
@@ -310,6 +310,9 @@ pub mod audit {
         #[serde(with = "string_or_vec")]
         #[serde(default)]
         pub aggregated_from: Vec<Spanned<String>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default)]
+        signature: Option<String>,
     }
 
     impl TryFrom<AuditEntryAll> for AuditEntry {
@@ -337,6 +340,7 @@ pub mod audit {
                 kind: kind?,
                 importable: val.importable.unwrap_or(true),
                 aggregated_from: val.aggregated_from,
+                signature: val.signature,
                 // By default, always read entries as non-fresh. The import code
                 // will set this flag to true for imported entries.
                 is_fresh_import: false,
@@ -367,6 +371,7 @@ pub mod audit {
                 violation,
                 importable: if val.importable { None } else { Some(false) },
                 aggregated_from: val.aggregated_from,
+                signature: val.signature,
             }
         }
     }
@@ -785,6 +790,7 @@ mod test {
                 audit_as_crates_io: None,
                 criteria: Some(vec!["long-criteria".to_owned().into()]),
                 dev_criteria: None,
+                build_criteria: None,
                 dependency_criteria: dc_long,
                 notes: Some("notes go here!".to_owned()),
             }),
@@ -795,6 +801,7 @@ mod test {
                 audit_as_crates_io: None,
                 criteria: Some(vec!["short-criteria".to_owned().into()]),
                 dev_criteria: None,
+                build_criteria: None,
                 dependency_criteria: dc_short,
                 notes: Some("notes go here!".to_owned()),
             }),
@@ -805,7 +812,16 @@ mod test {
                 cargo_vet: CargoVetConfig {
                     version: StoreVersion { major: 1, minor: 0 },
                 },
+                extends: Vec::new(),
                 default_criteria: get_default_criteria(),
+                targets: Vec::new(),
+                violation_conflicts: Default::default(),
+                profiles: SortedMap::new(),
+                workspaces: SortedMap::new(),
+                signing: None,
+                registry: None,
+                unpack: None,
+                diff: None,
                 imports: SortedMap::new(),
                 policy,
                 exemptions: SortedMap::new(),
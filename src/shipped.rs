@@ -0,0 +1,272 @@
+//! Working out which files of a package can end up in what it ships, so that
+//! diffs can focus on code which can actually run.
+//!
+//! A file is considered shipped unless it belongs only to a target which is
+//! never built into dependents (tests, benches and examples), is
+//! documentation, or is left out by the package's `include` and `exclude`
+//! lists. Those lists are only applied to git checkouts, as cargo has already
+//! applied them to published packages, and never to the modules of shipped
+//! targets. This is read from the package's own `Cargo.toml`, and is an
+//! estimate: a build script or macro could still read any file in the package.
+
+use std::path::{Component, Path, PathBuf};
+
+use serde::Deserialize;
+use tracing::info;
+
+/// Directories cargo discovers non-shipped targets in, along with those
+/// commonly used for documentation.
+const UNSHIPPED_DIRS: &[&str] = &["tests", "benches", "examples", "doc", "docs"];
+/// Extensions of documentation files.
+const DOC_EXTENSIONS: &[&str] = &["md", "markdown"];
+
+#[derive(Deserialize, Default)]
+struct Manifest {
+    #[serde(default)]
+    package: ManifestPackage,
+    lib: Option<ManifestTarget>,
+    #[serde(default)]
+    bin: Vec<ManifestTarget>,
+    #[serde(default)]
+    test: Vec<ManifestTarget>,
+    #[serde(default)]
+    bench: Vec<ManifestTarget>,
+    #[serde(default)]
+    example: Vec<ManifestTarget>,
+}
+
+#[derive(Deserialize, Default)]
+struct ManifestPackage {
+    /// Either the path of the build script, or `false` to disable it.
+    build: Option<toml::Value>,
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ManifestTarget {
+    path: Option<PathBuf>,
+}
+
+/// The files of a package which are shipped, read from its `Cargo.toml`.
+#[derive(Debug, Default)]
+pub struct ShippedFiles {
+    /// The entry points of the library, binaries and build script.
+    shipped_targets: Vec<PathBuf>,
+    /// The entry points of tests, benches and examples.
+    unshipped_targets: Vec<PathBuf>,
+    /// Directories containing the modules of shipped targets.
+    shipped_dirs: Vec<PathBuf>,
+    /// Directories containing unshipped targets or documentation. Files in
+    /// them are unshipped unless they're also in a more deeply nested shipped
+    /// directory.
+    unshipped_dirs: Vec<PathBuf>,
+    include: Vec<Glob>,
+    exclude: Vec<Glob>,
+}
+
+impl ShippedFiles {
+    /// Read the shipped files of the package unpacked at `package_dir`, which
+    /// is a git checkout if `is_checkout` is set. If its `Cargo.toml` can't be
+    /// read, every file is considered shipped.
+    pub fn read(package_dir: &Path, is_checkout: bool) -> Self {
+        let manifest_path = package_dir.join("Cargo.toml");
+        let manifest: Manifest = match std::fs::read_to_string(&manifest_path)
+            .map_err(|error| error.to_string())
+            .and_then(|source| toml::de::from_str(&source).map_err(|error| error.to_string()))
+        {
+            Ok(manifest) => manifest,
+            Err(error) => {
+                info!(
+                    "couldn't read {}, treating every file as shipped: {}",
+                    manifest_path.display(),
+                    error
+                );
+                return Self::default();
+            }
+        };
+
+        let mut shipped_targets: Vec<PathBuf> = manifest
+            .lib
+            .iter()
+            .chain(&manifest.bin)
+            .filter_map(|target| target.path.clone())
+            .collect();
+        shipped_targets.push("src/lib.rs".into());
+        shipped_targets.push("src/main.rs".into());
+        match manifest.package.build {
+            Some(toml::Value::String(build)) => shipped_targets.push(build.into()),
+            Some(toml::Value::Boolean(false)) => {}
+            _ => shipped_targets.push("build.rs".into()),
+        }
+        let shipped_targets: Vec<PathBuf> = shipped_targets.iter().map(|p| normalize(p)).collect();
+
+        let unshipped_targets: Vec<PathBuf> = manifest
+            .test
+            .iter()
+            .chain(&manifest.bench)
+            .chain(&manifest.example)
+            .filter_map(|target| target.path.as_deref().map(normalize))
+            .collect();
+
+        let shipped_dirs = shipped_targets
+            .iter()
+            .filter_map(|p| module_dir(p))
+            .collect();
+        let unshipped_dirs = UNSHIPPED_DIRS
+            .iter()
+            .map(PathBuf::from)
+            .chain(unshipped_targets.iter().filter_map(|p| module_dir(p)))
+            .collect();
+
+        let globs = |patterns: &[String]| {
+            if is_checkout {
+                patterns.iter().map(|p| Glob::new(p)).collect()
+            } else {
+                Vec::new()
+            }
+        };
+        ShippedFiles {
+            shipped_targets,
+            unshipped_targets,
+            shipped_dirs,
+            unshipped_dirs,
+            include: globs(&manifest.package.include),
+            exclude: globs(&manifest.package.exclude),
+        }
+    }
+
+    /// Whether the file at `rel_path` within the package is shipped.
+    pub fn is_shipped(&self, rel_path: &Path) -> bool {
+        if self.shipped_targets.iter().any(|target| target == rel_path) {
+            return true;
+        }
+        // As with cargo, `include` takes precedence over `exclude`. Neither
+        // hides the modules of a shipped target.
+        if !self
+            .shipped_dirs
+            .iter()
+            .any(|dir| rel_path.starts_with(dir))
+        {
+            let included = if self.include.is_empty() {
+                !self.exclude.iter().any(|glob| glob.matches(rel_path))
+            } else {
+                self.include.iter().any(|glob| glob.matches(rel_path))
+            };
+            if !included {
+                return false;
+            }
+        }
+        if self
+            .unshipped_targets
+            .iter()
+            .any(|target| target == rel_path)
+        {
+            return false;
+        }
+        // Files in both kinds of directory belong to the most deeply nested.
+        let deepest_dir = |dirs: &[PathBuf]| {
+            dirs.iter()
+                .filter(|dir| rel_path.starts_with(dir))
+                .map(|dir| dir.components().count())
+                .max()
+        };
+        if let Some(unshipped) = deepest_dir(&self.unshipped_dirs) {
+            if deepest_dir(&self.shipped_dirs).map_or(true, |shipped| shipped < unshipped) {
+                return false;
+            }
+        }
+        !rel_path.extension().map_or(false, |ext| {
+            DOC_EXTENSIONS
+                .iter()
+                .any(|doc| ext.eq_ignore_ascii_case(doc))
+        })
+    }
+}
+
+/// The directory holding the modules of the target whose entry point is
+/// `target`: the directory of a `lib.rs`, `main.rs` or `mod.rs`, or otherwise
+/// the directory named after the file.
+fn module_dir(target: &Path) -> Option<PathBuf> {
+    let parent = target.parent()?;
+    let dir = match target.file_name()?.to_str()? {
+        "lib.rs" | "main.rs" | "mod.rs" => parent.to_owned(),
+        _ => parent.join(target.file_stem()?),
+    };
+    (!dir.as_os_str().is_empty()).then_some(dir)
+}
+
+/// Remove `.` components from a target path, so it can be compared with the
+/// paths in the package.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+/// A pattern from `include` or `exclude`, which follow gitignore's rules.
+#[derive(Debug)]
+struct Glob {
+    pattern: String,
+    /// Whether the pattern must match from the package root, rather than
+    /// matching a file or directory name at any depth.
+    anchored: bool,
+    /// Whether the pattern only matches directories.
+    dir_only: bool,
+}
+
+impl Glob {
+    fn new(pattern: &str) -> Self {
+        let dir_only = pattern.ends_with('/');
+        let pattern = pattern.trim_end_matches('/');
+        let anchored = pattern.contains('/');
+        Glob {
+            pattern: pattern.trim_start_matches('/').to_owned(),
+            anchored,
+            dir_only,
+        }
+    }
+
+    /// Whether the pattern matches the file at `rel_path`, or a directory
+    /// containing it.
+    fn matches(&self, rel_path: &Path) -> bool {
+        let components: Vec<_> = rel_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect();
+        (1..=components.len()).any(|len| {
+            if self.dir_only && len == components.len() {
+                return false;
+            }
+            let candidate = if self.anchored {
+                components[..len].join("/")
+            } else {
+                components[len - 1].to_string()
+            };
+            glob_match(self.pattern.as_bytes(), candidate.as_bytes())
+        })
+    }
+}
+
+/// Match `text` against a pattern where `*` and `?` match within a path
+/// component, and `**` matches any number of components.
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+            rest.is_empty()
+                || (0..=text.len())
+                    .any(|i| (i == 0 || text[i - 1] == b'/') && glob_match(rest, &text[i..]))
+        }
+        [b'*', rest @ ..] => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != b'/')
+            .any(|i| glob_match(rest, &text[i..])),
+        [b'?', rest @ ..] => {
+            matches!(text.first(), Some(&c) if c != b'/') && glob_match(rest, &text[1..])
+        }
+        [p, rest @ ..] => text.first() == Some(p) && glob_match(rest, &text[1..]),
+    }
+}
@@ -0,0 +1,203 @@
+//! Signing of audits, and verification of the signatures on imported audits.
+//!
+//! Signatures are made and checked by invoking the `ssh-keygen` or `minisign`
+//! tools over a canonical serialization of each audit entry.
+
+use std::fmt::Write;
+use std::path::Path;
+use std::process::Stdio;
+
+use tokio::process::Command;
+
+use crate::errors::SigningError;
+use crate::format::{AuditEntry, AuditKind, PackageStr, SignatureFormat, SigningConfig};
+
+/// The namespace SSH signatures are made in, so that they can't be confused
+/// with signatures made by the same key for other purposes, like git commits.
+const SSH_NAMESPACE: &str = "cargo-vet";
+
+/// The principal every trusted key is listed under in the allowed signers file
+/// passed to `ssh-keygen -Y verify`.
+const SSH_PRINCIPAL: &str = "cargo-vet";
+
+const SSH_SIGNATURE_HEADER: &str = "-----BEGIN SSH SIGNATURE-----";
+
+/// The first line of every signed message, identifying the version of the
+/// serialization below.
+const PAYLOAD_HEADER: &str = "cargo-vet audit v1";
+
+/// The message which is signed for an audit of `package`.
+///
+/// This covers every field of the audit other than its signature and the
+/// sources it was aggregated from, which may be added after it was signed.
+/// The serialization is independent of how audits.toml is formatted, so that
+/// signatures remain valid across changes to it: after a header line, each
+/// field is written in a fixed order as a line with its name and the length
+/// of its value in bytes, followed by the value itself and a newline. Fields
+/// with several values are repeated once per value, in the order they were
+/// recorded in, and `notes` is omitted if there are none.
+pub fn audit_payload(package: PackageStr<'_>, entry: &AuditEntry) -> String {
+    fn field(payload: &mut String, name: &str, value: &str) {
+        write!(payload, "{name} {}\n{value}\n", value.len()).unwrap();
+    }
+
+    let mut payload = format!("{PAYLOAD_HEADER}\n");
+    field(&mut payload, "package", package);
+    match &entry.kind {
+        AuditKind::Full { version } => {
+            field(&mut payload, "kind", "full");
+            field(&mut payload, "version", &version.to_string());
+        }
+        AuditKind::Delta { from, to } => {
+            field(&mut payload, "kind", "delta");
+            field(&mut payload, "from", &from.to_string());
+            field(&mut payload, "to", &to.to_string());
+        }
+        AuditKind::Violation { violation } => {
+            field(&mut payload, "kind", "violation");
+            field(&mut payload, "violation", &violation.to_string());
+        }
+    }
+    for criteria in &entry.criteria {
+        field(&mut payload, "criteria", criteria);
+    }
+    for who in &entry.who {
+        field(&mut payload, "who", who);
+    }
+    field(
+        &mut payload,
+        "importable",
+        if entry.importable { "true" } else { "false" },
+    );
+    if let Some(notes) = &entry.notes {
+        field(&mut payload, "notes", notes);
+    }
+    payload
+}
+
+/// Sign `payload` with the key configured in `signing`, returning the
+/// signature to record on the audit.
+pub async fn sign(
+    signing: &SigningConfig,
+    store_path: &Path,
+    payload: &str,
+) -> Result<String, SigningError> {
+    let key = store_path.join(&signing.key);
+    let dir = tokio::task::spawn_blocking(tempfile::tempdir)
+        .await
+        .expect("creating a temporary directory panicked")?;
+    let message = dir.path().join("audit");
+    tokio::fs::write(&message, payload).await?;
+
+    let (tool, mut command) = match signing.format {
+        SignatureFormat::Ssh => {
+            let mut command = Command::new("ssh-keygen");
+            command
+                .args(["-Y", "sign", "-n", SSH_NAMESPACE, "-f"])
+                .arg(&key)
+                .arg(&message);
+            ("ssh-keygen", command)
+        }
+        SignatureFormat::Minisign => {
+            let mut command = Command::new("minisign");
+            command.args(["-S", "-s"]).arg(&key).arg("-m").arg(&message);
+            ("minisign", command)
+        }
+    };
+
+    // Both tools write the signature next to the message, and may prompt for
+    // the key's passphrase.
+    let status = command
+        .stdout(Stdio::null())
+        .status()
+        .await
+        .map_err(|error| SigningError::Spawn { tool, error })?;
+    if !status.success() {
+        return Err(SigningError::SignFailed { tool });
+    }
+
+    let extension = match signing.format {
+        SignatureFormat::Ssh => "sig",
+        SignatureFormat::Minisign => "minisig",
+    };
+    let signature = tokio::fs::read_to_string(message.with_extension(extension)).await?;
+    Ok(signature.trim_end().to_owned())
+}
+
+/// Whether `key` is an SSH public key, rather than a minisign one.
+fn is_ssh_key(key: &str) -> bool {
+    key.starts_with("ssh-") || key.starts_with("ecdsa-") || key.starts_with("sk-")
+}
+
+/// Check whether `signature` over `payload` was made by one of `trusted_keys`.
+pub async fn verify(
+    trusted_keys: &[String],
+    payload: &str,
+    signature: &str,
+) -> Result<bool, SigningError> {
+    let dir = tokio::task::spawn_blocking(tempfile::tempdir)
+        .await
+        .expect("creating a temporary directory panicked")?;
+    let message = dir.path().join("audit");
+    let signature_file = dir.path().join("audit.sig");
+    tokio::fs::write(&message, payload).await?;
+    tokio::fs::write(&signature_file, format!("{signature}\n")).await?;
+
+    if signature.starts_with(SSH_SIGNATURE_HEADER) {
+        let allowed_signers: String = trusted_keys
+            .iter()
+            .filter(|key| is_ssh_key(key))
+            .map(|key| format!("{SSH_PRINCIPAL} {key}\n"))
+            .collect();
+        if allowed_signers.is_empty() {
+            return Ok(false);
+        }
+        let allowed_signers_file = dir.path().join("allowed_signers");
+        tokio::fs::write(&allowed_signers_file, allowed_signers).await?;
+        let stdin = tokio::fs::File::open(&message).await?.into_std().await;
+
+        let status = Command::new("ssh-keygen")
+            .args([
+                "-Y",
+                "verify",
+                "-I",
+                SSH_PRINCIPAL,
+                "-n",
+                SSH_NAMESPACE,
+                "-f",
+            ])
+            .arg(&allowed_signers_file)
+            .arg("-s")
+            .arg(&signature_file)
+            .stdin(stdin)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .map_err(|error| SigningError::Spawn {
+                tool: "ssh-keygen",
+                error,
+            })?;
+        return Ok(status.success());
+    }
+
+    for key in trusted_keys.iter().filter(|key| !is_ssh_key(key)) {
+        let status = Command::new("minisign")
+            .args(["-V", "-q", "-P", key, "-m"])
+            .arg(&message)
+            .arg("-x")
+            .arg(&signature_file)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .map_err(|error| SigningError::Spawn {
+                tool: "minisign",
+                error,
+            })?;
+        if status.success() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
@@ -10,10 +10,11 @@ use std::{
 
 use cargo_metadata::semver;
 use flate2::read::GzDecoder;
-use futures_util::future::{join_all, try_join_all};
+use futures_util::future::{join_all, try_join_all, FutureExt, LocalBoxFuture};
 use miette::SourceOffset;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use similar::{udiff::unified_diff, Algorithm};
 use tar::Archive;
 use tracing::{error, info, log::warn, trace};
@@ -23,25 +24,32 @@ use crate::{
     errors::{
         AggregateError, BadFormatError, BadWildcardEndDateError, CacheAcquireError,
         CacheCommitError, CertifyError, CommandError, CrateInfoError, CriteriaChangeError,
-        CriteriaChangeErrors, DiffError, DownloadError, FetchAndDiffError,
-        FetchAuditAggregateError, FetchAuditError, FetchError, FetchRegistryError, FlockError,
-        InvalidCriteriaError, JsonParseError, LoadJsonError, LoadTomlError, SourceFile,
-        StoreAcquireError, StoreCommitError, StoreCreateError, StoreJsonError, StoreTomlError,
-        StoreValidateError, StoreValidateErrors, TomlParseError, UnpackCheckoutError, UnpackError,
+        CriteriaChangeErrors, DiffError, DiffSourceError, DownloadError, ExtendsError,
+        FetchAndDiffError, FetchAuditAggregateError, FetchAuditError, FetchError,
+        FetchRegistryError, FlockError, InvalidCriteriaError, InvalidWorkspaceError,
+        JsonParseError, LoadJsonError, LoadTomlError, SourceFile, StoreAcquireError,
+        StoreCommitError, StoreCreateError, StoreJsonError, StoreTomlError, StoreValidateError,
+        StoreValidateErrors, TargetPlatformError, TomlParseError, UnpackCheckoutError, UnpackError,
+        UnverifiedImportsError, WorkspaceError,
     },
     flock::{FileLock, Filesystem},
     format::{
-        self, AuditEntry, AuditKind, AuditedDependencies, AuditsFile, CommandHistory, ConfigFile,
-        CratesAPICrate, CratesAPICrateMetadata, CratesCache, CratesCacheEntry, CratesCacheUser,
-        CratesCacheVersionDetails, CratesPublisher, CratesUserId, CriteriaEntry, CriteriaMap,
-        CriteriaName, CriteriaStr, Delta, DiffCache, DiffStat, FastMap, FastSet, FetchCommand,
-        ForeignAuditsFile, ImportName, ImportsFile, MetaConfig, PackageName, PackageStr,
-        RegistryEntry, RegistryFile, SortedMap, StoreVersion, TrustEntry, TrustedPackages,
-        UnpublishedEntry, VetVersion, WildcardAudits, WildcardEntry, SAFE_TO_DEPLOY, SAFE_TO_RUN,
+        self, AuditEntry, AuditIndex, AuditIndexSource, AuditKind, AuditedDependencies, AuditsFile,
+        BaseConfigFile, CommandHistory, ConfigFile, CratesAPICrate, CratesAPICrateMetadata,
+        CratesCache, CratesCacheEntry, CratesCacheUser, CratesCacheVersionDetails, CratesPublisher,
+        CratesUserId, CriteriaEntry, CriteriaMap, CriteriaName, CriteriaStr, Delta, DiffCache,
+        DiffScope, DiffStat, ExemptedDependency, FastMap, FastSet, FetchCommand, ForeignAuditsFile,
+        ImportName, ImportsFile, IndexedAudit, MetaConfig, PackageName, PackagePolicyEntry,
+        PackageStr, RegistryCache, RegistryCacheEntry, RegistryConfig, RegistryEntry, RegistryFile,
+        RemoteImport, SortedMap, SortedSet, SourceComparison, StoreVersion, TrustEntry,
+        TrustedPackages, UnpackConfig, UnpublishedEntry, VetVersion, WhoFilter, WildcardAudits,
+        WildcardEntry, WorkspaceEntry, WorkspaceName, SAFE_TO_DEPLOY, SAFE_TO_RUN,
     },
     network::Network,
     out::{progress_bar, IncProgressOnDrop},
+    resolver::TargetPlatform,
     serialization::{parse_from_value, spanned::Spanned, to_formatted_toml},
+    shipped::ShippedFiles,
     Config, PackageExt, PartialConfig, CARGO_ENV,
 };
 
@@ -49,6 +57,8 @@ use crate::{
 const CACHE_DIFF_CACHE: &str = "diff-cache.toml";
 const CACHE_COMMAND_HISTORY: &str = "command-history.json";
 const CACHE_CRATES_IO_CACHE: &str = "crates-io-cache.json";
+const CACHE_PEER_REGISTRY_CACHE: &str = "registry-cache.json";
+const CACHE_AUDIT_INDEX: &str = "audit-index.json";
 const CACHE_EMPTY_PACKAGE: &str = "empty";
 const CACHE_REGISTRY_SRC: &str = "src";
 const CACHE_REGISTRY_CACHE: &str = "cache";
@@ -60,6 +70,8 @@ const CACHE_ALLOWED_FILES: &[&str] = &[
     CACHE_DIFF_CACHE,
     CACHE_COMMAND_HISTORY,
     CACHE_CRATES_IO_CACHE,
+    CACHE_PEER_REGISTRY_CACHE,
+    CACHE_AUDIT_INDEX,
     CACHE_EMPTY_PACKAGE,
     CACHE_REGISTRY_SRC,
     CACHE_REGISTRY_CACHE,
@@ -74,6 +86,20 @@ const CARGO_REGISTRY_CRATES_IO_HTTP: &str = "index.crates.io-6f17d22bba15001f";
 const CARGO_TOML_FILE: &str = "Cargo.toml";
 const CARGO_OK_FILE: &str = ".cargo-ok";
 const CARGO_OK_BODY: &str = "ok";
+const CARGO_LOCK_FILE: &str = "Cargo.lock";
+const CARGO_VCS_INFO_FILE: &str = ".cargo_vcs_info.json";
+const CARGO_TOML_ORIG_FILE: &str = "Cargo.toml.orig";
+
+// The default limits on unpacking a .crate file, which match the size limit
+// cargo uses and allow for crates with very many files.
+const DEFAULT_UNPACK_MAX_SIZE: u64 = 512 * 1024 * 1024;
+const DEFAULT_UNPACK_MAX_FILES: u64 = 100_000;
+/// Records the sha256 of the .crate file an unpacked package came from, if it
+/// was verified against Cargo.lock or the index.
+const CHECKSUM_FILE: &str = ".cargo-vet-checksum";
+/// Records the hash of the files in an unpacked package, as computed by
+/// `tree_hash`, so that changes made to them after unpacking are noticed.
+const TREE_HASH_FILE: &str = ".cargo-vet-tree-hash";
 
 pub const DEFAULT_STORE: &str = "supply-chain";
 
@@ -82,7 +108,13 @@ const CONFIG_TOML: &str = "config.toml";
 const IMPORTS_LOCK: &str = "imports.lock";
 
 // Files which are skipped when counting changes for diffs.
-const DIFF_SKIP_PATHS: &[&str] = &["Cargo.lock", ".cargo_vcs_info.json", ".cargo-ok"];
+const DIFF_SKIP_PATHS: &[&str] = &[
+    "Cargo.lock",
+    ".cargo_vcs_info.json",
+    ".cargo-ok",
+    ".cargo-vet-checksum",
+    ".cargo-vet-tree-hash",
+];
 
 // FIXME: This is a completely arbitrary number, and may be too high or too low.
 const MAX_CONCURRENT_DIFFS: usize = 40;
@@ -93,11 +125,18 @@ const METADATA_CACHE_EXPIRY_DAYS: i64 = 60;
 const VERSIONS_CACHE_EXPIRY_DAYS: i64 = 1;
 // Check whether a crate which was previously found to not exist now exists every 60 days.
 const NONEXISTENT_CRATE_EXPIRY_DAYS: i64 = 60;
+// Re-fetch the registry, and the audits of the peers in it, every day.
+const REGISTRY_CACHE_EXPIRY_DAYS: i64 = 1;
 
 // Url of the registry.
 pub const REGISTRY_URL: &str =
     "https://raw.githubusercontent.com/mozilla/cargo-vet/main/registry.toml";
 
+// Default crates.io endpoints, which can be overridden by the `[registry]` config.
+const CRATES_IO_API_URL: &str = "https://crates.io";
+const CRATES_IO_INDEX_URL: &str = "https://index.crates.io/";
+const CRATES_IO_DOWNLOAD_URL: &str = "https://crates.io/api/v1/crates";
+
 struct StoreLock {
     config: FileLock,
 }
@@ -154,9 +193,248 @@ pub struct Store {
     // initialized to `None` if `--locked` was passed.
     pub live_imports: Option<ImportsFile>,
 
+    // Entries from the base configs in `extends` which have been layered into
+    // `config` and `audits`, and shouldn't be written back to the store.
+    pub inherited: InheritedConfig,
+
     pub config_src: SourceFile,
     pub imports_src: SourceFile,
     pub audits_src: SourceFile,
+
+    // The target platforms being vetted for, either from `--target` or the
+    // `targets` key in config.toml. Empty if vetting for all platforms.
+    pub target_platforms: Vec<TargetPlatform>,
+    // The target platforms the store is maintained for, from the `targets`
+    // key in config.toml only. Updates to the store use these, so that
+    // vetting for a single platform with `--target` doesn't prune entries
+    // which other platforms need. Empty for all platforms.
+    pub store_target_platforms: Vec<TargetPlatform>,
+
+    // The workspaces which use each package version, if the store governs
+    // several of them. Empty otherwise.
+    pub workspace_users: WorkspaceUsers,
+}
+
+/// The workspaces which use each version of each package.
+pub type WorkspaceUsers = SortedMap<PackageName, SortedMap<VetVersion, SortedSet<WorkspaceName>>>;
+
+/// Find which of the workspaces governed by the store use each package
+/// version in the merged dependency graph.
+pub(crate) fn workspace_users(cfg: &Config) -> WorkspaceUsers {
+    let mut users = WorkspaceUsers::new();
+    for package in &cfg.metadata.packages {
+        for (name, workspace) in &cfg.workspaces {
+            if workspace.packages.contains(&package.id) {
+                users
+                    .entry(package.name.clone())
+                    .or_default()
+                    .entry(package.vet_version())
+                    .or_default()
+                    .insert(name.clone());
+            }
+        }
+    }
+    users
+}
+
+/// Look up the `cfg` values for the targets we're vetting for, and for those
+/// the store is maintained for. Targets passed on the command line take
+/// precedence over those listed in config.toml when vetting, but not for the
+/// store. `rustc` is only run if there are targets to look up.
+fn query_target_platforms(
+    cfg: &Config,
+    config: &ConfigFile,
+) -> Result<(Vec<TargetPlatform>, Vec<TargetPlatform>), TargetPlatformError> {
+    let query = |targets: &[String]| -> Result<Vec<TargetPlatform>, TargetPlatformError> {
+        if targets.is_empty() {
+            return Ok(Vec::new());
+        }
+        targets
+            .iter()
+            .map(|triple| TargetPlatform::query(triple))
+            .collect()
+    };
+    let store_target_platforms = query(&config.targets)?;
+    let target_platforms = if cfg.cli.target.is_empty() {
+        store_target_platforms.clone()
+    } else {
+        query(&cfg.cli.target)?
+    };
+    Ok((target_platforms, store_target_platforms))
+}
+
+/// Entries inherited from the base configs named by `extends`.
+///
+/// These are layered into the store's `config` and `audits` when it's
+/// acquired, and are tracked separately so that they can be stripped back out
+/// before the store is written, and so that diagnostics point at the base
+/// config they came from.
+#[derive(Clone, Default)]
+pub struct InheritedConfig {
+    // The source of each base config, in `extends` order. Entries below refer
+    // to their base by index into this list.
+    sources: Vec<SourceFile>,
+    default_criteria: Option<(usize, CriteriaName)>,
+    criteria: SortedMap<CriteriaName, (usize, CriteriaEntry)>,
+    imports: SortedMap<ImportName, (usize, RemoteImport)>,
+    policy: SortedMap<PackageName, (usize, PackagePolicyEntry)>,
+    workspaces: SortedMap<WorkspaceName, (usize, WorkspaceEntry)>,
+}
+
+impl InheritedConfig {
+    /// Layer `bases` under `config` and `audits`. Later bases take precedence
+    /// over earlier ones, and the store's own entries take precedence over
+    /// all of them.
+    fn layer(
+        bases: Vec<(SourceFile, BaseConfigFile)>,
+        config: &mut ConfigFile,
+        audits: &mut AuditsFile,
+    ) -> Self {
+        let mut this = Self::default();
+        for (index, (source, base)) in bases.into_iter().enumerate() {
+            this.sources.push(source);
+            if let Some(default_criteria) = base.default_criteria {
+                this.default_criteria = Some((index, default_criteria));
+            }
+            this.criteria
+                .extend(base.criteria.into_iter().map(|(k, v)| (k, (index, v))));
+            this.imports
+                .extend(base.imports.into_iter().map(|(k, v)| (k, (index, v))));
+            this.policy.extend(
+                base.policy
+                    .package
+                    .into_iter()
+                    .map(|(k, v)| (k, (index, v))),
+            );
+            this.workspaces
+                .extend(base.workspaces.into_iter().map(|(k, v)| (k, (index, v))));
+        }
+
+        if config.default_criteria != format::DEFAULT_CRITERIA {
+            this.default_criteria = None;
+        }
+        this.criteria
+            .retain(|name, _| !audits.criteria.contains_key(name));
+        this.imports
+            .retain(|name, _| !config.imports.contains_key(name));
+        this.policy
+            .retain(|name, _| !config.policy.package.contains_key(name));
+        this.workspaces
+            .retain(|name, _| !config.workspaces.contains_key(name));
+
+        if let Some((_, default_criteria)) = &this.default_criteria {
+            config.default_criteria = default_criteria.clone();
+        }
+        for (name, (_, entry)) in &this.criteria {
+            audits.criteria.insert(name.clone(), entry.clone());
+        }
+        for (name, (_, import)) in &this.imports {
+            config.imports.insert(name.clone(), import.clone());
+        }
+        for (name, (_, entry)) in &this.policy {
+            config.policy.insert(name.clone(), entry.clone());
+        }
+        for (name, (_, entry)) in &this.workspaces {
+            config.workspaces.insert(name.clone(), entry.clone());
+        }
+        this
+    }
+
+    /// Remove inherited entries from `config` which haven't been modified
+    /// since they were layered in.
+    fn strip_config(&self, config: &mut ConfigFile) {
+        if let Some((_, default_criteria)) = &self.default_criteria {
+            if &config.default_criteria == default_criteria {
+                config.default_criteria = format::get_default_criteria();
+            }
+        }
+        for (name, (_, import)) in &self.imports {
+            if config.imports.get(name) == Some(import) {
+                config.imports.remove(name);
+            }
+        }
+        for (name, (_, entry)) in &self.policy {
+            if config.policy.package.get(name) == Some(entry) {
+                config.policy.package.remove(name);
+            }
+        }
+        for (name, (_, entry)) in &self.workspaces {
+            if config.workspaces.get(name) == Some(entry) {
+                config.workspaces.remove(name);
+            }
+        }
+    }
+
+    /// Remove inherited criteria from `audits` which haven't been modified
+    /// since they were layered in.
+    fn strip_audits(&self, audits: &mut AuditsFile) {
+        for (name, (_, entry)) in &self.criteria {
+            if audits.criteria.get(name) == Some(entry) {
+                audits.criteria.remove(name);
+            }
+        }
+    }
+
+    /// The base config which the policy for `package` was inherited from.
+    pub fn policy_source(&self, package: PackageStr<'_>) -> Option<&SourceFile> {
+        let (index, _) = self.policy.get(package)?;
+        Some(&self.sources[*index])
+    }
+
+    /// The base config which `criteria` was inherited from.
+    pub fn criteria_source(&self, criteria: CriteriaStr<'_>) -> Option<&SourceFile> {
+        let (index, _) = self.criteria.get(criteria)?;
+        Some(&self.sources[*index])
+    }
+}
+
+/// Whether a base config in `extends` should be fetched from the network,
+/// rather than read relative to the store.
+fn is_url(base: &str) -> bool {
+    base.starts_with("https://") || base.starts_with("http://")
+}
+
+/// Load each of the base configs named by `extends` in config.toml. Bases
+/// with URLs are fetched if `network` is provided, and are otherwise read
+/// from the copies pinned in imports.lock.
+fn load_bases(
+    root: &Path,
+    config: &ConfigFile,
+    imports: &ImportsFile,
+    imports_src: &SourceFile,
+    network: Option<&Network>,
+) -> Result<Vec<(SourceFile, BaseConfigFile)>, ExtendsError> {
+    config
+        .extends
+        .iter()
+        .map(|base| {
+            if !is_url(base) {
+                let file = File::open(root.join(base)).map_err(|error| ExtendsError::ReadFile {
+                    base: base.clone(),
+                    error,
+                })?;
+                return Ok(load_toml(base, file)?);
+            }
+            let Some(network) = network else {
+                let pinned = imports
+                    .extends
+                    .get(base)
+                    .ok_or_else(|| ExtendsError::NotPinned { url: base.clone() })?;
+                return Ok((imports_src.clone(), pinned.clone()));
+            };
+            let url = Url::parse(base).map_err(|error| ExtendsError::InvalidUrl {
+                url: base.clone(),
+                error,
+            })?;
+            let bytes = tokio::runtime::Handle::current()
+                .block_on(network.download(url))
+                .map_err(|error| ExtendsError::Download {
+                    url: base.clone(),
+                    error,
+                })?;
+            Ok(load_toml(base, &bytes[..])?)
+        })
+        .collect()
 }
 
 impl Store {
@@ -167,19 +445,34 @@ impl Store {
 
         let lock = StoreLock::new(&root)?;
 
+        let config = ConfigFile {
+            cargo_vet: Default::default(),
+            extends: Vec::new(),
+            default_criteria: format::get_default_criteria(),
+            targets: Vec::new(),
+            violation_conflicts: Default::default(),
+            profiles: SortedMap::new(),
+            workspaces: SortedMap::new(),
+            signing: None,
+            registry: None,
+            unpack: None,
+            diff: None,
+            imports: SortedMap::new(),
+            policy: Default::default(),
+            exemptions: SortedMap::new(),
+        };
+        let (target_platforms, store_target_platforms) = query_target_platforms(cfg, &config)?;
+        let workspace_users = workspace_users(cfg);
+
         Ok(Self {
             lock: Some(lock),
-            config: ConfigFile {
-                cargo_vet: Default::default(),
-                default_criteria: format::get_default_criteria(),
-                imports: SortedMap::new(),
-                policy: Default::default(),
-                exemptions: SortedMap::new(),
-            },
+            config,
             imports: ImportsFile {
                 unpublished: SortedMap::new(),
                 publisher: SortedMap::new(),
                 audits: SortedMap::new(),
+                extends: SortedMap::new(),
+                rejected: SortedMap::new(),
             },
             audits: AuditsFile {
                 criteria: SortedMap::new(),
@@ -188,18 +481,71 @@ impl Store {
                 trusted: SortedMap::new(),
             },
             live_imports: None,
+            inherited: InheritedConfig::default(),
             config_src: SourceFile::new_empty(CONFIG_TOML),
             audits_src: SourceFile::new_empty(AUDITS_TOML),
             imports_src: SourceFile::new_empty(IMPORTS_LOCK),
+            target_platforms,
+            store_target_platforms,
+            workspace_users,
         })
     }
 
+    /// Read the manifest paths of the workspaces governed by the store from
+    /// its config.toml, without acquiring the store. The dependency graphs of
+    /// these workspaces are needed before the store itself is loaded.
+    pub fn read_workspaces(
+        metacfg: &MetaConfig,
+    ) -> Result<SortedMap<WorkspaceName, PathBuf>, WorkspaceError> {
+        let root = metacfg.store_path();
+        let mut config = Self::read_config_unlocked(metacfg)?;
+        if !config.extends.is_empty() {
+            // The store hasn't been acquired yet, so base configs from URLs
+            // are read from the copies pinned in imports.lock.
+            let file = File::open(root.as_path_unlocked().join(IMPORTS_LOCK))
+                .map_err(LoadTomlError::from)?;
+            let (imports_src, imports): (_, ImportsFile) = load_toml(IMPORTS_LOCK, file)?;
+            let bases = load_bases(
+                root.as_path_unlocked(),
+                &config,
+                &imports,
+                &imports_src,
+                None,
+            )?;
+            InheritedConfig::layer(bases, &mut config, &mut AuditsFile::default());
+        }
+        Ok(config
+            .workspaces
+            .into_iter()
+            .map(|(name, entry)| (name, root.as_path_unlocked().join(entry.manifest)))
+            .collect())
+    }
+
+    /// Read the config.toml of the store without acquiring the store, for the
+    /// settings which are needed before it is loaded.
+    pub fn read_config_unlocked(metacfg: &MetaConfig) -> Result<ConfigFile, LoadTomlError> {
+        let root = metacfg.store_path();
+        let file = File::open(root.as_path_unlocked().join(CONFIG_TOML))?;
+        let (_, config): (_, ConfigFile) = load_toml(CONFIG_TOML, file)?;
+        Ok(config)
+    }
+
     pub fn is_init(metacfg: &MetaConfig) -> bool {
         // Probably want to do more here later...
         metacfg.store_path().as_path_unlocked().exists()
     }
 
     pub fn acquire_offline(cfg: &Config) -> Result<Self, StoreAcquireError> {
+        Self::acquire_with_bases(cfg, None)
+    }
+
+    /// Acquire the store, fetching any base configs in `extends` which come
+    /// from URLs with `network` if it's provided, and otherwise using the
+    /// copies pinned in imports.lock.
+    fn acquire_with_bases(
+        cfg: &Config,
+        network: Option<&Network>,
+    ) -> Result<Self, StoreAcquireError> {
         let root = cfg.metacfg.store_path();
 
         // Before we do anything else, acquire an exclusive lock on the
@@ -222,19 +568,46 @@ impl Store {
         }
         config.cargo_vet.version = current_version;
 
-        let (audits_src, audits): (_, AuditsFile) = load_toml(AUDITS_TOML, lock.read_audits()?)?;
-        let (imports_src, imports): (_, ImportsFile) =
+        let (audits_src, mut audits): (_, AuditsFile) =
+            load_toml(AUDITS_TOML, lock.read_audits()?)?;
+        let (imports_src, mut imports): (_, ImportsFile) =
             load_toml(IMPORTS_LOCK, lock.read_imports()?)?;
 
+        let bases = load_bases(
+            root.as_path_unlocked(),
+            &config,
+            &imports,
+            &imports_src,
+            network,
+        )?;
+        if network.is_some() {
+            // Re-pin the freshly fetched base configs.
+            imports.extends = config
+                .extends
+                .iter()
+                .zip(&bases)
+                .filter(|(base, _)| is_url(base))
+                .map(|(base, (_, base_config))| (base.clone(), base_config.clone()))
+                .collect();
+        }
+        let inherited = InheritedConfig::layer(bases, &mut config, &mut audits);
+
+        let (target_platforms, store_target_platforms) = query_target_platforms(cfg, &config)?;
+        let workspace_users = workspace_users(cfg);
+
         let store = Self {
             lock: Some(lock),
             config,
             audits,
             imports,
             live_imports: None,
+            inherited,
             config_src,
             audits_src,
             imports_src,
+            target_platforms,
+            store_target_platforms,
+            workspace_users,
         };
 
         // Check that the store isn't corrupt
@@ -246,13 +619,15 @@ impl Store {
     /// Acquire an existing store
     ///
     /// If `network` is passed and `!cfg.cli.locked`, this will fetch remote
-    /// imports to use for comparison purposes.
+    /// imports to use for comparison purposes. Otherwise, only the imports
+    /// which are read from local files or git repositories are read.
     pub fn acquire(
         cfg: &Config,
         network: Option<&Network>,
         allow_criteria_changes: bool,
     ) -> Result<Self, StoreAcquireError> {
-        let mut this = Self::acquire_offline(cfg)?;
+        let network = network.filter(|_| !cfg.cli.locked);
+        let mut this = Self::acquire_with_bases(cfg, network)?;
         if let Some(network) = network {
             let cache = Cache::acquire(cfg).map_err(Box::new)?;
             tokio::runtime::Handle::current().block_on(this.go_online(
@@ -261,12 +636,54 @@ impl Store {
                 &cache,
                 allow_criteria_changes,
             ))?;
-
+        } else {
+            let root = cfg.metacfg.store_path();
+            tokio::runtime::Handle::current().block_on(
+                this.read_local_imports(root.as_path_unlocked(), allow_criteria_changes),
+            )?;
+        }
+        if this.live_imports.is_some() {
             this.validate(cfg.today(), cfg.cli.locked)?;
         }
         Ok(this)
     }
 
+    /// Read the live state of the imports which are read from local files or
+    /// git repositories, which doesn't need the network, so that changes to
+    /// them are seen while offline or `--locked`. All other imports keep the
+    /// audits recorded in imports.lock.
+    pub async fn read_local_imports(
+        &mut self,
+        root: &Path,
+        allow_criteria_changes: bool,
+    ) -> Result<(), StoreAcquireError> {
+        let local_criteria_mapper = CriteriaMapper::new(&self.audits.criteria);
+        let fetched_audits =
+            fetch_imported_audits(None, Some(root), &local_criteria_mapper, &self.config).await?;
+        if fetched_audits.is_empty() {
+            return Ok(());
+        }
+        let mut live_imports = process_imported_audits(
+            fetched_audits,
+            &self.config,
+            &self.imports,
+            &local_criteria_mapper,
+            allow_criteria_changes,
+        )?;
+        for (import_name, audits_file) in &self.imports.audits {
+            if self.config.imports.contains_key(import_name) {
+                live_imports
+                    .audits
+                    .entry(import_name.clone())
+                    .or_insert_with(|| audits_file.clone());
+            }
+        }
+        live_imports.unpublished = self.imports.unpublished.clone();
+        live_imports.publisher = self.imports.publisher.clone();
+        self.live_imports = Some(live_imports);
+        Ok(())
+    }
+
     pub async fn go_online(
         &mut self,
         cfg: &Config,
@@ -281,10 +698,21 @@ impl Store {
         // If this command isn't locked, and the network is available, fetch the
         // live state of imported audits.
         let local_criteria_mapper = CriteriaMapper::new(&self.audits.criteria);
-        let fetched_audits =
-            fetch_imported_audits(network, &local_criteria_mapper, &self.config).await?;
-        let mut live_imports =
-            process_imported_audits(fetched_audits, &self.imports, allow_criteria_changes)?;
+        let root = cfg.metacfg.store_path();
+        let fetched_audits = fetch_imported_audits(
+            Some(network),
+            Some(root.as_path_unlocked()),
+            &local_criteria_mapper,
+            &self.config,
+        )
+        .await?;
+        let mut live_imports = process_imported_audits(
+            fetched_audits,
+            &self.config,
+            &self.imports,
+            &local_criteria_mapper,
+            allow_criteria_changes,
+        )?;
         import_unpublished_entries(
             &cfg.metadata,
             network,
@@ -308,6 +736,9 @@ impl Store {
         )
         .await
         .map_err(Box::new)?;
+        for (import_name, audits_file) in &live_imports.audits {
+            cache.update_audit_index(import_name, [audits_file]);
+        }
         self.live_imports = Some(live_imports);
         Ok(())
     }
@@ -321,9 +752,13 @@ impl Store {
             imports,
             audits,
             live_imports: None,
+            inherited: InheritedConfig::default(),
             config_src: SourceFile::new_empty(CONFIG_TOML),
             audits_src: SourceFile::new_empty(AUDITS_TOML),
             imports_src: SourceFile::new_empty(IMPORTS_LOCK),
+            target_platforms: Vec::new(),
+            store_target_platforms: Vec::new(),
+            workspace_users: WorkspaceUsers::new(),
         }
     }
 
@@ -342,13 +777,21 @@ impl Store {
         allow_criteria_changes: bool,
     ) -> Result<Self, StoreAcquireError> {
         let local_criteria_mapper = CriteriaMapper::new(&audits.criteria);
+        // Mock stores have no root, so relative imports are read from the
+        // working directory.
         let fetched_audits = tokio::runtime::Handle::current().block_on(fetch_imported_audits(
-            network,
+            Some(network),
+            Some(Path::new(".")),
             &local_criteria_mapper,
             &config,
         ))?;
-        let mut live_imports =
-            process_imported_audits(fetched_audits, &imports, allow_criteria_changes)?;
+        let mut live_imports = process_imported_audits(
+            fetched_audits,
+            &config,
+            &imports,
+            &local_criteria_mapper,
+            allow_criteria_changes,
+        )?;
         let cache = Cache::acquire(cfg).map_err(Box::new)?;
         tokio::runtime::Handle::current()
             .block_on(import_unpublished_entries(
@@ -380,9 +823,13 @@ impl Store {
             imports,
             audits,
             live_imports: Some(live_imports),
+            inherited: InheritedConfig::default(),
             config_src: SourceFile::new_empty(CONFIG_TOML),
             audits_src: SourceFile::new_empty(AUDITS_TOML),
             imports_src: SourceFile::new_empty(IMPORTS_LOCK),
+            target_platforms: Vec::new(),
+            store_target_platforms: Vec::new(),
+            workspace_users: WorkspaceUsers::new(),
         };
 
         let today = chrono::NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
@@ -400,19 +847,50 @@ impl Store {
         today: chrono::NaiveDate,
         check_file_formatting: bool,
     ) -> Result<Self, StoreAcquireError> {
-        let (config_src, config): (_, ConfigFile) = load_toml(CONFIG_TOML, config.as_bytes())?;
-        let (audits_src, audits): (_, AuditsFile) = load_toml(AUDITS_TOML, audits.as_bytes())?;
+        Self::mock_acquire_with_bases(config, audits, imports, &[], today, check_file_formatting)
+    }
+
+    /// Like `mock_acquire`, but also providing the contents of each base
+    /// config named by `extends` in config.toml.
+    #[cfg(test)]
+    pub fn mock_acquire_with_bases(
+        config: &str,
+        audits: &str,
+        imports: &str,
+        bases: &[(&str, &str)],
+        today: chrono::NaiveDate,
+        check_file_formatting: bool,
+    ) -> Result<Self, StoreAcquireError> {
+        let (config_src, mut config): (_, ConfigFile) = load_toml(CONFIG_TOML, config.as_bytes())?;
+        let (audits_src, mut audits): (_, AuditsFile) = load_toml(AUDITS_TOML, audits.as_bytes())?;
         let (imports_src, imports): (_, ImportsFile) = load_toml(IMPORTS_LOCK, imports.as_bytes())?;
 
+        let bases = config
+            .extends
+            .iter()
+            .map(|base| {
+                let (_, contents) = bases
+                    .iter()
+                    .find(|(name, _)| name == base)
+                    .expect("missing mock base config");
+                load_toml(base, contents.as_bytes())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let inherited = InheritedConfig::layer(bases, &mut config, &mut audits);
+
         let store = Self {
             lock: None,
             config,
             imports,
             audits,
             live_imports: None,
+            inherited,
             config_src,
             audits_src,
             imports_src,
+            target_platforms: Vec::new(),
+            store_target_platforms: Vec::new(),
+            workspace_users: WorkspaceUsers::new(),
         };
 
         store.validate(today, check_file_formatting)?;
@@ -437,9 +915,13 @@ impl Store {
             imports: self.imports.clone(),
             audits: self.audits.clone(),
             live_imports: self.live_imports.clone(),
+            inherited: self.inherited.clone(),
             config_src: self.config_src.clone(),
             audits_src: self.audits_src.clone(),
             imports_src: self.imports_src.clone(),
+            target_platforms: self.target_platforms.clone(),
+            store_target_platforms: self.store_target_platforms.clone(),
+            workspace_users: self.workspace_users.clone(),
         };
         if clear_exemptions {
             // Delete all exemptions entries except those that are suggest=false
@@ -458,6 +940,23 @@ impl Store {
         clone
     }
 
+    /// Whether `exemption` for `package` applies to the dependency graph being
+    /// vetted. An exemption which records the workspace needing it doesn't
+    /// apply if any other workspace also uses that version.
+    pub fn exemption_applies(
+        &self,
+        package: PackageStr<'_>,
+        exemption: &ExemptedDependency,
+    ) -> bool {
+        let Some(workspace) = &exemption.workspace else {
+            return true;
+        };
+        self.workspace_users
+            .get(package)
+            .and_then(|versions| versions.get(&exemption.version))
+            .map_or(true, |users| users.iter().all(|user| *user == **workspace))
+    }
+
     /// Returns the set of audits which should be operated upon.
     ///
     /// If the store was acquired unlocked, this will include audits which are
@@ -494,17 +993,33 @@ impl Store {
         }
     }
 
+    /// The store's own config.toml, without any unmodified entries inherited
+    /// from base configs.
+    fn local_config(&self) -> ConfigFile {
+        let mut config = self.config.clone();
+        self.inherited.strip_config(&mut config);
+        config
+    }
+
+    /// The store's own audits.toml, without any unmodified criteria inherited
+    /// from base configs.
+    fn local_audits(&self) -> AuditsFile {
+        let mut audits = self.audits.clone();
+        self.inherited.strip_audits(&mut audits);
+        audits
+    }
+
     /// Commit the store's contents back to disk
     pub fn commit(self) -> Result<(), StoreCommitError> {
         // TODO: make this truly transactional?
         // (With a dir rename? Does that work with the lock? Fine because it's already closed?)
-        if let Some(lock) = self.lock {
+        if let Some(lock) = &self.lock {
             let mut audits = lock.write_audits()?;
             let mut config = lock.write_config()?;
             let mut imports = lock.write_imports()?;
             let user_info = user_info_map(&self.imports);
-            audits.write_all(store_audits(self.audits, &user_info)?.as_bytes())?;
-            config.write_all(store_config(self.config)?.as_bytes())?;
+            audits.write_all(store_audits(self.local_audits(), &user_info)?.as_bytes())?;
+            config.write_all(store_config(self.local_config())?.as_bytes())?;
             imports.write_all(store_imports(self.imports, &user_info)?.as_bytes())?;
         }
         Ok(())
@@ -518,11 +1033,11 @@ impl Store {
         [
             (
                 AUDITS_TOML.to_owned(),
-                store_audits(self.audits.clone(), &user_info).unwrap(),
+                store_audits(self.local_audits(), &user_info).unwrap(),
             ),
             (
                 CONFIG_TOML.to_owned(),
-                store_config(self.config.clone()).unwrap(),
+                store_config(self.local_config()).unwrap(),
             ),
             (
                 IMPORTS_LOCK.to_owned(),
@@ -600,29 +1115,69 @@ impl Store {
                     &mut errors,
                     &entry.criteria,
                 );
+                if let Some(workspace) = &entry.workspace {
+                    if !self.config.workspaces.contains_key(&**workspace) {
+                        errors.push(StoreValidateError::InvalidWorkspace(
+                            InvalidWorkspaceError {
+                                source_code: self.config_src.clone(),
+                                span: Spanned::span(workspace),
+                                invalid: workspace.to_string(),
+                                valid_names: self.config.workspaces.keys().cloned().collect(),
+                            },
+                        ));
+                    }
+                }
             }
         }
-        for (_name, _version, policy) in &self.config.policy {
+        for (name, _version, policy) in &self.config.policy {
+            let source_code = self
+                .inherited
+                .policy_source(name)
+                .unwrap_or(&self.config_src);
             check_criteria(
-                &self.config_src,
+                source_code,
                 &valid_criteria,
                 &mut errors,
                 policy.criteria.as_ref().unwrap_or(&no_criteria),
             );
             check_criteria(
-                &self.config_src,
+                source_code,
                 &valid_criteria,
                 &mut errors,
                 policy.dev_criteria.as_ref().unwrap_or(&no_criteria),
             );
+            check_criteria(
+                source_code,
+                &valid_criteria,
+                &mut errors,
+                policy.build_criteria.as_ref().unwrap_or(&no_criteria),
+            );
             for (_dep_package, dep_criteria) in &policy.dependency_criteria {
-                check_criteria(&self.config_src, &valid_criteria, &mut errors, dep_criteria);
+                check_criteria(source_code, &valid_criteria, &mut errors, dep_criteria);
+            }
+        }
+        for import in self.config.imports.values() {
+            check_criteria(
+                &self.config_src,
+                &valid_criteria,
+                &mut errors,
+                &import.allowed_criteria,
+            );
+            for scope in &import.scope {
+                check_criteria(
+                    &self.config_src,
+                    &valid_criteria,
+                    &mut errors,
+                    &scope.criteria,
+                );
             }
         }
-        for (_new_criteria, entry) in &self.audits.criteria {
+        for (new_criteria, entry) in &self.audits.criteria {
             // TODO: check that new_criteria isn't shadowing a builtin criteria
             check_criteria(
-                &self.audits_src,
+                self.inherited
+                    .criteria_source(new_criteria)
+                    .unwrap_or(&self.audits_src),
                 &valid_criteria,
                 &mut errors,
                 &entry.implies,
@@ -671,13 +1226,13 @@ impl Store {
                 (
                     CONFIG_TOML,
                     self.config_src.source(),
-                    store_config(self.config.clone())
+                    store_config(self.local_config())
                         .unwrap_or_else(|_| self.config_src.source().to_owned()),
                 ),
                 (
                     AUDITS_TOML,
                     self.audits_src.source(),
-                    store_audits(self.audits.clone(), &user_info)
+                    store_audits(self.local_audits(), &user_info)
                         .unwrap_or_else(|_| self.audits_src.source().to_owned()),
                 ),
                 (
@@ -727,6 +1282,18 @@ impl Store {
             return true;
         }
 
+        // Likewise, every base config fetched from a URL must be pinned, and
+        // no others.
+        let url_bases = self
+            .config
+            .extends
+            .iter()
+            .filter(|base| is_url(base))
+            .collect::<SortedSet<_>>();
+        if url_bases.into_iter().ne(self.imports.extends.keys()) {
+            return true;
+        }
+
         for (import_name, config) in &self.config.imports {
             let audits_file = self.imports.audits.get(import_name).unwrap();
             // If we have any excluded crates in the imports.lock, it is out of
@@ -783,7 +1350,9 @@ impl Store {
         network: &Network,
         cache: &Cache,
     ) -> Result<Vec<(ImportName, RegistryEntry, AuditsFile)>, FetchRegistryError> {
-        let registry_file = fetch_registry(network).await?;
+        let registry_file = fetch_registry(Some(network), cache, cfg.registry_url()).await?;
+        // Only a local mirror of the registry may refer to local audits.
+        let root = local_registry_dir(cfg.registry_url());
 
         let registry_entries = {
             let progress_bar = progress_bar(
@@ -801,7 +1370,8 @@ impl Store {
                         let _guard = IncProgressOnDrop(&progress_bar, 1);
                         let existing_entry = self.config.imports.get(&name);
                         fetch_imported_audit(
-                            network,
+                            Some(network),
+                            root.as_deref(),
                             &local_criteria_mapper,
                             &name,
                             &entry.url,
@@ -809,12 +1379,19 @@ impl Store {
                             existing_entry
                                 .map(|e| &e.criteria_map)
                                 .unwrap_or(&SortedMap::new()),
+                            existing_entry.map(|e| &e.trusted_keys[..]).unwrap_or(&[]),
+                            existing_entry
+                                .map(|e| e.transitive_depth_limit())
+                                .unwrap_or(0),
                         )
                         .await
                         .map_err(|error| {
                             error!("Error fetching registry audits for '{name}': {error:?}")
                         })
-                        .map(|audit_file| (name, entry, audit_file))
+                        .map(|(audit_file, _)| {
+                            cache.update_audit_index(&name, [&audit_file]);
+                            (name, entry, audit_file)
+                        })
                         .ok()
                     }),
             )
@@ -852,21 +1429,133 @@ impl Store {
     }
 }
 
+/// Reject entries in an imported audits file which aren't signed by one of
+/// `trusted_keys`. Only audits can be signed, so any wildcard audits or trusted
+/// entries are rejected as well.
+async fn verify_foreign_audits(
+    name: &str,
+    foreign_audit_file: &mut ForeignAuditsFile,
+    trusted_keys: &[String],
+) -> Result<(), FetchAuditError> {
+    let mut rejected = Vec::new();
+    for (package, audits) in &mut foreign_audit_file.audits {
+        let mut verified = Vec::new();
+        for value in mem::take(audits) {
+            // Entries which can't be parsed will be ignored later on.
+            let Ok(entry) = parse_from_value::<AuditEntry>(value.clone()) else {
+                verified.push(value);
+                continue;
+            };
+            let what = describe_audit(package, &entry);
+            let Some(signature) = &entry.signature else {
+                rejected.push(format!("{what}: unsigned"));
+                continue;
+            };
+            let payload = crate::signing::audit_payload(package, &entry);
+            let valid = crate::signing::verify(trusted_keys, &payload, signature)
+                .await
+                .map_err(|error| FetchAuditError::Signing {
+                    import_name: name.to_owned(),
+                    error,
+                })?;
+            if valid {
+                verified.push(value);
+            } else {
+                rejected.push(format!("{what}: bad signature"));
+            }
+        }
+        *audits = verified;
+    }
+    for (package, entries) in mem::take(&mut foreign_audit_file.wildcard_audits) {
+        for _ in entries {
+            rejected.push(format!("{package} wildcard audit: unsigned"));
+        }
+    }
+    for (package, entries) in mem::take(&mut foreign_audit_file.trusted) {
+        for _ in entries {
+            rejected.push(format!("{package} trusted entry: unsigned"));
+        }
+    }
+
+    if !rejected.is_empty() {
+        warn!(
+            "{:?}",
+            miette::Report::new(UnverifiedImportsError {
+                import_name: name.to_owned(),
+                rejected,
+            })
+        );
+    }
+    Ok(())
+}
+
 /// Process imported audits from the network, generating a `LiveImports`
 /// description of the live state of imported audits.
+///
+/// Imports pinned to a `sha256` which no longer matches their contents keep
+/// the audits recorded for them in `imports.lock`. Entries which were rejected
+/// when reviewing an import are removed, as are new entries from imports which
+/// require review, and entries which the import's allow-lists don't allow.
 fn process_imported_audits(
-    fetched_audits: Vec<(ImportName, AuditsFile)>,
+    fetched_audits: Vec<FetchedImport>,
+    config: &ConfigFile,
     imports_lock: &ImportsFile,
+    local_criteria_mapper: &CriteriaMapper,
     allow_criteria_changes: bool,
-) -> Result<ImportsFile, CriteriaChangeErrors> {
+) -> Result<ImportsFile, StoreAcquireError> {
     let mut new_imports = ImportsFile {
         unpublished: SortedMap::new(),
         publisher: SortedMap::new(),
         audits: SortedMap::new(),
+        extends: SortedMap::new(),
+        rejected: SortedMap::new(),
     };
     let mut changed_criteria = Vec::new();
 
-    for (import_name, mut audits_file) in fetched_audits {
+    for FetchedImport {
+        import_name,
+        mut audits_file,
+        sha256,
+    } in fetched_audits
+    {
+        let pinned = config
+            .imports
+            .get(&import_name)
+            .and_then(|import| import.sha256.as_ref());
+        // The hash doesn't cover the audits imported through the peer.
+        if pinned.is_some() && config.imports[&import_name].transitive {
+            return Err(StoreAcquireError::PinnedTransitiveImport { import_name });
+        }
+        if let Some(pinned) = pinned.filter(|pinned| **pinned != sha256) {
+            let Some(existing_audits_file) = imports_lock.audits.get(&import_name) else {
+                return Err(StoreAcquireError::PinnedImportChanged {
+                    import_name,
+                    expected: pinned.clone(),
+                    actual: sha256,
+                });
+            };
+            warn!(
+                "The contents of the import '{import_name}' no longer match its pinned sha256, \
+                so the audits in imports.lock will be used\n\
+                Run `cargo vet import --update {import_name}` to review and accept the changes"
+            );
+            new_imports
+                .audits
+                .insert(import_name, existing_audits_file.clone());
+            continue;
+        }
+
+        // Filter before checking freshness, so that entries are compared with
+        // the filtered entries recorded in imports.lock.
+        if let Some(import) = config.imports.get(&import_name) {
+            filter_imported_audits(
+                &import_name,
+                import,
+                local_criteria_mapper,
+                &mut audits_file,
+            );
+        }
+
         if let Some(existing_audits_file) = imports_lock.audits.get(&import_name) {
             update_import_freshness(
                 &mut audits_file,
@@ -892,6 +1581,17 @@ fn process_imported_audits(
             );
         }
 
+        if let Some(rejected) = imports_lock.rejected.get(&import_name) {
+            remove_rejected_entries(&mut audits_file, rejected);
+        }
+        let require_review = config
+            .imports
+            .get(&import_name)
+            .map_or(false, |import| import.require_review);
+        if require_review {
+            remove_fresh_entries(&mut audits_file, imports_lock.audits.get(&import_name));
+        }
+
         // Now add the new import
         new_imports.audits.insert(import_name, audits_file);
     }
@@ -899,7 +1599,8 @@ fn process_imported_audits(
     if !changed_criteria.is_empty() {
         return Err(CriteriaChangeErrors {
             errors: changed_criteria,
-        });
+        }
+        .into());
     }
 
     // FIXME: Consider doing some additional validation on these audits
@@ -908,25 +1609,237 @@ fn process_imported_audits(
     Ok(new_imports)
 }
 
-fn update_import_freshness(
+/// Whether `text` matches `pattern`, in which `*` matches any sequence of
+/// characters.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = text.strip_prefix(parts.next().unwrap()) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        let Some(index) = rest.find(part) else {
+            return false;
+        };
+        rest = &rest[index + part.len()..];
+    }
+    rest.ends_with(last)
+}
+
+/// Whether the `who` filter of an import allows an entry by `who`.
+fn who_allowed(filter: &WhoFilter, who: &[Spanned<String>]) -> bool {
+    let matches = |patterns: &[String], who: &str| {
+        let email = who
+            .rsplit_once('<')
+            .and_then(|(_, email)| email.strip_suffix('>'));
+        patterns.iter().any(|pattern| {
+            glob_matches(pattern, who) || email.map_or(false, |email| glob_matches(pattern, email))
+        })
+    };
+    if who.iter().any(|who| matches(&filter.deny, who)) {
+        return false;
+    }
+    filter.allow.is_empty() || who.iter().any(|who| matches(&filter.allow, who))
+}
+
+/// A description of an audit of `package`, for listing entries which weren't
+/// imported.
+fn describe_audit(package: PackageStr<'_>, entry: &AuditEntry) -> String {
+    match &entry.kind {
+        AuditKind::Full { version } => format!("{package} {version}"),
+        AuditKind::Delta { from, to } => format!("{package} {from} -> {to}"),
+        AuditKind::Violation { violation } => format!("{package} violation {violation}"),
+    }
+}
+
+/// Remove any entries from an imported audits file which aren't allowed by
+/// the `include`, `allowed-criteria`, `who` and `scope` lists of its import,
+/// and restrict the criteria of the remaining entries to the allowed criteria.
+///
+/// Violations are only filtered by `include`, so that they aren't missed.
+fn filter_imported_audits(
+    import_name: &str,
+    import: &RemoteImport,
+    local_criteria_mapper: &CriteriaMapper,
     audits_file: &mut AuditsFile,
-    existing_audits_file: &AuditsFile,
-    mut on_changed_criteria_description: impl FnMut(CriteriaStr<'_>, &str, &str),
 ) {
-    // Compare the new criteria descriptions with existing criteria
-    // descriptions. If the description already exists, notify our caller.
-    for (criteria_name, old_entry) in &existing_audits_file.criteria {
-        if let Some(new_entry) = audits_file.criteria.get(criteria_name) {
-            let old_desc = old_entry.description.as_ref().unwrap();
-            let new_desc = new_entry.description.as_ref().unwrap();
-            if old_desc != new_desc {
-                on_changed_criteria_description(criteria_name, old_desc, new_desc);
+    let matches_any = |patterns: &[String], package: PackageStr<'_>| {
+        patterns
+            .iter()
+            .any(|pattern| glob_matches(pattern, package))
+    };
+    let included = |package: PackageStr<'_>| {
+        import.include.is_empty() || matches_any(&import.include, package)
+    };
+    let allowed_criteria = (!import.allowed_criteria.is_empty())
+        .then(|| local_criteria_mapper.criteria_from_list(&import.allowed_criteria));
+    // The criteria which the scopes covering an entry for `package` by `who`
+    // allow, where `who` is `None` for trusted entries.
+    let scope_criteria = |package: PackageStr<'_>, who: Option<&[Spanned<String>]>| {
+        let mut set = local_criteria_mapper.no_criteria();
+        for scope in &import.scope {
+            let covers_crate = scope.crates.is_empty() || matches_any(&scope.crates, package);
+            let covers_who = match who {
+                Some(who) => who_allowed(&scope.who, who),
+                None => scope.who.is_empty(),
+            };
+            if covers_crate && covers_who {
+                set.unioned_with(&local_criteria_mapper.criteria_from_list(&scope.criteria));
             }
         }
-    }
-
-    // Compare the new audits with existing audits. If an audit already
-    // existed in the existing audits file, mark it as non-fresh.
+        set
+    };
+    // Restrict `criteria` to those allowed, returning whether any remain.
+    let restrict_criteria = |criteria: &mut Vec<Spanned<CriteriaName>>,
+                             package: PackageStr<'_>,
+                             who: Option<&[Spanned<String>]>| {
+        if allowed_criteria.is_none() && import.scope.is_empty() {
+            return true;
+        }
+        let mut set = local_criteria_mapper.criteria_from_list(&*criteria);
+        if let Some(allowed_criteria) = &allowed_criteria {
+            set.intersected_with(allowed_criteria);
+        }
+        if !import.scope.is_empty() {
+            set.intersected_with(&scope_criteria(package, who));
+        }
+        *criteria = local_criteria_mapper
+            .criteria_names(&set)
+            .map(|name| name.to_owned().into())
+            .collect();
+        !criteria.is_empty()
+    };
+
+    let mut filtered = Vec::new();
+    for (package, audits) in &mut audits_file.audits {
+        audits.retain_mut(|entry| {
+            let reason = if !included(package) {
+                "crate not included"
+            } else if matches!(entry.kind, AuditKind::Violation { .. }) {
+                return true;
+            } else if !who_allowed(&import.who, &entry.who) {
+                "auditor not allowed"
+            } else if !restrict_criteria(&mut entry.criteria, package, Some(&entry.who)) {
+                "criteria not allowed"
+            } else {
+                return true;
+            };
+            filtered.push(format!("{}: {reason}", describe_audit(package, entry)));
+            false
+        });
+    }
+    for (package, entries) in &mut audits_file.wildcard_audits {
+        entries.retain_mut(|entry| {
+            let reason = if !included(package) {
+                "crate not included"
+            } else if !who_allowed(&import.who, &entry.who) {
+                "auditor not allowed"
+            } else if !restrict_criteria(&mut entry.criteria, package, Some(&entry.who)) {
+                "criteria not allowed"
+            } else {
+                return true;
+            };
+            filtered.push(format!("{package} wildcard audit: {reason}"));
+            false
+        });
+    }
+    for (package, entries) in &mut audits_file.trusted {
+        entries.retain_mut(|entry| {
+            let reason = if !included(package) {
+                "crate not included"
+            } else if !restrict_criteria(&mut entry.criteria, package, None) {
+                "criteria not allowed"
+            } else {
+                return true;
+            };
+            filtered.push(format!("{package} trusted entry: {reason}"));
+            false
+        });
+    }
+    audits_file.audits.retain(|_, audits| !audits.is_empty());
+    audits_file
+        .wildcard_audits
+        .retain(|_, entries| !entries.is_empty());
+    audits_file.trusted.retain(|_, entries| !entries.is_empty());
+
+    if !filtered.is_empty() {
+        info!(
+            "Filtered out {} entries when importing from '{}' which its allow-lists don't allow:\n  {}",
+            filtered.len(),
+            import_name,
+            filtered.join("\n  ")
+        );
+    }
+}
+
+/// Remove any entries from an imported audits file which were rejected when
+/// reviewing the import.
+pub fn remove_rejected_entries(audits_file: &mut AuditsFile, rejected: &AuditsFile) {
+    for (package, rejected) in &rejected.audits {
+        if let Some(audits) = audits_file.audits.get_mut(package) {
+            audits.retain(|audit| !rejected.iter().any(|r| r.same_audit_as(audit)));
+        }
+    }
+    for (package, rejected) in &rejected.wildcard_audits {
+        if let Some(audits) = audits_file.wildcard_audits.get_mut(package) {
+            audits.retain(|audit| !rejected.iter().any(|r| r.same_audit_as(audit)));
+        }
+    }
+    for (package, rejected) in &rejected.trusted {
+        if let Some(entries) = audits_file.trusted.get_mut(package) {
+            entries.retain(|entry| !rejected.iter().any(|r| r.same_trust_as(entry)));
+        }
+    }
+}
+
+/// Remove any entries from an imported audits file which aren't already
+/// recorded in imports.lock, as `existing_audits_file`.
+fn remove_fresh_entries(audits_file: &mut AuditsFile, existing_audits_file: Option<&AuditsFile>) {
+    // Violations only ever prevent crates from being vetted, so are never held
+    // back.
+    for audits in audits_file.audits.values_mut() {
+        audits.retain(|audit| {
+            !audit.is_fresh_import || matches!(audit.kind, AuditKind::Violation { .. })
+        });
+    }
+    for audits in audits_file.wildcard_audits.values_mut() {
+        audits.retain(|audit| !audit.is_fresh_import);
+    }
+    // Trusted entries don't track freshness, as they're only recorded in
+    // imports.lock for imports which require review.
+    audits_file.trusted.retain(|package, entries| {
+        let existing = existing_audits_file.and_then(|existing| existing.trusted.get(package));
+        entries.retain(|entry| {
+            existing.map_or(false, |existing| {
+                existing.iter().any(|e| e.same_trust_as(entry))
+            })
+        });
+        !entries.is_empty()
+    });
+}
+
+fn update_import_freshness(
+    audits_file: &mut AuditsFile,
+    existing_audits_file: &AuditsFile,
+    mut on_changed_criteria_description: impl FnMut(CriteriaStr<'_>, &str, &str),
+) {
+    // Compare the new criteria descriptions with existing criteria
+    // descriptions. If the description already exists, notify our caller.
+    for (criteria_name, old_entry) in &existing_audits_file.criteria {
+        if let Some(new_entry) = audits_file.criteria.get(criteria_name) {
+            let old_desc = old_entry.description.as_ref().unwrap();
+            let new_desc = new_entry.description.as_ref().unwrap();
+            if old_desc != new_desc {
+                on_changed_criteria_description(criteria_name, old_desc, new_desc);
+            }
+        }
+    }
+
+    // Compare the new audits with existing audits. If an audit already
+    // existed in the existing audits file, mark it as non-fresh.
     for (package, existing_audits) in &existing_audits_file.audits {
         let new_audits = audits_file
             .audits
@@ -959,108 +1872,643 @@ fn update_import_freshness(
     }
 }
 
+/// An import which has been fetched from the network.
+struct FetchedImport {
+    import_name: ImportName,
+    audits_file: AuditsFile,
+    /// The hash of the import's contents, to compare with any pinned hash.
+    sha256: String,
+}
+
 /// Fetch all declared imports from the network, mapping criteria to the local
 /// namespace, and filling in any criteria descriptions.
+///
+/// Without a `network`, only the imports which are read entirely from local
+/// files or git repositories, and which aren't transitive, are fetched. Any of
+/// those which still need the network, such as to fetch a criteria
+/// description, are skipped with a warning.
 async fn fetch_imported_audits(
-    network: &Network,
+    network: Option<&Network>,
+    root: Option<&Path>,
     local_criteria_mapper: &CriteriaMapper,
     config: &ConfigFile,
-) -> Result<Vec<(ImportName, AuditsFile)>, Box<FetchAuditError>> {
-    let progress_bar = progress_bar("Fetching", "imported audits", config.imports.len() as u64);
-    try_join_all(config.imports.iter().map(|(name, import)| async {
-        let _guard = IncProgressOnDrop(&progress_bar, 1);
-        let audit_file = fetch_imported_audit(
+) -> Result<Vec<FetchedImport>, Box<FetchAuditError>> {
+    let imports = config
+        .imports
+        .iter()
+        .filter(|(_, import)| {
+            network.is_some()
+                || (import.transitive_depth_limit() == 0
+                    && import.url.iter().all(|url| is_local_import_source(url)))
+        })
+        .collect::<Vec<_>>();
+    let progress_bar = &progress_bar("Fetching", "imported audits", imports.len() as u64);
+    let fetched = try_join_all(imports.into_iter().map(|(name, import)| async move {
+        let _guard = IncProgressOnDrop(progress_bar, 1);
+        let result = fetch_imported_audit(
             network,
+            root,
             local_criteria_mapper,
             name,
             &import.url,
             &import.exclude,
             &import.criteria_map,
+            &import.trusted_keys,
+            import.transitive_depth_limit(),
         )
-        .await
-        .map_err(Box::new)?;
-        Ok::<_, Box<FetchAuditError>>((name.clone(), audit_file))
+        .await;
+        let (audits_file, sha256) = match result {
+            Err(FetchAuditError::Offline { import_url, .. }) => {
+                warn!(
+                    "The import '{name}' needs to fetch '{import_url}' from the network, \
+                    so the audits in imports.lock will be used while offline"
+                );
+                return Ok(None);
+            }
+            result => result.map_err(Box::new)?,
+        };
+        Ok::<_, Box<FetchAuditError>>(Some(FetchedImport {
+            import_name: name.clone(),
+            audits_file,
+            sha256,
+        }))
     }))
-    .await
+    .await?;
+    Ok(fetched.into_iter().flatten().collect())
+}
+
+/// Fetch the hex-encoded SHA-256 hash of the contents of an import, which is
+/// taken over the URL and contents of each of its sources in order.
+pub async fn fetch_import_sha256(
+    network: Option<&Network>,
+    root: Option<&Path>,
+    name: &str,
+    urls: &[String],
+) -> Result<String, FetchAuditError> {
+    let sources = try_join_all(
+        urls.iter()
+            .map(|url| read_import_source(network, root, name, url)),
+    )
+    .await?;
+    Ok(import_sha256(&sources))
+}
+
+/// Each URL and its contents are prefixed with their length in bytes, as a
+/// little-endian u64, so that moving bytes between sources changes the hash.
+fn import_sha256(sources: &[SourceFile]) -> String {
+    let mut hasher = Sha256::new();
+    for source in sources {
+        for part in [source.name(), source.source()] {
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part);
+        }
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// The file read from a local git repository if its URL doesn't specify one.
+const DEFAULT_GIT_IMPORT_PATH: &str = "supply-chain/audits.toml";
+
+/// Whether a source of an import is read locally, rather than from the
+/// network, so can be read while offline.
+fn is_local_import_source(url: &str) -> bool {
+    url.starts_with("git+")
+        || match Url::parse(url) {
+            Ok(parsed_url) => parsed_url.scheme() == "file",
+            Err(error) => error == url::ParseError::RelativeUrlWithoutBase,
+        }
+}
+
+/// Read one of the sources of an import. In addition to being downloaded from
+/// the network, sources may be read from `file://` URLs, from paths relative to
+/// `root`, or from a local git repository at a specific revision, like
+/// `git+file:///path/to/repo?path=supply-chain/audits.toml#rev`. The path to
+/// the git repository may also be relative to `root`, like
+/// `git+file:../repo#rev`.
+///
+/// If `root` is `None`, only sources on the network are permitted, and if
+/// `network` is `None`, only local sources are.
+async fn read_import_source(
+    network: Option<&Network>,
+    root: Option<&Path>,
+    name: &str,
+    url: &str,
+) -> Result<SourceFile, FetchAuditError> {
+    let invalid_url = |error| FetchAuditError::InvalidUrl {
+        import_url: url.to_owned(),
+        import_name: name.to_owned(),
+        error,
+    };
+    let root = || {
+        root.ok_or_else(|| FetchAuditError::LocalSourceNotAllowed {
+            import_name: name.to_owned(),
+            import_url: url.to_owned(),
+        })
+    };
+    let local_path = |path: PathBuf| Ok::<_, FetchAuditError>(root()?.join(path));
+
+    if let Some(git_url) = url.strip_prefix("git+") {
+        let parsed_url = match git_url.strip_prefix("file:") {
+            Some(relative) if !relative.starts_with('/') => {
+                // Resolve the relative path as a URL reference, so that the
+                // query and fragment are parsed in the same way.
+                let root = root()?;
+                let base = root
+                    .canonicalize()
+                    .map_err(|error| FetchAuditError::ReadFile {
+                        import_name: name.to_owned(),
+                        path: root.to_owned(),
+                        error,
+                    })?;
+                Url::from_directory_path(base)
+                    .map_err(|()| invalid_url(url::ParseError::RelativeUrlWithoutBase))?
+                    .join(relative)
+                    .map_err(invalid_url)?
+            }
+            _ => Url::parse(git_url).map_err(invalid_url)?,
+        };
+        let repo = match parsed_url.to_file_path() {
+            Ok(repo) if parsed_url.scheme() == "file" => local_path(repo)?,
+            _ => {
+                return Err(FetchAuditError::UnsupportedGitUrl {
+                    import_name: name.to_owned(),
+                    import_url: url.to_owned(),
+                })
+            }
+        };
+        let path = parsed_url
+            .query_pairs()
+            .find(|(key, _)| key == "path")
+            .map(|(_, path)| path.into_owned())
+            .unwrap_or_else(|| DEFAULT_GIT_IMPORT_PATH.to_owned());
+        let rev = parsed_url.fragment().unwrap_or("HEAD");
+        // The revision is passed to git as an argument, so must not be
+        // mistaken for an option.
+        if rev.is_empty() || rev.starts_with('-') {
+            return Err(FetchAuditError::InvalidGitRev {
+                import_name: name.to_owned(),
+                import_url: url.to_owned(),
+                rev: rev.to_owned(),
+            });
+        }
+
+        let git = |args: &[&OsStr]| {
+            let mut command = tokio::process::Command::new("git");
+            command.arg("-C").arg(&repo).args(args);
+            async move {
+                let output = command
+                    .output()
+                    .await
+                    .map_err(|error| FetchAuditError::GitSpawn {
+                        import_name: name.to_owned(),
+                        import_url: url.to_owned(),
+                        error,
+                    })?;
+                if !output.status.success() {
+                    return Err(FetchAuditError::GitShow {
+                        import_name: name.to_owned(),
+                        import_url: url.to_owned(),
+                        stderr: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+                    });
+                }
+                Ok(output.stdout)
+            }
+        };
+        let commit = git(&[
+            "rev-parse".as_ref(),
+            "--verify".as_ref(),
+            "--end-of-options".as_ref(),
+            format!("{rev}^{{commit}}").as_ref(),
+        ])
+        .await?;
+        let commit = String::from_utf8_lossy(&commit).trim().to_owned();
+        let source = git(&["show".as_ref(), format!("{commit}:{path}").as_ref()]).await?;
+        let source = String::from_utf8(source).map_err(|error| FetchAuditError::ReadFile {
+            import_name: name.to_owned(),
+            path: repo.join(&path),
+            error: io::Error::new(io::ErrorKind::InvalidData, error),
+        })?;
+        return Ok(SourceFile::new(url, source));
+    }
+
+    let path = match Url::parse(url) {
+        Ok(parsed_url) if parsed_url.scheme() == "file" => {
+            let path = parsed_url
+                .to_file_path()
+                .map_err(|()| invalid_url(url::ParseError::InvalidDomainCharacter))?;
+            local_path(path)?
+        }
+        Ok(parsed_url) => {
+            let Some(network) = network else {
+                return Err(FetchAuditError::Offline {
+                    import_name: name.to_owned(),
+                    import_url: url.to_owned(),
+                });
+            };
+            return Ok(network.download_source_file_cached(parsed_url).await?);
+        }
+        Err(url::ParseError::RelativeUrlWithoutBase) => local_path(url.into())?,
+        Err(error) => return Err(invalid_url(error)),
+    };
+    let source =
+        tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|error| FetchAuditError::ReadFile {
+                import_name: name.to_owned(),
+                path,
+                error,
+            })?;
+    Ok(SourceFile::new(url, source))
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn fetch_imported_audit(
-    network: &Network,
+    network: Option<&Network>,
+    root: Option<&Path>,
     local_criteria_mapper: &CriteriaMapper,
     name: &str,
     urls: &[String],
     exclude: &[PackageName],
     criteria_map: &CriteriaMap,
-) -> Result<AuditsFile, FetchAuditError> {
+    trusted_keys: &[String],
+    transitive_depth: usize,
+) -> Result<(AuditsFile, String), FetchAuditError> {
     // Fetch all imported URLs, and then aggregate them.
-    let sources = try_join_all(urls.iter().map(|url| async {
-        fetch_single_imported_audit(
-            network,
-            local_criteria_mapper,
-            name,
-            url,
-            exclude,
-            criteria_map,
-        )
-        .await
-        .map(|audits_file| (url.clone(), audits_file))
-    }))
+    let audit_sources = try_join_all(
+        urls.iter()
+            .map(|url| read_import_source(network, root, name, url)),
+    )
+    .await?;
+    let sha256 = import_sha256(&audit_sources);
+    let sources = try_join_all(
+        urls.iter()
+            .zip(audit_sources)
+            .map(|(url, audit_source)| async {
+                fetch_single_imported_audit(
+                    network,
+                    root,
+                    local_criteria_mapper,
+                    name,
+                    audit_source,
+                    exclude,
+                    criteria_map,
+                    trusted_keys,
+                    transitive_depth,
+                )
+                .await
+                .map(|audits_file| (url.clone(), audits_file))
+            }),
+    )
     .await?;
 
     // If we only have a single source, don't aggregate so that we don't add
     // unnecessary `aggregated-from` members.
     if sources.len() == 1 {
-        Ok(sources.into_iter().next().unwrap().1)
+        Ok((sources.into_iter().next().unwrap().1, sha256))
     } else {
-        crate::do_aggregate_audits(sources).map_err(|error| FetchAuditError::Aggregate {
-            import_name: name.to_owned(),
-            errors: error
-                .errors
-                .into_iter()
-                .map(|err| match err {
-                    AggregateError::CriteriaDescriptionMismatch(mismatch) => {
-                        FetchAuditAggregateError {
-                            mapped_to: criteria_map
-                                .get(&mismatch.criteria_name)
-                                .cloned()
-                                .unwrap_or_default(),
-                            criteria_name: mismatch.criteria_name,
-                            first: mismatch.first,
-                            second: mismatch.second,
+        let audits_file =
+            crate::do_aggregate_audits(sources).map_err(|error| FetchAuditError::Aggregate {
+                import_name: name.to_owned(),
+                errors: error
+                    .errors
+                    .into_iter()
+                    .map(|err| match err {
+                        AggregateError::CriteriaDescriptionMismatch(mismatch) => {
+                            FetchAuditAggregateError {
+                                mapped_to: criteria_map
+                                    .get(&mismatch.criteria_name)
+                                    .cloned()
+                                    .unwrap_or_default(),
+                                criteria_name: mismatch.criteria_name,
+                                first: mismatch.first,
+                                second: mismatch.second,
+                            }
+                        }
+                        AggregateError::ImpliesMismatch(_) => {
+                            unreachable!("implies is stripped by fetch_single_imported_audit")
                         }
+                    })
+                    .collect(),
+            })?;
+        Ok((audits_file, sha256))
+    }
+}
+
+/// The URL of the `config.toml` next to the `audits.toml` at `audits_url`, if
+/// there is one.
+fn sibling_config_url(audits_url: &str) -> Option<String> {
+    if let Some(git_url) = audits_url.strip_prefix("git+") {
+        // The repository's path may be relative, so the URL is split up by
+        // hand rather than parsed.
+        let (git_url, rev) = match git_url.split_once('#') {
+            Some((git_url, rev)) => (git_url, Some(rev)),
+            None => (git_url, None),
+        };
+        let (repo, query) = git_url.split_once('?').unwrap_or((git_url, ""));
+        let path = url::form_urlencoded::parse(query.as_bytes())
+            .find(|(key, _)| key == "path")
+            .map(|(_, path)| path.into_owned())
+            .unwrap_or_else(|| DEFAULT_GIT_IMPORT_PATH.to_owned());
+        let config_path = format!("{}config.toml", path.strip_suffix("audits.toml")?);
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("path", &config_path)
+            .finish();
+        let rev = rev.map(|rev| format!("#{rev}")).unwrap_or_default();
+        return Some(format!("git+{repo}?{query}{rev}"));
+    }
+    match Url::parse(audits_url) {
+        Ok(mut parsed_url) => {
+            let path = parsed_url.path().strip_suffix("audits.toml")?.to_owned();
+            parsed_url.set_path(&format!("{path}config.toml"));
+            Some(parsed_url.into())
+        }
+        Err(_) => Some(format!(
+            "{}config.toml",
+            audits_url.strip_suffix("audits.toml")?
+        )),
+    }
+}
+
+/// Fetch the audits imported by the peer whose `audits.toml` is at
+/// `audits_url`, as listed in the `config.toml` next to it, and merge them into
+/// that peer's `audit_file`, mapped into its criteria namespace. The peer's own
+/// `exclude`, `include`, `allowed-criteria`, `who` and `scope` lists are
+/// applied, and the name of the peer's import is appended to the
+/// `aggregated-from` chain of each merged entry.
+///
+/// Imports which the peer imports transitively are followed up to `depth`
+/// imports deep, skipping any import which leads back to one of the `visited`
+/// sources, which no longer matches the sha256 the peer pinned it to, or which
+/// is both pinned and transitive. As a peer's imports are outside of our
+/// control, they may only be fetched from the network, and any failure to fetch
+/// them is reported as a warning rather than an error.
+///
+/// `trusted_keys` holds the keys of every import along the chain leading to
+/// this peer, and entries are checked against each of them as well as against
+/// those of the peer's import, as the signatures can't be checked once they're
+/// merged.
+#[allow(clippy::too_many_arguments)]
+fn fetch_transitive_imports<'a>(
+    network: &'a Network,
+    root: Option<&'a Path>,
+    name: &'a str,
+    audits_url: &'a str,
+    audit_file: &'a mut AuditsFile,
+    trusted_keys: &'a [&'a [String]],
+    depth: usize,
+    visited: &'a [String],
+) -> LocalBoxFuture<'a, ()> {
+    async move {
+        let Some(config_url) = sibling_config_url(audits_url) else {
+            warn!(
+                "Couldn't find the config.toml next to '{audits_url}' to transitively \
+                import from '{name}'"
+            );
+            return;
+        };
+        // The peer's config.toml is read from the same place as its audits.
+        let config_source = match read_import_source(Some(network), root, name, &config_url).await
+        {
+            Ok(config_source) => config_source,
+            Err(error) => {
+                warn!("Couldn't fetch '{config_url}' to transitively import from '{name}': {error}");
+                return;
+            }
+        };
+        let imports = match toml::de::from_str::<toml::value::Table>(config_source.source()) {
+            Ok(mut config) => config.remove("imports"),
+            Err(error) => {
+                warn!("Couldn't parse '{config_url}' to transitively import from '{name}': {error}");
+                return;
+            }
+        };
+        let Some(toml::Value::Table(imports)) = imports else {
+            return;
+        };
+
+        let peer_criteria_mapper = CriteriaMapper::new(&audit_file.criteria);
+        for (import_name, import) in imports {
+            // Parse each import independently, so that imports using newer
+            // features of cargo-vet don't prevent using the others.
+            let mut import: RemoteImport = match parse_from_value(import) {
+                Ok(import) => import,
+                Err(error) => {
+                    warn!(
+                        "Ignored the import '{import_name}' of '{name}', as it couldn't be parsed: {error}"
+                    );
+                    continue;
+                }
+            };
+            let scope_criteria = import.scope.iter().flat_map(|scope| &scope.criteria);
+            if import
+                .allowed_criteria
+                .iter()
+                .chain(scope_criteria)
+                .any(|allowed| {
+                    !peer_criteria_mapper
+                        .all_criteria_names()
+                        .any(|name| name == allowed.as_str())
+                })
+            {
+                warn!(
+                    "Ignored the import '{import_name}' of '{name}', as it allows undefined criteria"
+                );
+                continue;
+            }
+
+            // Relative imports are relative to the peer's config.toml.
+            for url in &mut import.url {
+                if let (Err(url::ParseError::RelativeUrlWithoutBase), Ok(base)) =
+                    (Url::parse(url), Url::parse(&config_url))
+                {
+                    if let Ok(joined) = base.join(url) {
+                        *url = joined.into();
                     }
-                    AggregateError::ImpliesMismatch(_) => {
-                        unreachable!("implies is stripped by fetch_single_imported_audit")
+                }
+            }
+            // The peer's pin only covers the audits it imports directly, so we
+            // can't tell whether those imported through it have changed.
+            if import.sha256.is_some() && import.transitive {
+                warn!(
+                    "Ignored the import '{import_name}' of '{name}', as it is pinned to a sha256 \
+                    while also being transitive"
+                );
+                continue;
+            }
+            if let Some(url) = import.url.iter().find(|url| visited.contains(url)) {
+                warn!(
+                    "Ignored the import '{import_name}' of '{name}', as importing '{url}' \
+                    would be a cycle"
+                );
+                continue;
+            }
+            let visited = visited
+                .iter()
+                .chain(&import.url)
+                .cloned()
+                .collect::<Vec<_>>();
+            let trusted_keys = trusted_keys
+                .iter()
+                .copied()
+                .chain([&import.trusted_keys[..]])
+                .collect::<Vec<_>>();
+
+            let audit_sources = match try_join_all(
+                import
+                    .url
+                    .iter()
+                    .map(|url| read_import_source(Some(network), None, &import_name, url)),
+            )
+            .await
+            {
+                Ok(audit_sources) => audit_sources,
+                Err(error) => {
+                    warn!("Couldn't fetch the import '{import_name}' of '{name}': {error}");
+                    continue;
+                }
+            };
+            // We can't see the audits the peer has locked, so if the import
+            // has changed since the peer pinned it, skip it entirely.
+            if import
+                .sha256
+                .as_ref()
+                .map_or(false, |pinned| *pinned != import_sha256(&audit_sources))
+            {
+                warn!(
+                    "Ignored the import '{import_name}' of '{name}', as its contents no longer \
+                    match the sha256 it is pinned to"
+                );
+                continue;
+            }
+
+            let mut import_audit_files = Vec::new();
+            for audit_source in audit_sources {
+                let url = audit_source.name().to_owned();
+                let mut import_audit_file = match parse_imported_audit_source(
+                    &import_name,
+                    audit_source,
+                    &import.exclude,
+                    &trusted_keys,
+                )
+                .await
+                {
+                    Ok(import_audit_file) => import_audit_file,
+                    Err(error) => {
+                        warn!(
+                            "Couldn't parse the import '{import_name}' of '{name}' from '{url}': {error}"
+                        );
+                        continue;
                     }
-                })
-                .collect(),
-        })
+                };
+                let depth = (depth - 1).min(import.transitive_depth_limit());
+                if depth > 0 {
+                    fetch_transitive_imports(
+                        network,
+                        None,
+                        &import_name,
+                        &url,
+                        &mut import_audit_file,
+                        &trusted_keys,
+                        depth,
+                        &visited,
+                    )
+                    .await;
+                }
+                import_audit_files.push(import_audit_file);
+            }
+
+            for mut import_audit_file in import_audit_files {
+                map_imported_criteria(
+                    &mut import_audit_file,
+                    &peer_criteria_mapper,
+                    &import.criteria_map,
+                );
+                filter_imported_audits(
+                    &import_name,
+                    &import,
+                    &peer_criteria_mapper,
+                    &mut import_audit_file,
+                );
+                for (package, entries) in import_audit_file.audits {
+                    audit_file.audits.entry(package).or_default().extend(
+                        entries.into_iter().map(|mut entry| {
+                            entry.aggregated_from.push(import_name.clone().into());
+                            entry
+                        }),
+                    );
+                }
+                for (package, entries) in import_audit_file.wildcard_audits {
+                    audit_file.wildcard_audits.entry(package).or_default().extend(
+                        entries.into_iter().map(|mut entry| {
+                            entry.aggregated_from.push(import_name.clone().into());
+                            entry
+                        }),
+                    );
+                }
+                for (package, entries) in import_audit_file.trusted {
+                    audit_file.trusted.entry(package).or_default().extend(
+                        entries.into_iter().map(|mut entry| {
+                            entry.aggregated_from.push(import_name.clone().into());
+                            entry
+                        }),
+                    );
+                }
+            }
+        }
     }
+    .boxed_local()
 }
 
-/// Fetch a single AuditsFile from the network, filling in any criteria
-/// descriptions.
-async fn fetch_single_imported_audit(
-    network: &Network,
+/// Fetch the audits of a peer in the registry through the cache, to browse
+/// them without importing them. The audits of each of the peer's sources are
+/// returned separately, and are left in the peer's criteria namespace.
+///
+/// The audits are also added to the audit index, with their criteria mapped
+/// into the local namespace as if they were imported.
+pub async fn fetch_registry_peer_audits(
+    network: Option<&Network>,
+    cache: &Cache,
     local_criteria_mapper: &CriteriaMapper,
     name: &str,
-    url: &str,
+    entry: &RegistryEntry,
+) -> Result<Vec<AuditsFile>, FetchRegistryError> {
+    let audits_files = try_join_all(entry.url.iter().map(|url| async move {
+        let audit_source = cache.fetch_registry_file(network, url).await?;
+        parse_imported_audit_source(name, audit_source, &[], &[])
+            .await
+            .map_err(|error| FetchRegistryError::PeerAudits {
+                import_name: name.to_owned(),
+                error: Box::new(error),
+            })
+    }))
+    .await?;
+
+    let mut local_audits_files = audits_files.clone();
+    for audits_file in &mut local_audits_files {
+        map_imported_criteria(audits_file, local_criteria_mapper, &SortedMap::new());
+    }
+    cache.update_audit_index(name, &local_audits_files);
+    Ok(audits_files)
+}
+
+/// Parse a single AuditsFile read from one of an import's sources, verifying
+/// its signatures and removing excluded entries. The audits are left in the
+/// foreign criteria namespace.
+///
+/// Each non-empty list of `trusted_keys` must have signed every audit which is
+/// kept.
+async fn parse_imported_audit_source(
+    name: &str,
+    audit_source: SourceFile,
     exclude: &[PackageName],
-    criteria_map: &CriteriaMap,
+    trusted_keys: &[&[String]],
 ) -> Result<AuditsFile, FetchAuditError> {
-    let parsed_url = Url::parse(url).map_err(|error| FetchAuditError::InvalidUrl {
-        import_url: url.to_owned(),
-        import_name: name.to_owned(),
-        error,
-    })?;
-    let audit_source = network.download_source_file_cached(parsed_url).await?;
-
     // Attempt to parse each criteria and audit independently, to allow
     // recovering from parsing or validation errors on a per-entry basis when
     // importing audits. This reduces the risk of an upstream vendor adopting a
     // new cargo-vet feature breaking projects still using an older version of
     // cargo-vet.
-    let foreign_audit_file: ForeignAuditsFile = toml::de::from_str(audit_source.source())
+    let mut foreign_audit_file: ForeignAuditsFile = toml::de::from_str(audit_source.source())
         .map_err(|error| {
             let (line, col) = error.line_col().unwrap_or((0, 0));
             TomlParseError {
@@ -1070,6 +2518,15 @@ async fn fetch_single_imported_audit(
             }
         })
         .map_err(LoadTomlError::from)?;
+
+    // If the import requires signed audits, check signatures before anything
+    // else, as the signed contents of each entry will be modified below.
+    for trusted_keys in trusted_keys {
+        if !trusted_keys.is_empty() {
+            verify_foreign_audits(name, &mut foreign_audit_file, trusted_keys).await?;
+        }
+    }
+
     let ForeignAuditFileToLocalResult {
         mut audit_file,
         ignored_criteria,
@@ -1107,6 +2564,17 @@ async fn fetch_single_imported_audit(
         audit_file.audits.remove(excluded);
     }
 
+    Ok(audit_file)
+}
+
+/// Re-write the criteria of the entries in an imported audits file from the
+/// foreign criteria namespace into the namespace of `local_criteria_mapper`,
+/// based on `criteria_map`, and mark them as fresh imports.
+fn map_imported_criteria(
+    audit_file: &mut AuditsFile,
+    local_criteria_mapper: &CriteriaMapper,
+    criteria_map: &CriteriaMap,
+) {
     // Construct a mapping from the foreign criteria namespace into the
     // local criteria namespace based on the criteria map from the config.
     let foreign_criteria_mapper = CriteriaMapper::new(&audit_file.criteria);
@@ -1116,7 +2584,14 @@ async fn fetch_single_imported_audit(
             // NOTE: We try the map before we check for built-in criteria to
             // allow overriding the default behaviour.
             if let Some(mapped) = criteria_map.get(foreign_name) {
-                local_criteria_mapper.criteria_from_list(mapped)
+                // The criteria maps of transitive imports haven't been
+                // validated by us, so ignore any criteria they map to which
+                // don't exist.
+                local_criteria_mapper.criteria_from_list(mapped.iter().filter(|mapped| {
+                    local_criteria_mapper
+                        .all_criteria_names()
+                        .any(|name| name == mapped.as_str())
+                }))
             } else if foreign_name == SAFE_TO_DEPLOY {
                 local_criteria_mapper.criteria_from_list([SAFE_TO_DEPLOY])
             } else if foreign_name == SAFE_TO_RUN {
@@ -1161,6 +2636,45 @@ async fn fetch_single_imported_audit(
     for trust_entry in audit_file.trusted.values_mut().flat_map(|v| v.iter_mut()) {
         make_criteria_local(&mut trust_entry.criteria);
     }
+}
+
+/// Parse a single AuditsFile read from one of an import's sources, merging in
+/// any transitively imported audits, mapping its criteria into the local
+/// namespace, and filling in any criteria descriptions from the network.
+#[allow(clippy::too_many_arguments)]
+async fn fetch_single_imported_audit(
+    network: Option<&Network>,
+    root: Option<&Path>,
+    local_criteria_mapper: &CriteriaMapper,
+    name: &str,
+    audit_source: SourceFile,
+    exclude: &[PackageName],
+    criteria_map: &CriteriaMap,
+    trusted_keys: &[String],
+    transitive_depth: usize,
+) -> Result<AuditsFile, FetchAuditError> {
+    let audits_url = audit_source.name().to_owned();
+    let mut audit_file =
+        parse_imported_audit_source(name, audit_source, exclude, &[trusted_keys]).await?;
+
+    // Merge in the audits this peer imports while the audits are still in
+    // its criteria namespace, so that the criteria map below composes with
+    // the criteria maps of its imports.
+    if let Some(network) = network.filter(|_| transitive_depth > 0) {
+        fetch_transitive_imports(
+            network,
+            root,
+            name,
+            &audits_url,
+            &mut audit_file,
+            &[trusted_keys],
+            transitive_depth,
+            std::slice::from_ref(&audits_url),
+        )
+        .await;
+    }
+
+    map_imported_criteria(&mut audit_file, local_criteria_mapper, criteria_map);
 
     // Now that we're done with foreign criteria, trim the set to only
     // contain mapped criteria, as we don't care about other criteria, so
@@ -1195,6 +2709,12 @@ async fn fetch_single_imported_audit(
                         error,
                     }
                 })?;
+                let Some(network) = network else {
+                    return Err(FetchAuditError::Offline {
+                        import_name: name.to_owned(),
+                        import_url: url_string.clone(),
+                    });
+                };
                 let bytes = network.download(url.clone()).await?;
                 let description =
                     String::from_utf8(bytes).map_err(|error| DownloadError::InvalidText {
@@ -1629,10 +3149,27 @@ async fn import_publisher_versions(
     Ok(())
 }
 
-pub async fn fetch_registry(network: &Network) -> Result<RegistryFile, FetchRegistryError> {
-    let registry_url = Url::parse(REGISTRY_URL).unwrap();
-    let registry_source = network.download_source_file_cached(registry_url).await?;
-    let registry_file: RegistryFile = toml::de::from_str(registry_source.source())
+/// The absolute path of the directory containing the registry at
+/// `registry_url`, if it is a local file rather than on the network.
+fn local_registry_dir(registry_url: &str) -> Option<PathBuf> {
+    let path = match Url::parse(registry_url) {
+        Ok(parsed_url) if parsed_url.scheme() == "file" => parsed_url.to_file_path().ok()?,
+        Ok(_) => return None,
+        Err(_) => std::env::current_dir().ok()?.join(registry_url),
+    };
+    path.parent().map(Path::to_owned)
+}
+
+/// Fetch the registry of peers at `registry_url`. Relative URLs in the registry
+/// are resolved against `registry_url`, so that a local mirror of the registry
+/// can refer to local copies of the audits of each peer.
+pub async fn fetch_registry(
+    network: Option<&Network>,
+    cache: &Cache,
+    registry_url: &str,
+) -> Result<RegistryFile, FetchRegistryError> {
+    let registry_source = cache.fetch_registry_file(network, registry_url).await?;
+    let mut registry_file: RegistryFile = toml::de::from_str(registry_source.source())
         .map_err(|error| {
             let (line, col) = error.line_col().unwrap_or((0, 0));
             TomlParseError {
@@ -1642,6 +3179,19 @@ pub async fn fetch_registry(network: &Network) -> Result<RegistryFile, FetchRegi
             }
         })
         .map_err(LoadTomlError::from)?;
+    for url in registry_file
+        .registry
+        .values_mut()
+        .flat_map(|entry| &mut entry.url)
+    {
+        if let Err(url::ParseError::RelativeUrlWithoutBase) = Url::parse(url) {
+            *url = match (Url::parse(registry_url), local_registry_dir(registry_url)) {
+                (Ok(base), _) => base.join(url).map_or_else(|_| url.clone(), String::from),
+                (Err(_), Some(dir)) => dir.join(&*url).display().to_string(),
+                (Err(_), None) => url.clone(),
+            };
+        }
+    }
     Ok(registry_file)
 }
 
@@ -1665,12 +3215,23 @@ struct CacheState {
     command_history: CommandHistory,
     /// Cache of fetched info from crates.io.
     crates_cache: CratesCache,
+    /// Cache of files fetched from the registry.
+    registry_cache: RegistryCache,
+    /// Index of the audits from every peer which has been fetched.
+    audit_index: AuditIndex,
+    /// Checksums of crates.io packages recorded in `Cargo.lock`, read on first
+    /// use.
+    lockfile_checksums: Option<FastMap<(PackageName, semver::Version), String>>,
     /// Paths for unpacked packages from this version.
     fetched_packages: FastMap<(String, VetVersion), Arc<tokio::sync::OnceCell<PathBuf>>>,
     /// Computed diffstats from this version.
-    diffed: FastMap<(String, Delta), Arc<tokio::sync::OnceCell<DiffStat>>>,
+    diffed: FastMap<(String, Delta, DiffScope), Arc<tokio::sync::OnceCell<DiffStat>>>,
 }
 
+/// The diffstat of two unpacked packages, the pairs of files which differ, and
+/// the files left out of the diff as they aren't shipped, with their diffstats.
+pub type UnpackedDiff = (DiffStat, Vec<(PathBuf, PathBuf)>, Vec<(PathBuf, DiffStat)>);
+
 /// The cache where we store globally shared artifacts like fetched packages and diffstats
 ///
 /// All access to this directory should be managed by this type to avoid races.
@@ -1685,10 +3246,18 @@ pub struct Cache {
     command_history_path: Option<PathBuf>,
     /// Path to the CratesCache (for when we want to save it back)
     publisher_cache_path: Option<PathBuf>,
+    /// Path to the RegistryCache (for when we want to save it back)
+    registry_cache_path: Option<PathBuf>,
+    /// Path to the AuditIndex (for when we want to save it back)
+    audit_index_path: Option<PathBuf>,
     /// Semaphore preventing exceeding the maximum number of concurrent diffs.
     diff_semaphore: tokio::sync::Semaphore,
     /// The time to use as `now` when considering cache expiry.
     now: chrono::DateTime<chrono::Utc>,
+    /// The endpoints to fetch crates and information about them from.
+    registry: RegistryConfig,
+    /// Limits on unpacking fetched crates.
+    unpack: UnpackConfig,
     /// Common mutable state for the cache which can be mutated concurrently
     /// from multiple tasks.
     state: Mutex<CacheState>,
@@ -1727,6 +3296,26 @@ impl Drop for Cache {
                 error!("error writing back changes to publisher-cache: {:?}", err);
             }
         }
+        if let Some(registry_cache_path) = &self.registry_cache_path {
+            // Write back the registry_cache
+            if let Err(err) = || -> Result<(), CacheCommitError> {
+                let registry_cache = store_json(mem::take(&mut state.registry_cache))?;
+                fs::write(registry_cache_path, registry_cache)?;
+                Ok(())
+            }() {
+                error!("error writing back changes to registry-cache: {:?}", err);
+            }
+        }
+        if let Some(audit_index_path) = &self.audit_index_path {
+            // Write back the audit_index
+            if let Err(err) = || -> Result<(), CacheCommitError> {
+                let audit_index = store_json(mem::take(&mut state.audit_index))?;
+                fs::write(audit_index_path, audit_index)?;
+                Ok(())
+            }() {
+                error!("error writing back changes to audit-index: {:?}", err);
+            }
+        }
         // `_lock: FileLock` implicitly released here
     }
 }
@@ -1743,12 +3332,19 @@ impl Cache {
                 diff_cache_path: None,
                 command_history_path: None,
                 publisher_cache_path: None,
+                registry_cache_path: None,
+                audit_index_path: None,
                 diff_semaphore: tokio::sync::Semaphore::new(MAX_CONCURRENT_DIFFS),
                 now: cfg.now,
+                registry: cfg.registry.clone(),
+                unpack: cfg.unpack.clone(),
                 state: Mutex::new(CacheState {
                     diff_cache: DiffCache::default(),
                     command_history: CommandHistory::default(),
                     crates_cache: CratesCache::default(),
+                    registry_cache: RegistryCache::default(),
+                    audit_index: AuditIndex::default(),
+                    lockfile_checksums: None,
                     fetched_packages: FastMap::new(),
                     diffed: FastMap::new(),
                 }),
@@ -1803,18 +3399,39 @@ impl Cache {
             .and_then(|f| load_json(f).ok())
             .unwrap_or_default();
 
+        // Setup the registry_cache.
+        let registry_cache_path = root.join(CACHE_PEER_REGISTRY_CACHE);
+        let registry_cache: RegistryCache = File::open(&registry_cache_path)
+            .ok()
+            .and_then(|f| load_json(f).ok())
+            .unwrap_or_default();
+
+        // Setup the audit_index.
+        let audit_index_path = root.join(CACHE_AUDIT_INDEX);
+        let audit_index: AuditIndex = File::open(&audit_index_path)
+            .ok()
+            .and_then(|f| load_json(f).ok())
+            .unwrap_or_default();
+
         Ok(Self {
             _lock: Some(lock),
             root: Some(root),
             diff_cache_path: Some(diff_cache_path),
             command_history_path: Some(command_history_path),
             publisher_cache_path: Some(publisher_cache_path),
+            registry_cache_path: Some(registry_cache_path),
+            audit_index_path: Some(audit_index_path),
             diff_semaphore: tokio::sync::Semaphore::new(MAX_CONCURRENT_DIFFS),
             now: cfg.now,
+            registry: cfg.registry.clone(),
+            unpack: cfg.unpack.clone(),
             state: Mutex::new(CacheState {
                 diff_cache,
                 command_history,
                 crates_cache: publisher_cache,
+                registry_cache,
+                audit_index,
+                lockfile_checksums: None,
                 fetched_packages: FastMap::new(),
                 diffed: FastMap::new(),
             }),
@@ -1927,7 +3544,7 @@ impl Cache {
                 .expect("failed to join");
 
                 // If the file isn't in our local cache, make sure to download it.
-                let file = match cached_file {
+                let mut file = match cached_file {
                     Ok(file) => file,
                     Err(_) => {
                         let network = network.ok_or_else(|| FetchError::Frozen {
@@ -1936,8 +3553,7 @@ impl Cache {
                         })?;
 
                         // We don't have it, so download it
-                        let url =
-                            format!("https://crates.io/api/v1/crates/{package}/{version}/download");
+                        let url = self.crate_download_url(package, version);
                         let url = Url::parse(&url).map_err(|error| FetchError::InvalidUrl {
                             url: url.clone(),
                             error,
@@ -1962,43 +3578,178 @@ impl Cache {
                     }
                 };
 
-                // TODO(#116): take the SHA2 of the bytes and compare it to what the registry says
-
+                // An existing unpacked copy can be used if it came from a
+                // tarball matching the checksum in Cargo.lock, and its files
+                // haven't changed since it was unpacked.
+                let lockfile_checksum = self.lockfile_checksum(metadata, package, version);
                 if fetch_is_ok(&fetched_src).await {
-                    Ok(fetched_src)
-                } else {
-                    info!(
-                        "unpacking package {}:{} from {} to {}",
-                        package,
-                        version,
-                        fetched_package.display(),
-                        fetched_src.display()
-                    );
-                    // The tarball needs to be unpacked, so do so.
-                    tokio::task::spawn_blocking(move || {
-                        unpack_package(&file, &fetched_src)
-                            .map(|_| fetched_src)
-                            .map_err(|error| FetchError::Unpack {
-                                src: fetched_package.clone(),
-                                error,
+                    match unpacked_checksum(&fetched_src).await {
+                        Some(recorded)
+                            if lockfile_checksum
+                                .as_ref()
+                                .map_or(true, |expected| *expected == recorded) =>
+                        {
+                            let fetched_src_ = fetched_src.clone();
+                            let unchanged = tokio::task::spawn_blocking(move || {
+                                tree_is_unchanged(&fetched_src_)
                             })
-                    })
-                    .await
-                    .expect("failed to join")
+                            .await
+                            .expect("failed to join");
+                            if unchanged {
+                                return Ok(fetched_src);
+                            }
+                            warn!(
+                                "unpacked package {}:{} at {} was modified after it was unpacked, \
+                                unpacking again",
+                                package,
+                                version,
+                                fetched_src.display()
+                            );
+                        }
+                        _ => info!(
+                            "unpacked package {}:{} at {} is unverified, unpacking again",
+                            package,
+                            version,
+                            fetched_src.display()
+                        ),
+                    }
                 }
+
+                // The tarball must match the checksum in Cargo.lock or the
+                // index before it is unpacked. Tarballs are verified when they
+                // are downloaded, so if we're offline and the lockfile doesn't
+                // know the package, the cached tarball is trusted.
+                let expected_checksum = match (lockfile_checksum, network) {
+                    (Some(checksum), _) => Some(checksum),
+                    (None, Some(network)) => {
+                        Some(self.index_checksum(network, package, version).await?)
+                    }
+                    (None, None) => None,
+                };
+                let fetched_package_ = fetched_package.clone();
+                let (file, actual_checksum) = tokio::task::spawn_blocking(move || {
+                    let checksum = checksum_file(&mut file);
+                    (file, checksum)
+                })
+                .await
+                .expect("failed to join");
+                let actual_checksum = actual_checksum.map_err(|error| FetchError::ReadCached {
+                    target: fetched_package_,
+                    error,
+                })?;
+                let verified_checksum = match expected_checksum {
+                    Some(expected) if expected != actual_checksum => {
+                        // Remove the tarball so that it is downloaded again
+                        // next time, rather than failing forever.
+                        if let Err(err) = tokio::fs::remove_file(&fetched_package).await {
+                            warn!("failed to remove {}: {}", fetched_package.display(), err);
+                        }
+                        return Err(FetchError::ChecksumMismatch {
+                            package: package.to_owned(),
+                            version: version.clone(),
+                            expected,
+                            actual: actual_checksum,
+                        });
+                    }
+                    Some(_) => Some(actual_checksum),
+                    // The unpacked tree isn't marked as verified, so it will
+                    // be checked again once a checksum is known.
+                    None => {
+                        warn!(
+                            "no checksum is known for {}:{} while offline, so its source is \
+                            unverified",
+                            package, version
+                        );
+                        None
+                    }
+                };
+
+                info!(
+                    "unpacking package {}:{} from {} to {}",
+                    package,
+                    version,
+                    fetched_package.display(),
+                    fetched_src.display()
+                );
+                // The tarball needs to be unpacked, so do so.
+                let limits = UnpackLimits {
+                    max_size: self.unpack.max_size.unwrap_or(DEFAULT_UNPACK_MAX_SIZE),
+                    max_files: self.unpack.max_files.unwrap_or(DEFAULT_UNPACK_MAX_FILES),
+                };
+                tokio::task::spawn_blocking(move || {
+                    unpack_package(&file, &fetched_src, verified_checksum.as_deref(), limits)
+                        .map(|_| fetched_src)
+                        .map_err(|error| FetchError::Unpack {
+                            src: fetched_package.clone(),
+                            error,
+                        })
+                })
+                .await
+                .expect("failed to join")
             })
             .await;
         let path = path_res?;
         Ok(path.to_owned())
     }
 
+    /// Look up the checksum which `Cargo.lock` records for a crates.io
+    /// package, reading the lockfile on first use.
+    fn lockfile_checksum(
+        &self,
+        metadata: &cargo_metadata::Metadata,
+        package: PackageStr<'_>,
+        version: &semver::Version,
+    ) -> Option<String> {
+        let mut guard = self.state.lock().unwrap();
+        guard
+            .lockfile_checksums
+            .get_or_insert_with(|| {
+                read_lockfile_checksums(metadata.workspace_root.join(CARGO_LOCK_FILE).as_ref())
+            })
+            .get(&(package.to_owned(), version.clone()))
+            .cloned()
+    }
+
+    /// Look up the checksum of a package in the crates.io index.
+    async fn index_checksum(
+        &self,
+        network: &Network,
+        package: PackageStr<'_>,
+        version: &semver::Version,
+    ) -> Result<String, FetchError> {
+        let index_error = |error| FetchError::IndexChecksum {
+            package: package.to_owned(),
+            version: version.clone(),
+            error: Box::new(error),
+        };
+        let index_crate = self
+            .get_index_crate(network, package)
+            .await
+            .map_err(index_error)?;
+        let index_version =
+            exact_version(&index_crate, version).ok_or_else(|| FetchError::MissingChecksum {
+                package: package.to_owned(),
+                version: version.clone(),
+            })?;
+        Ok(index_version
+            .checksum()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect())
+    }
+
+    /// Compute the diffstat between two unpacked packages, along with the pairs
+    /// of files which differ. With `DiffScope::Shipped`, files which aren't
+    /// shipped in the version they belong to are left out, and are returned
+    /// separately with the diffstat of each, relative to the package.
     #[tracing::instrument(skip_all, err)]
     pub async fn diffstat_package(
         &self,
         version1: &Path,
         version2: &Path,
         has_git_rev: bool,
-    ) -> Result<(DiffStat, Vec<(PathBuf, PathBuf)>), DiffError> {
+        scope: DiffScope,
+    ) -> Result<UnpackedDiff, DiffError> {
         let _permit = self
             .diff_semaphore
             .acquire()
@@ -2034,6 +3785,18 @@ impl Cache {
             deletions: 0,
         };
         let mut to_compare = Vec::new();
+        let mut unshipped = Vec::new();
+
+        let shipped = match scope {
+            DiffScope::All => None,
+            DiffScope::Shipped => {
+                // `version1` is never a git revision.
+                Some((
+                    ShippedFiles::read(version1, false),
+                    ShippedFiles::read(version2, has_git_rev),
+                ))
+            }
+        };
 
         // Thanks to the `-z` flag the output takes the rough format of:
         // "{INSERTED}\t{DELETED}\t\0{FROM_PATH}\0{TO_PATH}\0" for each file
@@ -2045,44 +3808,228 @@ impl Cache {
         while let (Some(changes_s), Some(from_s), Some(to_s)) =
             (chunks.next(), chunks.next(), chunks.next())
         {
-            // Check if the path is one of the files which is ignored.
-            let rel_path = if to_s != "/dev/null" {
-                Path::new(to_s)
-                    .strip_prefix(version2)
-                    .map_err(DiffError::UnexpectedPath)?
-            } else {
-                assert_ne!(
-                    from_s, "/dev/null",
-                    "unexpected diff from /dev/null to /dev/null"
-                );
-                Path::new(from_s)
-                    .strip_prefix(version1)
-                    .map_err(DiffError::UnexpectedPath)?
-            };
-            if DIFF_SKIP_PATHS.iter().any(|p| Path::new(p) == rel_path)
-                || (has_git_rev && Path::new(CARGO_TOML_FILE) == rel_path)
-            {
-                continue;
+            // Check if the path is one of the files which is ignored.
+            let rel_path = if to_s != "/dev/null" {
+                Path::new(to_s)
+                    .strip_prefix(version2)
+                    .map_err(DiffError::UnexpectedPath)?
+            } else {
+                assert_ne!(
+                    from_s, "/dev/null",
+                    "unexpected diff from /dev/null to /dev/null"
+                );
+                Path::new(from_s)
+                    .strip_prefix(version1)
+                    .map_err(DiffError::UnexpectedPath)?
+            };
+            if DIFF_SKIP_PATHS.iter().any(|p| Path::new(p) == rel_path)
+                || (has_git_rev && Path::new(CARGO_TOML_FILE) == rel_path)
+            {
+                continue;
+            }
+
+            let (insertions, deletions) = match changes_s.trim().split_once('\t') {
+                Some(("-", "-")) => (0, 0), // binary diff
+                Some((insertions_s, deletions_s)) => (
+                    insertions_s
+                        .parse::<u64>()
+                        .map_err(|_| DiffError::InvalidOutput)?,
+                    deletions_s
+                        .parse::<u64>()
+                        .map_err(|_| DiffError::InvalidOutput)?,
+                ),
+                None => Err(DiffError::InvalidOutput)?,
+            };
+
+            if let Some((shipped1, shipped2)) = &shipped {
+                let shipped = if to_s != "/dev/null" {
+                    shipped2
+                } else {
+                    shipped1
+                };
+                if !shipped.is_shipped(rel_path) {
+                    unshipped.push((
+                        rel_path.to_owned(),
+                        DiffStat {
+                            insertions,
+                            deletions,
+                            files_changed: 1,
+                        },
+                    ));
+                    continue;
+                }
+            }
+
+            to_compare.push((from_s.into(), to_s.into()));
+
+            diffstat.files_changed += 1;
+            diffstat.insertions += insertions;
+            diffstat.deletions += deletions;
+        }
+        Ok((diffstat, to_compare, unshipped))
+    }
+
+    /// Compare the published contents of a package, unpacked at `published`,
+    /// with the commit recorded in its `.cargo_vcs_info.json`, which is taken
+    /// from the git checkout at `repo`.
+    ///
+    /// Differences which cargo's packaging explains are ignored: the manifest
+    /// is normalized (with the original kept as `Cargo.toml.orig`), so is only
+    /// reported if it builds something different, and the readme and license
+    /// file it names may be copied into the package from directories above it.
+    /// Files in the repository which weren't published are also ignored.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn compare_with_source(
+        &self,
+        published: &Path,
+        repo: &Path,
+    ) -> Result<SourceComparison, DiffSourceError> {
+        let vcs_info = tokio::fs::read_to_string(published.join(CARGO_VCS_INFO_FILE))
+            .await
+            .map_err(|_| DiffSourceError::MissingVcsInfo {
+                published: published.to_owned(),
+            })?;
+        let vcs_info: CargoVcsInfo =
+            serde_json::from_str(&vcs_info).map_err(|error| DiffSourceError::InvalidVcsInfo {
+                published: published.to_owned(),
+                error,
+            })?;
+        // The file comes from the published package, so don't let it pass
+        // options to git or point outside of the extracted commit.
+        let unsafe_vcs_info = |field, value: &str| DiffSourceError::UnsafeVcsInfo {
+            published: published.to_owned(),
+            field,
+            value: value.to_owned(),
+        };
+        if vcs_info.git.sha1.len() != 40
+            || !vcs_info
+                .git
+                .sha1
+                .bytes()
+                .all(|byte| byte.is_ascii_hexdigit())
+        {
+            return Err(unsafe_vcs_info("sha1", &vcs_info.git.sha1));
+        }
+        if !Path::new(&vcs_info.path_in_vcs)
+            .components()
+            .all(|component| matches!(component, std::path::Component::Normal(_)))
+        {
+            return Err(unsafe_vcs_info("path_in_vcs", &vcs_info.path_in_vcs));
+        }
+        let commit = vcs_info.git.sha1;
+        let extract_error = |error| DiffSourceError::Extract {
+            repo: repo.to_owned(),
+            commit: commit.clone(),
+            error,
+        };
+
+        // Extract the commit with `git archive` rather than checking it out, so
+        // the working tree of the checkout is left alone.
+        let tmp = tempfile::tempdir().map_err(extract_error)?;
+        let archive = tmp.path().join("source.tar");
+        let out = tokio::process::Command::new("git")
+            .arg("-C")
+            .arg(repo)
+            .arg("archive")
+            .arg("--format=tar")
+            .arg("-o")
+            .arg(&archive)
+            .arg(&commit)
+            .output()
+            .await
+            .map_err(CommandError::CommandFailed)?;
+        if !out.status.success() {
+            return Err(DiffSourceError::MissingCommit {
+                repo: repo.to_owned(),
+                commit,
+            });
+        }
+        let source_root = tmp.path().join("source");
+        let source_root_ = source_root.clone();
+        tokio::task::spawn_blocking(move || {
+            Archive::new(File::open(archive)?).unpack(source_root_)
+        })
+        .await
+        .expect("failed to join")
+        .map_err(extract_error)?;
+
+        // The commit may contain symlinks, so check where the package ended
+        // up, and replace rather than write through any Cargo.toml.orig.
+        let source = source_root.join(&vcs_info.path_in_vcs);
+        let canonical_root = tokio::fs::canonicalize(&source_root)
+            .await
+            .map_err(extract_error)?;
+        if tokio::fs::canonicalize(&source)
+            .await
+            .map_or(false, |source| !source.starts_with(&canonical_root))
+        {
+            return Err(unsafe_vcs_info("path_in_vcs", &vcs_info.path_in_vcs));
+        }
+        if tokio::fs::metadata(source.join(CARGO_TOML_FILE))
+            .await
+            .is_ok()
+        {
+            match tokio::fs::remove_file(source.join(CARGO_TOML_ORIG_FILE)).await {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                    return Err(extract_error(error))
+                }
+                _ => {}
             }
+            tokio::fs::copy(
+                source.join(CARGO_TOML_FILE),
+                source.join(CARGO_TOML_ORIG_FILE),
+            )
+            .await
+            .map_err(extract_error)?;
+        }
 
-            to_compare.push((from_s.into(), to_s.into()));
-
-            diffstat.files_changed += 1;
+        let (_, to_compare, _) = self
+            .diffstat_package(&source, published, false, DiffScope::All)
+            .await?;
 
-            match changes_s.trim().split_once('\t') {
-                Some(("-", "-")) => {} // binary diff
-                Some((insertions_s, deletions_s)) => {
-                    diffstat.insertions += insertions_s
-                        .parse::<u64>()
-                        .map_err(|_| DiffError::InvalidOutput)?;
-                    diffstat.deletions += deletions_s
-                        .parse::<u64>()
-                        .map_err(|_| DiffError::InvalidOutput)?;
+        // The published Cargo.toml is normalized by cargo, so rather than
+        // comparing its text, compare what it says to build with what the
+        // original says.
+        let original = read_manifest(&source.join(CARGO_TOML_FILE)).await;
+        let workspace = workspace_manifest(&source_root, &vcs_info.path_in_vcs).await;
+        let mut added = Vec::new();
+        let mut modified = Vec::new();
+        for (from, to) in to_compare {
+            if to == Path::new("/dev/null") {
+                continue;
+            }
+            let rel_path = to
+                .strip_prefix(published)
+                .map_err(DiffError::UnexpectedPath)?
+                .to_owned();
+            if from != Path::new("/dev/null") {
+                if rel_path == Path::new(CARGO_TOML_FILE)
+                    && manifests_match(&original, &to, workspace.as_ref(), published).await
+                {
+                    continue;
                 }
-                None => Err(DiffError::InvalidOutput)?,
-            };
+                modified.push(rel_path);
+            } else if !copied_from_manifest(
+                &source_root,
+                &vcs_info.path_in_vcs,
+                original.as_ref(),
+                workspace.as_ref(),
+                &rel_path,
+                &to,
+            )
+            .await
+            {
+                added.push(rel_path);
+            }
         }
-        Ok((diffstat, to_compare))
+
+        Ok(SourceComparison {
+            commit,
+            path_in_vcs: vcs_info.path_in_vcs,
+            dirty: vcs_info.git.dirty,
+            added,
+            modified,
+        })
     }
 
     #[tracing::instrument(skip(self, metadata, network), err)]
@@ -2092,6 +4039,7 @@ impl Cache {
         network: Option<&Network>,
         package: PackageStr<'_>,
         delta: &Delta,
+        scope: DiffScope,
     ) -> Result<DiffStat, FetchAndDiffError> {
         // Lock the mutex to extract a reference to the OnceCell which we'll use
         // to asynchronously synchronize on and diff the package only once in a
@@ -2104,8 +4052,9 @@ impl Cache {
             let mut guard = self.state.lock().unwrap();
 
             // Check if the value has already been cached.
-            let DiffCache::V2 { diffs } = &guard.diff_cache;
-            if let Some(cached) = diffs
+            if let Some(cached) = guard
+                .diff_cache
+                .diffs(scope)
                 .get(package)
                 .and_then(|cache| cache.get(delta))
                 .cloned()
@@ -2134,7 +4083,7 @@ impl Cache {
 
             guard
                 .diffed
-                .entry((package.to_owned(), delta.clone()))
+                .entry((package.to_owned(), delta.clone(), scope))
                 .or_default()
                 .clone()
         };
@@ -2152,15 +4101,16 @@ impl Cache {
                 // Have fetches, do a real diffstat
                 // NOTE: We'll never pick a 'from' version with a git_rev, so we
                 // don't need to check for that here.
-                let (diffstat, _) = self
-                    .diffstat_package(&from, &to, delta.to.git_rev.is_some())
+                let (diffstat, _, _) = self
+                    .diffstat_package(&from, &to, delta.to.git_rev.is_some(), scope)
                     .await?;
 
                 // Record the cache result in the diffcache
                 {
                     let mut guard = self.state.lock().unwrap();
-                    let DiffCache::V2 { diffs } = &mut guard.diff_cache;
-                    diffs
+                    guard
+                        .diff_cache
+                        .diffs_mut(scope)
                         .entry(package.to_string())
                         .or_default()
                         .insert(delta.clone(), diffstat.clone());
@@ -2361,6 +4311,129 @@ impl Cache {
             .unwrap_or_default()
     }
 
+    /// Read a file from the registry of peers, such as the registry itself or
+    /// the audits of one of its peers. Files on the network are cached for a
+    /// day, and cached copies are always used when `network` is `None`. Files
+    /// may also be read from local paths, such as those in a local mirror of
+    /// the registry.
+    pub async fn fetch_registry_file(
+        &self,
+        network: Option<&Network>,
+        url: &str,
+    ) -> Result<SourceFile, FetchRegistryError> {
+        let invalid_url = |error| FetchRegistryError::InvalidUrl {
+            url: url.to_owned(),
+            error,
+        };
+        let path = match Url::parse(url) {
+            Ok(parsed_url) if parsed_url.scheme() == "file" => parsed_url
+                .to_file_path()
+                .map_err(|()| invalid_url(url::ParseError::InvalidDomainCharacter))?,
+            Ok(parsed_url) => {
+                {
+                    let guard = self.state.lock().unwrap();
+                    if let Some(entry) = guard.registry_cache.files.get(url) {
+                        let expiry =
+                            entry.last_fetched + chrono::Duration::days(REGISTRY_CACHE_EXPIRY_DAYS);
+                        if network.is_none() || self.now < expiry {
+                            return Ok(SourceFile::new(url, entry.source.clone()));
+                        }
+                    }
+                }
+                let Some(network) = network else {
+                    return Err(FetchRegistryError::NotCached {
+                        url: url.to_owned(),
+                    });
+                };
+                let source = network.download_source_file_cached(parsed_url).await?;
+                self.state.lock().unwrap().registry_cache.files.insert(
+                    url.to_owned(),
+                    RegistryCacheEntry {
+                        last_fetched: self.now,
+                        source: source.source().to_owned(),
+                    },
+                );
+                return Ok(source);
+            }
+            Err(url::ParseError::RelativeUrlWithoutBase) => PathBuf::from(url),
+            Err(error) => return Err(invalid_url(error)),
+        };
+        let source = tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|error| FetchRegistryError::ReadFile { path, error })?;
+        Ok(SourceFile::new(url, source))
+    }
+
+    /// Replace the audits indexed from the peer `source` with the audits in
+    /// `audits_files`.
+    pub fn update_audit_index<'a>(
+        &self,
+        source: &str,
+        audits_files: impl IntoIterator<Item = &'a AuditsFile>,
+    ) {
+        let mut audits: SortedMap<PackageName, Vec<IndexedAudit>> = SortedMap::new();
+        for (package, entries) in audits_files
+            .into_iter()
+            .flat_map(|audits_file| &audits_file.audits)
+        {
+            audits
+                .entry(package.clone())
+                .or_default()
+                .extend(entries.iter().map(IndexedAudit::from));
+        }
+        self.state.lock().unwrap().audit_index.sources.insert(
+            source.to_owned(),
+            AuditIndexSource {
+                last_updated: self.now,
+                audits,
+            },
+        );
+    }
+
+    /// Look up the audits of `package` from every peer in the audit index,
+    /// along with the name of the peer.
+    pub fn search_audit_index(&self, package: PackageStr<'_>) -> Vec<(ImportName, IndexedAudit)> {
+        let guard = self.state.lock().unwrap();
+        guard
+            .audit_index
+            .sources
+            .iter()
+            .flat_map(|(source, indexed)| {
+                indexed
+                    .audits
+                    .get(package)
+                    .into_iter()
+                    .flatten()
+                    .map(|audit| (source.clone(), audit.clone()))
+            })
+            .collect()
+    }
+
+    /// Merge the crates.io information read from a database dump into the
+    /// cache. Crates which were fetched more recently than the dump was taken
+    /// are left alone. Returns the number of crates and versions which were
+    /// updated.
+    pub fn ingest_db_dump(&self, dump: CratesCache) -> (usize, usize) {
+        let mut guard = self.state.lock().unwrap();
+        let crates_cache = &mut guard.crates_cache;
+        let mut crates = 0;
+        let mut versions = 0;
+        for (name, entry) in dump.crates {
+            if crates_cache
+                .crates
+                .get(&name)
+                .map_or(false, |existing| existing.last_fetched > entry.last_fetched)
+            {
+                continue;
+            }
+            crates += 1;
+            versions += entry.versions.len();
+            crates_cache.crates.insert(name, entry);
+        }
+        crates_cache.users.extend(dump.users);
+        (crates, versions)
+    }
+
     /// Look up information about who published each version of the specified
     /// crates. Versions for each crate are also specified in order to avoid
     /// hitting the network in the case where the cache already has the relevant
@@ -2435,6 +4508,67 @@ impl Cache {
             .collect())
     }
 
+    /// Read the index entry for this package, listing every published
+    /// version along with its dependencies.
+    pub async fn get_index_crate(
+        &self,
+        network: &Network,
+        name: PackageStr<'_>,
+    ) -> Result<crates_index::Crate, CrateInfoError> {
+        let url = self.crate_index_file_url(name);
+        let url = Url::parse(&url).map_err(|error| CrateInfoError::InvalidUrl { url, error })?;
+        let response = network.download(url).await?;
+        Ok(crates_index::Crate::from_slice(&response[..]).map_err(LoadJsonError::from)?)
+    }
+
+    fn crates_api_url(&self) -> &str {
+        self.registry.api.as_deref().unwrap_or(CRATES_IO_API_URL)
+    }
+
+    /// The URL of the index file for `name`.
+    fn crate_index_file_url(&self, name: PackageStr<'_>) -> String {
+        // Crate index always use lowercases, but crate name may contain uppercase characters.
+        format!(
+            "{}/{}/{}",
+            self.crates_index_url().trim_end_matches('/'),
+            index_prefix(name).to_ascii_lowercase(),
+            name.to_ascii_lowercase()
+        )
+    }
+
+    fn crates_index_url(&self) -> &str {
+        self.registry
+            .index
+            .as_deref()
+            .unwrap_or(CRATES_IO_INDEX_URL)
+    }
+
+    /// The URL to download a `.crate` file from. Like cargo, the `download`
+    /// URL may contain the markers `{crate}`, `{version}`, `{prefix}` and
+    /// `{lowerprefix}`, or otherwise has `/{crate}/{version}/download`
+    /// appended to it.
+    fn crate_download_url(&self, package: PackageStr<'_>, version: &semver::Version) -> String {
+        let download_url = self
+            .registry
+            .download
+            .as_deref()
+            .unwrap_or(CRATES_IO_DOWNLOAD_URL);
+        const MARKERS: &[&str] = &["{crate}", "{version}", "{prefix}", "{lowerprefix}"];
+        if MARKERS.iter().any(|marker| download_url.contains(marker)) {
+            let prefix = index_prefix(package);
+            download_url
+                .replace("{crate}", package)
+                .replace("{version}", &version.to_string())
+                .replace("{prefix}", &prefix)
+                .replace("{lowerprefix}", &prefix.to_ascii_lowercase())
+        } else {
+            format!(
+                "{}/{package}/{version}/download",
+                download_url.trim_end_matches('/')
+            )
+        }
+    }
+
     /// Look up user information for a crates.io user from the publisher cache.
     pub fn get_crates_user_info(&self, user_id: u64) -> Option<CratesCacheUser> {
         let guard = self.state.lock().unwrap();
@@ -2616,11 +4750,11 @@ impl<'a> UpdateCratesCache<'a> {
         &self,
         network: &Network,
     ) -> Result<std::sync::MutexGuard<'a, CacheState>, CrateInfoError> {
-        let url = Url::parse(&format!(
-            "https://crates.io/api/v1/crates/{}",
+        let url = self.parse_url(format!(
+            "{}/api/v1/crates/{}",
+            self.cache.crates_api_url().trim_end_matches('/'),
             self.crate_name
-        ))
-        .expect("invalid crate name");
+        ))?;
 
         let response = self.try_download(network, url).await?;
         let result = load_json::<CratesAPICrate>(&response[..])?;
@@ -2679,21 +4813,8 @@ impl<'a> UpdateCratesCache<'a> {
         &self,
         network: &Network,
     ) -> Result<std::sync::MutexGuard<'a, CacheState>, CrateInfoError> {
-        // Crate names can only be a subset of ascii (valid rust identifier characters and `-`), so
-        // using `len()` and indexing will result in valid counts/characters.
-        let mut url = String::from("https://index.crates.io/");
         let name = self.crate_name;
-        use std::fmt::Write;
-        match name.len() {
-            1 => write!(url, "1/{name}"),
-            2 => write!(url, "2/{name}"),
-            3 => write!(url, "3/{}/{name}", &name[0..1]),
-            _ => write!(url, "{}/{}/{name}", &name[0..2], &name[2..4]),
-        }
-        .expect("writing to a String should not fail");
-        // Crate index always use lowercases, but crate name may contain uppercase characters.
-        url.make_ascii_lowercase();
-        let url = Url::parse(&url).expect("invalid crate name");
+        let url = self.parse_url(self.cache.crate_index_file_url(name))?;
 
         let response = self.try_download(network, url).await?;
 
@@ -2724,6 +4845,10 @@ impl<'a> UpdateCratesCache<'a> {
         Ok(guard)
     }
 
+    fn parse_url(&self, url: String) -> Result<Url, CrateInfoError> {
+        Url::parse(&url).map_err(|error| CrateInfoError::InvalidUrl { url, error })
+    }
+
     async fn try_download(&self, network: &Network, url: Url) -> Result<Vec<u8>, CrateInfoError> {
         network.download(url).await.map_err(|e| match e {
             DownloadError::FailedToStartDownload { error, .. }
@@ -2752,6 +4877,18 @@ impl<'a> UpdateCratesCache<'a> {
     }
 }
 
+/// The directories a crate is stored under in a registry index.
+fn index_prefix(name: PackageStr<'_>) -> String {
+    // Crate names can only be a subset of ascii (valid rust identifier characters and `-`), so
+    // using `len()` and indexing will result in valid counts/characters.
+    match name.len() {
+        1 => "1".to_owned(),
+        2 => "2".to_owned(),
+        3 => format!("3/{}", &name[0..1]),
+        _ => format!("{}/{}", &name[0..2], &name[2..4]),
+    }
+}
+
 /// Queries a package in the crates.io registry for a specific published version
 pub fn exact_version<'a>(
     this: &'a crates_index::Crate,
@@ -2767,6 +4904,461 @@ pub fn exact_version<'a>(
     None
 }
 
+/// The subset of `Cargo.lock` needed to find the checksums of packages.
+#[derive(Deserialize)]
+struct CargoLockfile {
+    #[serde(default)]
+    package: Vec<CargoLockfilePackage>,
+}
+
+#[derive(Deserialize)]
+struct CargoLockfilePackage {
+    name: PackageName,
+    version: semver::Version,
+    source: Option<cargo_metadata::Source>,
+    checksum: Option<String>,
+}
+
+/// Read the checksums of crates.io packages from the `Cargo.lock` at `path`.
+/// Problems reading the lockfile aren't fatal, as checksums can also be found
+/// in the index.
+fn read_lockfile_checksums(path: &Path) -> FastMap<(PackageName, semver::Version), String> {
+    let lockfile: CargoLockfile = match fs::read_to_string(path)
+        .map_err(|error| error.to_string())
+        .and_then(|source| toml::de::from_str(&source).map_err(|error| error.to_string()))
+    {
+        Ok(lockfile) => lockfile,
+        Err(error) => {
+            info!("couldn't read checksums from {}: {}", path.display(), error);
+            return FastMap::new();
+        }
+    };
+    lockfile
+        .package
+        .into_iter()
+        .filter(|package| package.source.as_ref().map_or(false, |s| s.is_crates_io()))
+        .filter_map(|package| Some(((package.name, package.version), package.checksum?)))
+        .collect()
+}
+
+/// The contents of the `.cargo_vcs_info.json` file cargo adds to packages
+/// published from a git checkout.
+#[derive(Deserialize)]
+struct CargoVcsInfo {
+    git: CargoVcsInfoGit,
+    #[serde(default)]
+    path_in_vcs: String,
+}
+
+#[derive(Deserialize)]
+struct CargoVcsInfoGit {
+    sha1: String,
+    #[serde(default)]
+    dirty: bool,
+}
+
+/// The kinds of dependency tables in a manifest, along with the name of each
+/// table and the older spelling cargo still accepts for it.
+const DEPENDENCY_TABLES: [(&str, &str, &str); 3] = [
+    ("normal", "dependencies", "dependencies"),
+    ("dev", "dev-dependencies", "dev_dependencies"),
+    ("build", "build-dependencies", "build_dependencies"),
+];
+
+/// The kinds of targets other than the library, along with the name of their
+/// tables, the directory cargo discovers them in, and the key which turns off
+/// that discovery.
+const TARGET_TABLES: [(&str, &str, &str); 4] = [
+    ("bin", "src/bin", "autobins"),
+    ("example", "examples", "autoexamples"),
+    ("test", "tests", "autotests"),
+    ("bench", "benches", "autobenches"),
+];
+
+/// The platform, kind and name of a dependency in a manifest.
+type DependencyKey = (Option<String>, &'static str, String);
+
+/// The parts of a manifest which decide what is built from a package, which
+/// cargo may write differently but shouldn't change when it normalizes the
+/// manifest for publishing.
+#[derive(Debug, PartialEq, Eq)]
+struct ManifestSummary {
+    /// Each dependency, keyed by its platform, kind and name, along with the
+    /// package it renames and whether it is optional.
+    dependencies: SortedMap<DependencyKey, (Option<String>, bool)>,
+    /// The kind and path of each target whose file is in the package.
+    targets: SortedSet<(&'static str, PathBuf)>,
+    build: Option<PathBuf>,
+    features: SortedMap<String, SortedSet<String>>,
+    links: Option<String>,
+}
+
+impl ManifestSummary {
+    /// Summarize `manifest`, looking for targets and the build script within
+    /// the package at `package`, and taking any dependencies inherited from
+    /// the workspace from `workspace`.
+    fn new(manifest: &toml::Value, workspace: Option<&toml::Value>, package: &Path) -> Self {
+        fn table(value: Option<&toml::Value>) -> Option<&toml::value::Table> {
+            value.and_then(toml::Value::as_table)
+        }
+        let package_table = table(manifest.get("package"));
+        let package_field = |field| package_table.and_then(|package| package.get(field));
+        let workspace_dependencies = table(
+            workspace
+                .and_then(|workspace| workspace.get("workspace"))
+                .and_then(|workspace| workspace.get("dependencies")),
+        );
+
+        let mut dependencies = SortedMap::new();
+        let mut add_dependencies = |platform: Option<&String>, tables: &toml::value::Table| {
+            for (kind, name, alt_name) in DEPENDENCY_TABLES {
+                let Some(deps) = table(tables.get(name).or_else(|| tables.get(alt_name))) else {
+                    continue;
+                };
+                for (dep_name, dep) in deps {
+                    let inherited =
+                        dep.get("workspace").and_then(toml::Value::as_bool) == Some(true);
+                    let inherited_dep = workspace_dependencies
+                        .filter(|_| inherited)
+                        .and_then(|deps| deps.get(dep_name));
+                    let field = |field| {
+                        dep.get(field)
+                            .or_else(|| inherited_dep.and_then(|dep| dep.get(field)))
+                    };
+                    // Cargo leaves out dev-dependencies which only have a path
+                    // or git source when publishing.
+                    let has_version = dep.is_str()
+                        || inherited_dep.map_or(false, toml::Value::is_str)
+                        || field("version").is_some();
+                    if kind == "dev" && !has_version {
+                        continue;
+                    }
+                    dependencies.insert(
+                        (platform.cloned(), kind, dep_name.clone()),
+                        (
+                            field("package")
+                                .and_then(toml::Value::as_str)
+                                .map(str::to_owned),
+                            field("optional").and_then(toml::Value::as_bool) == Some(true),
+                        ),
+                    );
+                }
+            }
+        };
+        if let Some(tables) = manifest.as_table() {
+            add_dependencies(None, tables);
+        }
+        for (platform, tables) in table(manifest.get("target")).into_iter().flatten() {
+            if let Some(tables) = tables.as_table() {
+                add_dependencies(Some(platform), tables);
+            }
+        }
+
+        let exists = |path: &Path| package.join(path).is_file();
+        let mut targets = SortedSet::new();
+        let lib_path = table(manifest.get("lib"))
+            .and_then(|lib| lib.get("path"))
+            .and_then(toml::Value::as_str)
+            .map_or_else(|| PathBuf::from("src/lib.rs"), manifest_path);
+        if exists(&lib_path) {
+            targets.insert(("lib", lib_path));
+        }
+        for (kind, dir, auto) in TARGET_TABLES {
+            for target in manifest
+                .get(kind)
+                .and_then(toml::Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                let path = match target.get("path").and_then(toml::Value::as_str) {
+                    Some(path) => Some(manifest_path(path)),
+                    None => target
+                        .get("name")
+                        .and_then(toml::Value::as_str)
+                        .and_then(|name| {
+                            let mut candidates = vec![
+                                Path::new(dir).join(format!("{name}.rs")),
+                                Path::new(dir).join(name).join("main.rs"),
+                            ];
+                            if kind == "bin"
+                                && package_field("name").and_then(toml::Value::as_str) == Some(name)
+                            {
+                                candidates.push(PathBuf::from("src/main.rs"));
+                            }
+                            candidates.into_iter().find(|path| exists(path))
+                        }),
+                };
+                if let Some(path) = path.filter(|path| exists(path)) {
+                    targets.insert((kind, path));
+                }
+            }
+            if package_field(auto).and_then(toml::Value::as_bool) == Some(false) {
+                continue;
+            }
+            let mut discovered = Vec::new();
+            if kind == "bin" {
+                discovered.push(PathBuf::from("src/main.rs"));
+            }
+            for entry in fs::read_dir(package.join(dir)).into_iter().flatten() {
+                let Ok(entry) = entry else { continue };
+                let path = Path::new(dir).join(entry.file_name());
+                if path.extension() == Some(OsStr::new("rs")) {
+                    discovered.push(path);
+                } else {
+                    discovered.push(path.join("main.rs"));
+                }
+            }
+            for path in discovered {
+                if exists(&path) {
+                    targets.insert((kind, path));
+                }
+            }
+        }
+
+        let build = match package_field("build") {
+            Some(toml::Value::String(path)) => Some(manifest_path(path)),
+            Some(toml::Value::Boolean(false)) => None,
+            _ => Some(PathBuf::from("build.rs")),
+        }
+        .filter(|path| exists(path));
+
+        let features = table(manifest.get("features"))
+            .into_iter()
+            .flatten()
+            .map(|(name, enables)| {
+                let enables = enables
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(toml::Value::as_str)
+                    .map(str::to_owned)
+                    .collect();
+                (name.clone(), enables)
+            })
+            .collect();
+
+        ManifestSummary {
+            dependencies,
+            targets,
+            build,
+            features,
+            links: package_field("links")
+                .and_then(toml::Value::as_str)
+                .map(str::to_owned),
+        }
+    }
+}
+
+/// A relative path from a manifest, without any leading `./`.
+fn manifest_path(path: &str) -> PathBuf {
+    Path::new(path)
+        .components()
+        .filter(|component| *component != std::path::Component::CurDir)
+        .collect()
+}
+
+/// Read and parse the manifest at `path`, if it exists and is valid.
+async fn read_manifest(path: &Path) -> Option<toml::Value> {
+    let manifest = tokio::fs::read_to_string(path).await.ok()?;
+    toml::from_str(&manifest).ok()
+}
+
+/// The manifest of the workspace containing the package at `path_in_vcs`, if
+/// any, along with its directory within `source_root`.
+async fn workspace_manifest(
+    source_root: &Path,
+    path_in_vcs: &str,
+) -> Option<(PathBuf, toml::Value)> {
+    for dir in Path::new(path_in_vcs).ancestors() {
+        if let Some(manifest) = read_manifest(&source_root.join(dir).join(CARGO_TOML_FILE)).await {
+            if manifest.get("workspace").is_some() {
+                return Some((dir.to_owned(), manifest));
+            }
+        }
+    }
+    None
+}
+
+/// Whether the published manifest at `published_manifest` builds the same
+/// things as the `original` manifest of the package, with the published
+/// package at `published`.
+async fn manifests_match(
+    original: &Option<toml::Value>,
+    published_manifest: &Path,
+    workspace: Option<&(PathBuf, toml::Value)>,
+    published: &Path,
+) -> bool {
+    let (Some(original), Some(normalized)) = (original, read_manifest(published_manifest).await)
+    else {
+        return false;
+    };
+    let original = original.clone();
+    let workspace = workspace.map(|(_, workspace)| workspace.clone());
+    let published = published.to_owned();
+    tokio::task::spawn_blocking(move || {
+        ManifestSummary::new(&original, workspace.as_ref(), &published)
+            == ManifestSummary::new(&normalized, None, &published)
+    })
+    .await
+    .expect("failed to join")
+}
+
+/// Whether a file at the root of a published package, with the contents of
+/// `published_file`, is a copy of the readme or license file named by the
+/// `original` manifest of the package, as cargo makes when those are outside
+/// of the package.
+async fn copied_from_manifest(
+    source_root: &Path,
+    path_in_vcs: &str,
+    original: Option<&toml::Value>,
+    workspace: Option<&(PathBuf, toml::Value)>,
+    rel_path: &Path,
+    published_file: &Path,
+) -> bool {
+    let (Some(original), Some(file_name)) = (original, rel_path.file_name()) else {
+        return false;
+    };
+    if rel_path.parent() != Some(Path::new("")) {
+        return false;
+    }
+
+    // Each file the manifest names, relative to the directory its path is
+    // relative to.
+    let mut candidates = Vec::new();
+    for field in ["readme", "license-file"] {
+        match original
+            .get("package")
+            .and_then(|package| package.get(field))
+        {
+            Some(toml::Value::String(path)) => {
+                candidates.push((PathBuf::from(path_in_vcs), path.as_str()))
+            }
+            Some(toml::Value::Table(inherit)) if inherit.get("workspace").is_some() => {
+                if let Some((dir, toml::Value::String(path))) =
+                    workspace.and_then(|(dir, workspace)| {
+                        Some((dir, workspace.get("workspace")?.get("package")?.get(field)?))
+                    })
+                {
+                    candidates.push((dir.clone(), path.as_str()));
+                }
+            }
+            None if field == "readme" => {
+                for path in ["README.md", "README.txt", "README"] {
+                    candidates.push((PathBuf::from(path_in_vcs), path));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let Ok(contents) = tokio::fs::read(published_file).await else {
+        return false;
+    };
+    let Ok(canonical_root) = tokio::fs::canonicalize(source_root).await else {
+        return false;
+    };
+    for (dir, path) in candidates {
+        if Path::new(path).file_name() != Some(file_name) {
+            continue;
+        }
+        // The path may lead out of the repository, either directly or through
+        // a symlink, and nothing outside of it can explain the file.
+        let mut candidate = dir;
+        for component in Path::new(path).components() {
+            match component {
+                std::path::Component::Normal(name) => candidate.push(name),
+                std::path::Component::CurDir => {}
+                std::path::Component::ParentDir if candidate.pop() => {}
+                _ => return false,
+            }
+        }
+        let Ok(candidate) = tokio::fs::canonicalize(source_root.join(candidate)).await else {
+            continue;
+        };
+        if candidate.starts_with(&canonical_root)
+            && tokio::fs::read(&candidate).await.ok().as_ref() == Some(&contents)
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Compute the sha256 of a .crate file, leaving it ready to be read again.
+fn checksum_file(file: &mut File) -> Result<String, io::Error> {
+    let mut hasher = Sha256::new();
+    io::copy(file, &mut hasher)?;
+    file.rewind()?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// The checksum recorded when the package at `fetch` was unpacked, if any.
+async fn unpacked_checksum(fetch: &Path) -> Option<String> {
+    tokio::fs::read_to_string(fetch.join(CHECKSUM_FILE))
+        .await
+        .ok()
+        .map(|checksum| checksum.trim().to_owned())
+}
+
+/// Compute the hex-encoded sha256 of the files in the unpacked package at
+/// `dir`, taken over the relative path and contents of each file in sorted
+/// order, and leaving out the files cargo-vet and cargo add when unpacking.
+/// Each path and contents is prefixed with its length in bytes, as a
+/// little-endian u64, so that moving bytes between them changes the hash.
+pub(crate) fn tree_hash(dir: &Path) -> Result<String, io::Error> {
+    fn collect_files(dir: &Path, relative: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in fs::read_dir(dir.join(relative))? {
+            let entry = entry?;
+            let path = relative.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                collect_files(dir, &path, files)?;
+            } else {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    collect_files(dir, Path::new(""), &mut files)?;
+    files.retain(|path| {
+        ![CARGO_OK_FILE, CHECKSUM_FILE, TREE_HASH_FILE]
+            .iter()
+            .any(|skipped| path == Path::new(skipped))
+    });
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for path in files {
+        // Symlinks are never unpacked, so hash where any link points rather
+        // than following it.
+        let full_path = dir.join(&path);
+        let contents = if full_path.symlink_metadata()?.file_type().is_symlink() {
+            fs::read_link(&full_path)?
+                .to_string_lossy()
+                .into_owned()
+                .into_bytes()
+        } else {
+            fs::read(&full_path)?
+        };
+        let path = path.to_string_lossy().replace('\\', "/");
+        for part in [path.as_bytes(), &contents] {
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part);
+        }
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Whether the files in the unpacked package at `dir` still match the hash
+/// recorded when it was unpacked.
+fn tree_is_unchanged(dir: &Path) -> bool {
+    let Ok(recorded) = fs::read_to_string(dir.join(TREE_HASH_FILE)) else {
+        return false;
+    };
+    tree_hash(dir).map_or(false, |hash| hash == recorded.trim())
+}
+
 /// Locate the checkout path for the given package and version if it is part of
 /// the local build graph. Returns `None` if a local checkout cannot be found.
 pub fn locate_local_checkout(
@@ -2787,8 +5379,21 @@ pub fn locate_local_checkout(
     None
 }
 
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct UnpackLimits {
+    pub max_size: u64,
+    pub max_files: u64,
+}
+
 #[tracing::instrument(err)]
-fn unpack_package(tarball: &File, unpack_dir: &Path) -> Result<(), UnpackError> {
+/// Unpack the .crate file `tarball` into `unpack_dir`, recording the hash of
+/// the unpacked files, and `checksum` if the tarball was verified.
+pub(crate) fn unpack_package(
+    tarball: &File,
+    unpack_dir: &Path,
+    checksum: Option<&str>,
+    limits: UnpackLimits,
+) -> Result<(), UnpackError> {
     // If we get here and the unpack_dir exists, this implies we had a previously failed fetch,
     // blast it away so we can have a clean slate!
     if unpack_dir.exists() {
@@ -2799,6 +5404,8 @@ fn unpack_package(tarball: &File, unpack_dir: &Path) -> Result<(), UnpackError>
     let mut tar = Archive::new(gz);
     let prefix = unpack_dir.file_name().unwrap();
     let parent = unpack_dir.parent().unwrap();
+    let mut total_size = 0u64;
+    let mut total_files = 0u64;
     for entry in tar.entries()? {
         let mut entry = entry.map_err(UnpackError::ArchiveIterate)?;
         let entry_path = entry
@@ -2806,6 +5413,18 @@ fn unpack_package(tarball: &File, unpack_dir: &Path) -> Result<(), UnpackError>
             .map_err(UnpackError::ArchiveEntry)?
             .into_owned();
 
+        // `unpack_in` would silently skip entries like these, but a tarball
+        // containing them is never one cargo produced, so refuse it entirely.
+        if entry_path.has_root() {
+            return Err(UnpackError::AbsolutePath { entry_path });
+        }
+        if entry_path
+            .components()
+            .any(|component| component == std::path::Component::ParentDir)
+        {
+            return Err(UnpackError::ParentDirPath { entry_path });
+        }
+
         // We're going to unpack this tarball into the global source
         // directory, but we want to make sure that it doesn't accidentally
         // (or maliciously) overwrite source code from other crates. Cargo
@@ -2819,6 +5438,36 @@ fn unpack_package(tarball: &File, unpack_dir: &Path) -> Result<(), UnpackError>
             });
         }
 
+        // Cargo never packages device files, and follows links rather than
+        // packaging them, so refuse any of these outright. Checking where a
+        // link points isn't enough, as chained links can still escape.
+        let special_kind = match entry.header().entry_type() {
+            tar::EntryType::Block => Some("block device"),
+            tar::EntryType::Char => Some("character device"),
+            tar::EntryType::Fifo => Some("FIFO"),
+            tar::EntryType::Symlink => Some("symlink"),
+            tar::EntryType::Link => Some("hardlink"),
+            _ => None,
+        };
+        if let Some(kind) = special_kind {
+            return Err(UnpackError::SpecialFile { entry_path, kind });
+        }
+
+        total_files += 1;
+        if total_files > limits.max_files {
+            return Err(UnpackError::TooManyFiles {
+                entry_path,
+                limit: limits.max_files,
+            });
+        }
+        total_size = total_size.saturating_add(entry.size());
+        if total_size > limits.max_size {
+            return Err(UnpackError::TooLarge {
+                entry_path,
+                limit: limits.max_size,
+            });
+        }
+
         entry
             .unpack_in(parent)
             .map_err(|error| UnpackError::Unpack {
@@ -2827,6 +5476,16 @@ fn unpack_package(tarball: &File, unpack_dir: &Path) -> Result<(), UnpackError>
             })?;
     }
 
+    let lock_error = |error| UnpackError::LockCreate {
+        target: unpack_dir.to_owned(),
+        error,
+    };
+    let tree_hash = tree_hash(unpack_dir).map_err(lock_error)?;
+    fs::write(unpack_dir.join(TREE_HASH_FILE), tree_hash).map_err(lock_error)?;
+    if let Some(checksum) = checksum {
+        fs::write(unpack_dir.join(CHECKSUM_FILE), checksum).map_err(lock_error)?;
+    }
+
     create_unpack_lock(unpack_dir).map_err(|error| UnpackError::LockCreate {
         target: unpack_dir.to_owned(),
         error,
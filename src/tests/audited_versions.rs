@@ -0,0 +1,221 @@
+use super::*;
+
+fn get_audited_versions_report(metadata: &Metadata, store: &Store) -> (String, String) {
+    let mut registry = MockRegistryBuilder::new();
+    registry
+        .package(
+            "third-party1",
+            &[
+                reg_published_by(ver(8), None, "2022-01-01"),
+                reg_published_by(ver(9), None, "2022-02-01"),
+                reg_published_by(ver(DEFAULT_VER), None, "2022-03-01"),
+                reg_published_by(ver(11), None, "2022-04-01"),
+                reg_published_by(ver(12), None, "2022-05-01"),
+            ],
+        )
+        .package(
+            "third-party2",
+            &[
+                reg_published_by(ver(DEFAULT_VER), None, "2022-03-01"),
+                reg_published_by(ver(11), None, "2022-04-01"),
+                reg_published_by(ver(12), None, "2022-05-01"),
+            ],
+        );
+    get_audited_versions_report_from(metadata, store, &registry)
+}
+
+fn get_audited_versions_report_from(
+    metadata: &Metadata,
+    store: &Store,
+    registry: &MockRegistryBuilder,
+) -> (String, String) {
+    // FIXME: Figure out how to handle disabling output colours better in tests.
+    console::set_colors_enabled(false);
+    console::set_colors_enabled_stderr(false);
+
+    let mut network = Network::new_mock();
+    registry.serve(&mut network);
+
+    let cfg = mock_cfg_args(metadata, ["cargo", "vet", "--no-registry-suggestions"]);
+    let report = crate::resolver::resolve(metadata, None, store);
+    let mut suggest = report
+        .compute_suggest(&cfg, store, Some(&network))
+        .unwrap()
+        .expect("no suggestions?");
+    report
+        .suggest_audited_versions(&cfg, store, Some(&network), &mut suggest)
+        .unwrap();
+
+    let human_output = BasicTestOutput::new();
+    report
+        .print_suggest_human(&human_output.clone().as_dyn(), &cfg, Some(&suggest))
+        .unwrap();
+    let json_output = BasicTestOutput::new();
+    report
+        .print_json(&json_output.clone().as_dyn(), Some(&suggest))
+        .unwrap();
+    (human_output.to_string(), json_output.to_string())
+}
+
+#[test]
+fn audited_versions_within_requirements() {
+    // third-party1 can only move back to 9.0.0, as 12.0.0 is outside of its
+    // requirements and 11.0.0 isn't audited. third-party2 should prefer the
+    // nearest newer audited version, 11.0.0, over 12.0.0.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::new(vec![
+        MockPackage {
+            name: "root-package",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![
+                dep_req("third-party1", ">=9.0.0, <12.0.0"),
+                dep_req("third-party2", ">=10.0.0"),
+            ],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "third-party1",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "third-party2",
+            ..Default::default()
+        },
+    ]);
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    audits.audits.insert(
+        "third-party1".to_owned(),
+        vec![
+            full_audit(ver(8), SAFE_TO_DEPLOY),
+            full_audit(ver(9), SAFE_TO_DEPLOY),
+            full_audit(ver(12), SAFE_TO_DEPLOY),
+        ],
+    );
+    audits.audits.insert(
+        "third-party2".to_owned(),
+        vec![
+            full_audit(ver(11), SAFE_TO_DEPLOY),
+            full_audit(ver(12), SAFE_TO_DEPLOY),
+        ],
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    let (human, json) = get_audited_versions_report(&metadata, &store);
+    insta::assert_snapshot!("audited_versions_within_requirements", human);
+    insta::assert_snapshot!("audited_versions_within_requirements.json", json);
+}
+
+#[test]
+fn audited_versions_none_available() {
+    // No other version satisfies the exact requirement, so nothing but the
+    // audit is suggested.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::new(vec![
+        MockPackage {
+            name: "root-package",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![dep("third-party1"), dep_req("third-party2", ">=10.0.0")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "third-party1",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "third-party2",
+            ..Default::default()
+        },
+    ]);
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    audits.audits.insert(
+        "third-party1".to_owned(),
+        vec![full_audit(ver(9), SAFE_TO_DEPLOY)],
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    let (human, _) = get_audited_versions_report(&metadata, &store);
+    insta::assert_snapshot!("audited_versions_none_available", human);
+}
+
+#[test]
+fn audited_versions_skip_yanked_and_new_dependencies() {
+    // third-party1 can't move to 11.0.0 as it was yanked, or to 12.0.0 as it
+    // depends on a package which isn't in the graph, so 13.0.0 is suggested.
+    // third-party2 can move to 11.0.0, as the new package it depends on is
+    // optional and not enabled.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::new(vec![
+        MockPackage {
+            name: "root-package",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![
+                dep_req("third-party1", ">=10.0.0"),
+                dep_req("third-party2", ">=10.0.0"),
+            ],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "third-party1",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "third-party2",
+            ..Default::default()
+        },
+    ]);
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    audits.audits.insert(
+        "third-party1".to_owned(),
+        vec![
+            full_audit(ver(11), SAFE_TO_DEPLOY),
+            full_audit(ver(12), SAFE_TO_DEPLOY),
+            full_audit(ver(13), SAFE_TO_DEPLOY),
+        ],
+    );
+    audits.audits.insert(
+        "third-party2".to_owned(),
+        vec![full_audit(ver(11), SAFE_TO_DEPLOY)],
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    let mut registry = MockRegistryBuilder::new();
+    registry
+        .package(
+            "third-party1",
+            &[
+                reg_published_by(ver(DEFAULT_VER), None, "2022-03-01"),
+                reg_published_by(ver(11), None, "2022-04-01").yanked(),
+                reg_published_by(ver(12), None, "2022-05-01").dep("new-package", "^1", json!({})),
+                reg_published_by(ver(13), None, "2022-06-01").dep("third-party2", "^10", json!({})),
+            ],
+        )
+        .package(
+            "third-party2",
+            &[
+                reg_published_by(ver(DEFAULT_VER), None, "2022-03-01"),
+                reg_published_by(ver(11), None, "2022-04-01").dep(
+                    "new-package",
+                    "^1",
+                    json!({ "optional": true }),
+                ),
+            ],
+        );
+
+    let (human, _) = get_audited_versions_report_from(&metadata, &store, &registry);
+    insta::assert_snapshot!("audited_versions_skip_yanked_and_new_dependencies", human);
+}
@@ -0,0 +1,169 @@
+use super::*;
+
+fn build_criteria_policy(criteria: &str) -> PackagePolicyEntry {
+    PackagePolicyEntry::Unversioned(PolicyEntry {
+        build_criteria: Some(vec![criteria.to_owned().into()]),
+        ..default_policy()
+    })
+}
+
+#[test]
+fn build_criteria_simple_deps() {
+    // (Pass) Build-dependencies and proc-macros only need to be safe-to-run
+    // with `build-criteria`, while normal deps still need safe-to-deploy.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple_deps();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    config
+        .policy
+        .insert("root".to_owned(), build_criteria_policy(SAFE_TO_RUN));
+    for name in [
+        "build",
+        "build-proc-macro",
+        "proc-macro",
+        "dev",
+        "dev-proc-macro",
+    ] {
+        audits.audits.insert(
+            name.to_owned(),
+            vec![full_audit(ver(DEFAULT_VER), SAFE_TO_RUN)],
+        );
+    }
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("build-criteria-simple-deps", metadata, store);
+}
+
+#[test]
+fn build_criteria_unset_inherits() {
+    // (Fail) Without `build-criteria`, build-dependencies and proc-macros
+    // inherit safe-to-deploy like any other dependency, and are labeled as
+    // build-only in the report.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple_deps();
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    for name in [
+        "build",
+        "build-proc-macro",
+        "proc-macro",
+        "dev",
+        "dev-proc-macro",
+    ] {
+        audits.audits.insert(
+            name.to_owned(),
+            vec![full_audit(ver(DEFAULT_VER), SAFE_TO_RUN)],
+        );
+    }
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("build-criteria-unset-inherits", metadata, store);
+}
+
+#[test]
+fn build_criteria_transitive_and_shared() {
+    // (Fail) The dependencies of a build-dependency inherit its
+    // `build-criteria`, but a package which is both a normal and a build
+    // dependency is linked in, and still needs safe-to-deploy.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::new(vec![
+        MockPackage {
+            name: "root",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![dep("shared")],
+            build_deps: vec![dep("build"), dep("shared")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "build",
+            deps: vec![dep("build-transitive")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "build-transitive",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "shared",
+            ..Default::default()
+        },
+    ]);
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    config
+        .policy
+        .insert("root".to_owned(), build_criteria_policy(SAFE_TO_RUN));
+    for name in ["build", "build-transitive", "shared"] {
+        audits.audits.insert(
+            name.to_owned(),
+            vec![full_audit(ver(DEFAULT_VER), SAFE_TO_RUN)],
+        );
+    }
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("build-criteria-transitive-and-shared", metadata, store);
+}
+
+#[test]
+fn build_criteria_third_party_build_deps() {
+    // (Pass) The build-dependencies and proc-macros of third-party crates are
+    // held to the `build-criteria` of the first-party crate pulling them in.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::new(vec![
+        MockPackage {
+            name: "root",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![dep("lib")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "lib",
+            deps: vec![dep("lib-macro")],
+            build_deps: vec![dep("lib-build")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "lib-macro",
+            targets: vec!["proc-macro"],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "lib-build",
+            deps: vec![dep("lib-build-transitive")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "lib-build-transitive",
+            ..Default::default()
+        },
+    ]);
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    config
+        .policy
+        .insert("root".to_owned(), build_criteria_policy(SAFE_TO_RUN));
+    for name in ["lib-macro", "lib-build", "lib-build-transitive"] {
+        audits.audits.insert(
+            name.to_owned(),
+            vec![full_audit(ver(DEFAULT_VER), SAFE_TO_RUN)],
+        );
+    }
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("build-criteria-third-party-build-deps", metadata, store);
+}
@@ -739,6 +739,71 @@ fn mock_trust_flow_simple() {
     insta::assert_snapshot!(result);
 }
 
+#[test]
+fn mock_trust_flow_mirror() {
+    // Trusting a publisher should look them up through the mirror configured
+    // in `[registry]`, rather than crates.io.
+    let mock = MockMetadata::simple();
+
+    let _enter = TEST_RUNTIME.enter();
+    let metadata = mock.metadata();
+
+    let (config, audits, imports) = files_inited(&metadata);
+
+    let output = BasicTestOutput::with_callbacks(|_| Ok("\n".to_owned()), |_| unimplemented!());
+
+    let mut cfg = mock_cfg_args(&metadata, ["cargo", "vet", "trust", "third-party1"]);
+    cfg._rest.registry = crate::format::RegistryConfig {
+        api: Some("https://mirror.example.com/crates-io".to_owned()),
+        index: Some("https://mirror.example.com/crates-io/index/".to_owned()),
+        ..Default::default()
+    };
+    let sub_args = if let Some(crate::cli::Commands::Trust(sub_args)) = &cfg.cli.command {
+        sub_args
+    } else {
+        unreachable!();
+    };
+
+    let mut network = Network::new_mock();
+    MockRegistryBuilder::new()
+        .user(2, "testuser", "Test user")
+        .package(
+            "third-party1",
+            &[
+                reg_published_by(ver(1), None, "2022-10-12"),
+                reg_published_by(ver(9), Some(2), "2022-10-12"),
+                reg_published_by(ver(DEFAULT_VER), Some(2), "2022-12-12"),
+            ],
+        )
+        .serve_at(
+            &mut network,
+            "https://mirror.example.com/crates-io",
+            "https://mirror.example.com/crates-io/index",
+        );
+
+    let mut store = Store::mock_online(&cfg, config, audits, imports, &network, true)
+        .expect("store acquisition failed");
+
+    crate::do_cmd_trust(
+        &output.clone().as_dyn(),
+        &cfg,
+        sub_args,
+        &mut store,
+        Some(&network),
+    )
+    .expect("do_cmd_trust failed");
+
+    let audits = crate::serialization::to_formatted_toml(
+        &store.audits,
+        Some(&crate::storage::user_info_map(&store.imports)),
+    )
+    .unwrap();
+
+    let result = format!("OUTPUT:\n{output}\nAUDITS:\n{audits}");
+
+    insta::assert_snapshot!(result);
+}
+
 #[test]
 fn mock_trust_flow_ambiguous() {
     let mock = MockMetadata::simple();
@@ -0,0 +1,159 @@
+use std::fs;
+use std::path::Path;
+
+use crate::cli::IngestDbDumpArgs;
+
+use super::*;
+
+const CRATES_CSV: &str = "\
+created_at,description,documentation,downloads,homepage,id,max_upload_size,name,readme,repository,updated_at
+2019-01-01 00:00:00.000000,the first third-party crate,,100,,1,,third-party1,,https://github.com/example/third-party1,2022-06-01 00:00:00.000000
+2019-01-01 00:00:00.000000,,,100,,2,,third-party2,,,2022-06-01 00:00:00.000000
+2019-01-01 00:00:00.000000,not in the dependency graph,,100,,3,,unrelated,,,2022-06-01 00:00:00.000000
+";
+
+const VERSIONS_CSV: &str = "\
+checksum,crate_id,crate_size,created_at,downloads,features,id,license,links,num,published_by,updated_at,yanked
+,1,100,2022-03-01 12:00:00.123456,10,{},1,MIT,,9.0.0,1,2022-03-01 12:00:00.123456,f
+,1,100,2022-06-01 12:00:00.123456,10,{},2,MIT,,10.0.0,2,2022-06-01 12:00:00.123456,f
+,2,100,2022-06-01 12:00:00.123456,10,{},3,MIT,,10.0.0,,2022-06-01 12:00:00.123456,f
+,3,100,2022-06-01 12:00:00.123456,10,{},4,MIT,,1.0.0,3,2022-06-01 12:00:00.123456,f
+";
+
+const USERS_CSV: &str = "\
+gh_avatar,gh_id,gh_login,id,name
+,1,user1,1,User One
+,2,user2,2,
+,3,user3,3,User Three
+";
+
+const METADATA_JSON: &str =
+    r#"{"timestamp":"2023-01-01T02:00:00.000000Z","crates_io_commit":"0000000"}"#;
+
+fn write_dump(dir: &Path) {
+    fs::create_dir_all(dir.join("data")).unwrap();
+    fs::write(dir.join("data/crates.csv"), CRATES_CSV).unwrap();
+    fs::write(dir.join("data/versions.csv"), VERSIONS_CSV).unwrap();
+    fs::write(dir.join("data/users.csv"), USERS_CSV).unwrap();
+    fs::write(dir.join("metadata.json"), METADATA_JSON).unwrap();
+}
+
+fn ingest_and_describe(cfg: &Config, path: &Path, all: bool) -> String {
+    let cache = crate::storage::Cache::acquire(cfg).unwrap();
+    let output = BasicTestOutput::new();
+    let sub_args = IngestDbDumpArgs {
+        path: path.to_owned(),
+        all,
+    };
+    crate::do_cmd_ingest_db_dump(&output.clone().as_dyn(), cfg, &sub_args, &cache).unwrap();
+
+    // Everything should now be available without the network.
+    let mut result = output.to_string();
+    for name in ["third-party1", "third-party2", "unrelated"] {
+        let Ok(metadata) =
+            tokio::runtime::Handle::current().block_on(cache.get_crate_metadata(None, name))
+        else {
+            result.push_str(&format!("{name}: not cached\n"));
+            continue;
+        };
+        result.push_str(&format!(
+            "{name}: {:?} {:?}\n",
+            metadata.description, metadata.repository
+        ));
+        let versions = [
+            semver::Version::new(9, 0, 0),
+            semver::Version::new(10, 0, 0),
+        ];
+        let publishers = tokio::runtime::Handle::current()
+            .block_on(cache.get_publishers(None, name, versions.iter().collect()))
+            .unwrap();
+        for (version, details) in publishers {
+            let user = details
+                .published_by
+                .and_then(|user_id| cache.get_crates_user_info(user_id));
+            result.push_str(&format!(
+                "  {version}: {} by {}\n",
+                details.created_at,
+                user.map_or("(unknown)".to_owned(), |user| user.to_string())
+            ));
+        }
+    }
+    result
+}
+
+#[test]
+fn ingest_db_dump_directory() {
+    // Ingesting an extracted dump should only load the crates in the graph.
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+    let metadata = mock.metadata();
+    let cfg = mock_cfg(&metadata);
+
+    let dir = tempfile::tempdir().unwrap();
+    write_dump(dir.path());
+
+    insta::assert_snapshot!(ingest_and_describe(&cfg, dir.path(), false));
+}
+
+#[test]
+fn ingest_db_dump_tarball_all() {
+    // Ingesting the tarball with `--all` should load every crate.
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+    let metadata = mock.metadata();
+    let cfg = mock_cfg(&metadata);
+
+    let dir = tempfile::tempdir().unwrap();
+    let dump_dir = dir.path().join("2023-01-01-020000");
+    write_dump(&dump_dir);
+
+    let tarball = dir.path().join("db-dump.tar.gz");
+    let encoder = flate2::write::GzEncoder::new(
+        fs::File::create(&tarball).unwrap(),
+        flate2::Compression::default(),
+    );
+    let mut builder = tar::Builder::new(encoder);
+    builder
+        .append_dir_all("2023-01-01-020000", &dump_dir)
+        .unwrap();
+    builder.into_inner().unwrap().finish().unwrap();
+
+    insta::assert_snapshot!(ingest_and_describe(&cfg, &tarball, true));
+}
+
+#[test]
+fn ingest_db_dump_tarball_out_of_order() {
+    // The tables in the tarball may come in any order, and only the crates in
+    // the graph should still be loaded.
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+    let metadata = mock.metadata();
+    let cfg = mock_cfg(&metadata);
+
+    let dir = tempfile::tempdir().unwrap();
+    let dump_dir = dir.path().join("2023-01-01-020000");
+    write_dump(&dump_dir);
+
+    let tarball = dir.path().join("db-dump.tar.gz");
+    let encoder = flate2::write::GzEncoder::new(
+        fs::File::create(&tarball).unwrap(),
+        flate2::Compression::default(),
+    );
+    let mut builder = tar::Builder::new(encoder);
+    for file in [
+        "data/users.csv",
+        "data/versions.csv",
+        "data/crates.csv",
+        "metadata.json",
+    ] {
+        builder
+            .append_path_with_name(
+                dump_dir.join(file),
+                Path::new("2023-01-01-020000").join(file),
+            )
+            .unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap();
+
+    insta::assert_snapshot!(ingest_and_describe(&cfg, &tarball, false));
+}
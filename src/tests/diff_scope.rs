@@ -0,0 +1,112 @@
+use std::path::Path;
+
+use super::*;
+use crate::format::{DiffConfig, DiffScope};
+
+fn diffed_files(
+    cache: &crate::storage::Cache,
+    v1: &Path,
+    v2: &Path,
+    has_git_rev: bool,
+    scope: DiffScope,
+) -> String {
+    let (diffstat, to_compare, unshipped) = tokio::runtime::Handle::current()
+        .block_on(cache.diffstat_package(v1, v2, has_git_rev, scope))
+        .unwrap();
+    let mut output = format!("{diffstat}\n");
+    for (from, to) in to_compare {
+        let (path, root) = if to == Path::new("/dev/null") {
+            (from, v1)
+        } else {
+            (to, v2)
+        };
+        let rel_path = path.strip_prefix(root).unwrap();
+        output += &format!("{}\n", rel_path.to_string_lossy().replace('\\', "/"));
+    }
+    let unshipped_output = BasicTestOutput::new();
+    crate::print_unshipped_changes(&unshipped_output.clone().as_dyn(), &unshipped);
+    output.push_str(&unshipped_output.to_string().replace('\\', "/"));
+    output
+}
+
+#[test]
+fn diff_scope_shipped_skips_unshipped_files() {
+    // With the shipped scope, changes to tests, benches, examples and
+    // documentation shouldn't be diffed, while changes to the library,
+    // binaries and build script should be, even when they live in a directory
+    // which would otherwise be skipped. Excluded files are only skipped when
+    // diffing a git checkout, and never within the library's modules.
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+    let metadata = mock.metadata();
+    let cfg = mock_cfg(&metadata);
+
+    let manifest = r#"
+[package]
+name = "third-party1"
+exclude = ["/fixtures", "*.snap"]
+
+[[bin]]
+name = "tool"
+path = "examples/tool/main.rs"
+
+[[test]]
+name = "integration"
+path = "integration/main.rs"
+"#;
+
+    let v1 = tempfile::tempdir().unwrap();
+    let v2 = tempfile::tempdir().unwrap();
+    for (dir, version) in [(&v1, "1"), (&v2, "2")] {
+        write_files(
+            dir.path(),
+            &[
+                ("Cargo.toml", manifest),
+                ("build.rs", &format!("fn main() {{ /* {version} */ }}\n")),
+                (
+                    "src/lib.rs",
+                    &format!("pub fn hello() {{ /* {version} */ }}\n"),
+                ),
+                ("src/snapshots/lib.snap", &format!("{version}\n")),
+                ("README.md", &format!("# third-party1 {version}\n")),
+                ("docs/guide.txt", &format!("{version}\n")),
+                ("tests/test.rs", &format!("// {version}\n")),
+                ("benches/bench.rs", &format!("// {version}\n")),
+                ("examples/example.rs", &format!("// {version}\n")),
+                ("examples/tool/main.rs", &format!("// {version}\n")),
+                ("examples/tool/util.rs", &format!("// {version}\n")),
+                ("integration/main.rs", &format!("// {version}\n")),
+                ("integration/helper.rs", &format!("// {version}\n")),
+                ("fixtures/data.rs", &format!("// {version}\n")),
+            ],
+        );
+    }
+    write_files(v2.path(), &[("src/added.rs", "pub fn added() {}\n")]);
+
+    let cache = crate::storage::Cache::acquire(&cfg).unwrap();
+    let all = diffed_files(&cache, v1.path(), v2.path(), false, DiffScope::All);
+    let shipped = diffed_files(&cache, v1.path(), v2.path(), false, DiffScope::Shipped);
+    let checkout = diffed_files(&cache, v1.path(), v2.path(), true, DiffScope::Shipped);
+    insta::assert_snapshot!(format!(
+        "all:\n{all}\nshipped:\n{shipped}\nshipped from a git checkout:\n{checkout}"
+    ));
+}
+
+#[test]
+fn diff_scope_config() {
+    // The scope for a crate should come from `[diff.crates]`, falling back to
+    // the `[diff]` table's scope, and then to diffing every file.
+    let mock = MockMetadata::simple();
+    let metadata = mock.metadata();
+    let (mut config, _, _) = files_inited(&metadata);
+    assert_eq!(config.diff_scope("third-party1"), DiffScope::All);
+
+    config.diff = Some(DiffConfig {
+        scope: Some(DiffScope::Shipped),
+        crates: [("third-party2".to_owned(), DiffScope::All)]
+            .into_iter()
+            .collect(),
+    });
+    assert_eq!(config.diff_scope("third-party1"), DiffScope::Shipped);
+    assert_eq!(config.diff_scope("third-party2"), DiffScope::All);
+}
@@ -0,0 +1,459 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use super::*;
+
+#[test]
+fn diff_source_highlights_unexplained_files() {
+    // Files only in the published package, or which differ from the commit it
+    // was published from, should be listed, while changes made by cargo's
+    // packaging should not.
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+    let metadata = mock.metadata();
+    let cfg = mock_cfg(&metadata);
+
+    let repo = tempfile::tempdir().unwrap();
+    git(repo.path(), &["init"]);
+    write_files(
+        repo.path(),
+        &[
+            ("README.md", "# third-party1\n"),
+            ("LICENSE", "MIT\n"),
+            (
+                "third-party1/Cargo.toml",
+                "[package]\nname = \"third-party1\"\nreadme = \"../README.md\"\n",
+            ),
+            ("third-party1/src/lib.rs", "pub fn hello() {}\n"),
+            ("third-party1/src/util.rs", "pub fn util() {}\n"),
+            ("third-party1/tests/excluded.rs", "#[test]\nfn test() {}\n"),
+        ],
+    );
+    git(repo.path(), &["add", "-A"]);
+    git(repo.path(), &["commit", "-m", "Initial commit"]);
+    let commit = git(repo.path(), &["rev-parse", "HEAD"]);
+
+    let published = tempfile::tempdir().unwrap();
+    write_files(
+        published.path(),
+        &[
+            (
+                ".cargo_vcs_info.json",
+                &format!(
+                    r#"{{"git": {{"sha1": "{commit}"}}, "path_in_vcs": "third-party1"}}"#
+                ),
+            ),
+            ("README.md", "# third-party1\n"),
+            ("LICENSE", "Not the license from the repository\n"),
+            (
+                "Cargo.toml",
+                "# THIS FILE IS AUTOMATICALLY GENERATED BY CARGO\n[package]\nname = \"third-party1\"\n",
+            ),
+            (
+                "Cargo.toml.orig",
+                "[package]\nname = \"third-party1\"\nreadme = \"../README.md\"\n",
+            ),
+            ("src/lib.rs", "pub fn hello() {}\n"),
+            ("src/util.rs", "pub fn util() { std::process::exit(1) }\n"),
+            ("src/extra.rs", "pub fn extra() {}\n"),
+        ],
+    );
+
+    let cache = crate::storage::Cache::acquire(&cfg).unwrap();
+    let comparison = tokio::runtime::Handle::current()
+        .block_on(cache.compare_with_source(published.path(), repo.path()))
+        .unwrap();
+
+    let output = BasicTestOutput::new();
+    crate::print_source_comparison(
+        &output.clone().as_dyn(),
+        "third-party1",
+        &ver(DEFAULT_VER),
+        Path::new("third-party1-repo"),
+        &comparison,
+    );
+    insta::assert_snapshot!(output.to_string().replace(&commit, "<commit>"));
+}
+
+#[test]
+fn diff_source_rejects_unsafe_vcs_info() {
+    // A .cargo_vcs_info.json whose commit could be taken as an option by git,
+    // or whose path leads outside of the repository, is refused.
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+    let metadata = mock.metadata();
+    let cfg = mock_cfg(&metadata);
+    let cache = crate::storage::Cache::acquire(&cfg).unwrap();
+    let repo = tempfile::tempdir().unwrap();
+
+    let commit = "0123456789abcdef0123456789abcdef01234567";
+    let mut output = String::new();
+    for (sha1, path_in_vcs) in [
+        ("--output=/tmp/evil", ""),
+        ("HEAD", ""),
+        (commit, "../outside"),
+        (commit, "/etc"),
+        (commit, "crate/../../outside"),
+    ] {
+        let published = tempfile::tempdir().unwrap();
+        write_files(
+            published.path(),
+            &[(
+                ".cargo_vcs_info.json",
+                &format!(r#"{{"git": {{"sha1": "{sha1}"}}, "path_in_vcs": "{path_in_vcs}"}}"#),
+            )],
+        );
+        let error = tokio::runtime::Handle::current()
+            .block_on(cache.compare_with_source(published.path(), repo.path()))
+            .unwrap_err();
+        let message = error
+            .to_string()
+            .replace(&published.path().display().to_string(), "<published>");
+        output.push_str(&format!("{message}\n"));
+    }
+    insta::assert_snapshot!(output);
+}
+
+#[cfg(unix)]
+#[test]
+fn diff_source_rejects_symlinked_package() {
+    // A package whose path in the commit is a symlink leading out of the
+    // repository is refused, rather than copying its manifest outside.
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+    let metadata = mock.metadata();
+    let cfg = mock_cfg(&metadata);
+
+    let outside = tempfile::tempdir().unwrap();
+    write_files(outside.path(), &[("Cargo.toml", "[package]\n")]);
+
+    let repo = tempfile::tempdir().unwrap();
+    git(repo.path(), &["init"]);
+    std::os::unix::fs::symlink(outside.path(), repo.path().join("third-party1")).unwrap();
+    git(repo.path(), &["add", "-A"]);
+    git(repo.path(), &["commit", "-m", "Initial commit"]);
+    let commit = git(repo.path(), &["rev-parse", "HEAD"]);
+
+    let published = tempfile::tempdir().unwrap();
+    write_files(
+        published.path(),
+        &[(
+            ".cargo_vcs_info.json",
+            &format!(r#"{{"git": {{"sha1": "{commit}"}}, "path_in_vcs": "third-party1"}}"#),
+        )],
+    );
+
+    let cache = crate::storage::Cache::acquire(&cfg).unwrap();
+    let result = tokio::runtime::Handle::current()
+        .block_on(cache.compare_with_source(published.path(), repo.path()));
+    assert!(
+        matches!(
+            result,
+            Err(crate::errors::DiffSourceError::UnsafeVcsInfo {
+                field: "path_in_vcs",
+                ..
+            })
+        ),
+        "unexpected result: {result:?}"
+    );
+    assert!(!outside.path().join("Cargo.toml.orig").exists());
+}
+
+#[test]
+fn diff_source_compares_manifests() {
+    // The published Cargo.toml is compared by what it builds rather than by its
+    // text, and only the readme and license file the original manifest names
+    // may be copied in from above the package.
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+    let metadata = mock.metadata();
+    let cfg = mock_cfg(&metadata);
+
+    let repo = tempfile::tempdir().unwrap();
+    git(repo.path(), &["init"]);
+    write_files(
+        repo.path(),
+        &[
+            (
+                "Cargo.toml",
+                r#"[workspace]
+members = ["third-party1"]
+
+[workspace.package]
+license-file = "LICENSE"
+
+[workspace.dependencies]
+serde = { version = "1.0", package = "serde-renamed" }
+"#,
+            ),
+            ("LICENSE", "MIT\n"),
+            ("NOTICE", "Not named by the manifest\n"),
+            (
+                "third-party1/Cargo.toml",
+                r#"[package]
+name = "third-party1"
+license-file.workspace = true
+links = "foo"
+
+[dependencies]
+serde = { workspace = true, optional = true }
+log = "0.4"
+
+[dev-dependencies]
+helper = { path = "../helper" }
+
+[features]
+default = ["serde"]
+"#,
+            ),
+            ("third-party1/build.rs", "fn main() {}\n"),
+            ("third-party1/src/lib.rs", "pub fn hello() {}\n"),
+            ("third-party1/src/main.rs", "fn main() {}\n"),
+            ("third-party1/examples/demo.rs", "fn main() {}\n"),
+        ],
+    );
+    git(repo.path(), &["add", "-A"]);
+    git(repo.path(), &["commit", "-m", "Initial commit"]);
+    let commit = git(repo.path(), &["rev-parse", "HEAD"]);
+
+    let normalized = r#"# THIS FILE IS AUTOMATICALLY GENERATED BY CARGO
+[package]
+name = "third-party1"
+build = "build.rs"
+autobins = false
+links = "foo"
+license-file = "LICENSE"
+
+[lib]
+path = "src/lib.rs"
+
+[[bin]]
+name = "third-party1"
+path = "./src/main.rs"
+
+[[example]]
+name = "demo"
+path = "examples/demo.rs"
+
+[dependencies.log]
+version = "0.4"
+
+[dependencies.serde]
+version = "1.0"
+package = "serde-renamed"
+optional = true
+
+[features]
+default = ["serde"]
+"#;
+    let cases = [
+        ("normalized", normalized.to_owned()),
+        (
+            "extra dependency",
+            format!("{normalized}\n[build-dependencies.cc]\nversion = \"1.0\"\n"),
+        ),
+        (
+            "platform dependency",
+            format!("{normalized}\n[target.'cfg(unix)'.dependencies.libc]\nversion = \"0.2\"\n"),
+        ),
+        (
+            "renamed dependency",
+            normalized.replace("serde-renamed", "serde-evil"),
+        ),
+        (
+            "required dependency",
+            normalized.replace("optional = true", "optional = false"),
+        ),
+        (
+            "extra target",
+            format!("{normalized}\n[[bin]]\nname = \"demo\"\npath = \"examples/demo.rs\"\n"),
+        ),
+        (
+            "no build script",
+            normalized.replace("build = \"build.rs\"", "build = false"),
+        ),
+        (
+            "changed feature",
+            normalized.replace("default = [\"serde\"]", "default = []"),
+        ),
+        ("changed links", normalized.replace("\"foo\"", "\"bar\"")),
+        ("invalid", "[package".to_owned()),
+    ];
+
+    let cache = crate::storage::Cache::acquire(&cfg).unwrap();
+    let mut output = String::new();
+    for (name, manifest) in cases {
+        let published = tempfile::tempdir().unwrap();
+        write_files(
+            published.path(),
+            &[
+                (
+                    ".cargo_vcs_info.json",
+                    &format!(r#"{{"git": {{"sha1": "{commit}"}}, "path_in_vcs": "third-party1"}}"#),
+                ),
+                ("Cargo.toml", &manifest),
+                (
+                    "Cargo.toml.orig",
+                    &fs::read_to_string(repo.path().join("third-party1/Cargo.toml")).unwrap(),
+                ),
+                ("LICENSE", "MIT\n"),
+                ("NOTICE", "Not named by the manifest\n"),
+                ("build.rs", "fn main() {}\n"),
+                ("src/lib.rs", "pub fn hello() {}\n"),
+                ("src/main.rs", "fn main() {}\n"),
+                ("examples/demo.rs", "fn main() {}\n"),
+            ],
+        );
+        let comparison = tokio::runtime::Handle::current()
+            .block_on(cache.compare_with_source(published.path(), repo.path()))
+            .unwrap();
+        writeln!(
+            output,
+            "{name}: added {:?}, modified {:?}",
+            comparison.added, comparison.modified
+        )
+        .unwrap();
+    }
+    insta::assert_snapshot!(output);
+}
+
+/// Commit a package to a new repository, and cache a .crate for
+/// third-party1:10.0.0 published from that commit, with `src/lib.rs` replaced
+/// by `published_lib`. Returns the repository, the cache directory and the
+/// commit.
+fn mock_published_package(published_lib: &str) -> (tempfile::TempDir, tempfile::TempDir, String) {
+    let repo = tempfile::tempdir().unwrap();
+    let manifest = "[package]\nname = \"third-party1\"\n";
+    git(repo.path(), &["init"]);
+    write_files(
+        repo.path(),
+        &[
+            ("Cargo.toml", manifest),
+            ("src/lib.rs", "pub fn hello() {}\n"),
+        ],
+    );
+    git(repo.path(), &["add", "-A"]);
+    git(repo.path(), &["commit", "-m", "Initial commit"]);
+    let commit = git(repo.path(), &["rev-parse", "HEAD"]);
+
+    let published = tempfile::tempdir().unwrap();
+    write_files(
+        published.path(),
+        &[
+            (
+                ".cargo_vcs_info.json",
+                &format!(r#"{{"git": {{"sha1": "{commit}"}}, "path_in_vcs": ""}}"#),
+            ),
+            ("Cargo.toml", manifest),
+            ("Cargo.toml.orig", manifest),
+            ("src/lib.rs", published_lib),
+        ],
+    );
+    let cache_dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(cache_dir.path().join("cache")).unwrap();
+    let tarball =
+        fs::File::create(cache_dir.path().join("cache/third-party1-10.0.0.crate")).unwrap();
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+        tarball,
+        flate2::Compression::default(),
+    ));
+    builder
+        .append_dir_all("third-party1-10.0.0", published.path())
+        .unwrap();
+    builder.into_inner().unwrap().finish().unwrap();
+
+    (repo, cache_dir, commit)
+}
+
+/// Run `cargo vet certify` for third-party1:10.0.0 with `--matches-source`,
+/// using the cache at `cache_dir`.
+fn certify_matches_source(
+    store: &mut Store,
+    repo: &Path,
+    cache_dir: &Path,
+) -> Result<(), crate::errors::CertifyError> {
+    let mut metadata = MockMetadata::simple().metadata();
+    let workspace = tempfile::tempdir().unwrap();
+    metadata.workspace_root = workspace.path().to_owned().try_into().unwrap();
+    let repo = repo.to_str().unwrap();
+    let mut cfg = mock_cfg_args(
+        &metadata,
+        [
+            "cargo",
+            "vet",
+            "certify",
+            "third-party1",
+            "10.0.0",
+            "--who",
+            "testing",
+            "--criteria",
+            "safe-to-deploy",
+            "--accept-all",
+            "--matches-source",
+            repo,
+        ],
+    );
+    cfg._rest.mock_cache = false;
+    cfg._rest.cache_dir = cache_dir.to_owned();
+    let sub_args = if let Some(crate::cli::Commands::Certify(sub_args)) = &cfg.cli.command {
+        sub_args
+    } else {
+        unreachable!();
+    };
+
+    crate::do_cmd_certify(
+        &BasicTestOutput::new().as_dyn(),
+        &cfg,
+        sub_args,
+        store,
+        None,
+        None,
+    )
+}
+
+#[test]
+fn certify_matches_source_records_commit() {
+    // A package which matches the commit it was published from is certified,
+    // with the commit noted in the audit.
+    let _enter = TEST_RUNTIME.enter();
+    let metadata = MockMetadata::simple().metadata();
+    let (config, audits, imports) = files_inited(&metadata);
+    let mut store = Store::mock(config, audits, imports);
+
+    let (repo, cache_dir, commit) = mock_published_package("pub fn hello() {}\n");
+    certify_matches_source(&mut store, repo.path(), cache_dir.path())
+        .expect("do_cmd_certify failed");
+
+    let audits = crate::serialization::to_formatted_toml(&store.audits, None).unwrap();
+    insta::assert_snapshot!(audits.to_string().replace(&commit, "<commit>"));
+}
+
+#[test]
+fn certify_matches_source_rejects_mismatch() {
+    // A package which differs from the commit it was published from isn't
+    // certified.
+    let _enter = TEST_RUNTIME.enter();
+    let metadata = MockMetadata::simple().metadata();
+    let (config, audits, imports) = files_inited(&metadata);
+    let mut store = Store::mock(config, audits, imports);
+    let before = crate::serialization::to_formatted_toml(&store.audits, None)
+        .unwrap()
+        .to_string();
+
+    let (repo, cache_dir, commit) =
+        mock_published_package("pub fn hello() { std::process::exit(1) }\n");
+    let result = certify_matches_source(&mut store, repo.path(), cache_dir.path());
+    assert!(
+        matches!(
+            &result,
+            Err(crate::errors::CertifyError::SourceMismatch { commit: mismatched, .. })
+                if *mismatched == commit
+        ),
+        "unexpected result: {result:?}"
+    );
+    let after = crate::serialization::to_formatted_toml(&store.audits, None)
+        .unwrap()
+        .to_string();
+    assert_eq!(before, after);
+}
@@ -0,0 +1,170 @@
+use super::*;
+
+fn get_explain(
+    metadata: &Metadata,
+    store: &Store,
+    package: &str,
+    version: u64,
+) -> (String, String) {
+    // FIXME: Figure out how to handle disabling output colours better in tests.
+    console::set_colors_enabled(false);
+    console::set_colors_enabled_stderr(false);
+
+    let report = crate::resolver::resolve(metadata, None, store);
+    let explain = report.explain(store, package, &ver(version)).unwrap();
+
+    let human_output = BasicTestOutput::new();
+    crate::resolver::print_explain_human(&human_output.clone().as_dyn(), &explain).unwrap();
+    let json_output = BasicTestOutput::new();
+    serde_json::to_writer_pretty(&*json_output.clone().as_dyn(), &explain).unwrap();
+    (human_output.to_string(), json_output.to_string())
+}
+
+#[test]
+fn explain_audit_chain() {
+    // A full audit, followed by a local delta audit and an imported delta
+    // audit.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, mut audits, mut imports) = builtin_files_full_audited(&metadata);
+
+    let mut full = full_audit(ver(3), SAFE_TO_DEPLOY);
+    full.who = vec!["Alice <alice@example.com>".to_owned().into()];
+    full.notes = Some("Looked at everything".to_owned());
+    audits.audits.insert(
+        "third-party1".to_owned(),
+        vec![full, delta_audit(ver(3), ver(5), SAFE_TO_DEPLOY)],
+    );
+    imports.audits.insert(
+        FOREIGN.to_owned(),
+        AuditsFile {
+            criteria: SortedMap::new(),
+            wildcard_audits: SortedMap::new(),
+            audits: [(
+                "third-party1".to_owned(),
+                vec![delta_audit(ver(5), ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+            )]
+            .into_iter()
+            .collect(),
+            trusted: SortedMap::new(),
+        },
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    let (human, json) = get_explain(&metadata, &store, "third-party1", DEFAULT_VER);
+    insta::assert_snapshot!("explain_audit_chain", human);
+    insta::assert_snapshot!("explain_audit_chain.json", json);
+}
+
+#[test]
+fn explain_exemption() {
+    // A package which is only vetted because of an exemption.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    audits.audits.remove("transitive-third-party1");
+    config.exemptions.insert(
+        "transitive-third-party1".to_owned(),
+        vec![exemptions(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    let (human, json) = get_explain(&metadata, &store, "transitive-third-party1", DEFAULT_VER);
+    insta::assert_snapshot!("explain_exemption", human);
+    insta::assert_snapshot!("explain_exemption.json", json);
+}
+
+#[test]
+fn explain_wildcard_and_trusted() {
+    // One package vetted by a wildcard audit, and another by a trusted entry.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, mut audits, mut imports) = builtin_files_full_audited(&metadata);
+    audits.audits.remove("third-party1");
+    audits.audits.remove("third-party2");
+    audits.wildcard_audits.insert(
+        "third-party1".to_owned(),
+        vec![wildcard_audit(1, SAFE_TO_DEPLOY)],
+    );
+    audits.trusted.insert(
+        "third-party2".to_owned(),
+        vec![trusted_entry(2, SAFE_TO_DEPLOY)],
+    );
+    imports.publisher.insert(
+        "third-party1".to_owned(),
+        vec![publisher_entry(ver(DEFAULT_VER), 1)],
+    );
+    imports.publisher.insert(
+        "third-party2".to_owned(),
+        vec![publisher_entry(ver(DEFAULT_VER), 2)],
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    let (human, json) = get_explain(&metadata, &store, "third-party1", DEFAULT_VER);
+    insta::assert_snapshot!("explain_wildcard", human);
+    insta::assert_snapshot!("explain_wildcard.json", json);
+
+    let (human, json) = get_explain(&metadata, &store, "third-party2", DEFAULT_VER);
+    insta::assert_snapshot!("explain_trusted", human);
+    insta::assert_snapshot!("explain_trusted.json", json);
+}
+
+#[test]
+fn explain_unsatisfied() {
+    // A package which doesn't satisfy its required criteria.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    audits.audits.insert(
+        "third-party2".to_owned(),
+        vec![full_audit(ver(DEFAULT_VER), SAFE_TO_RUN)],
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    let (human, json) = get_explain(&metadata, &store, "third-party2", DEFAULT_VER);
+    insta::assert_snapshot!("explain_unsatisfied", human);
+    insta::assert_snapshot!("explain_unsatisfied.json", json);
+}
+
+#[test]
+fn explain_errors() {
+    // Explaining first-party crates or packages which aren't in the graph fails.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+    let report = crate::resolver::resolve(&metadata, None, &store);
+
+    assert!(matches!(
+        report.explain(&store, "first-party", &ver(DEFAULT_VER)),
+        Err(crate::errors::ExplainError::FirstParty { .. })
+    ));
+    assert!(matches!(
+        report.explain(&store, "third-party1", &ver(1)),
+        Err(crate::errors::ExplainError::NotInGraph { .. })
+    ));
+    assert!(matches!(
+        report.explain(&store, "unknown-package", &ver(DEFAULT_VER)),
+        Err(crate::errors::ExplainError::NotInGraph { .. })
+    ));
+}
@@ -0,0 +1,131 @@
+use super::*;
+
+use crate::errors::StoreAcquireError;
+
+const BASE_PATH: &str = "../org/base.toml";
+const BASE_URL: &str = "https://example.com/org/base.toml";
+
+const BASE_CONFIG: &str = r#"
+default-criteria = "reviewed"
+
+[criteria.reviewed]
+description = "reviewed by the org"
+implies = "safe-to-deploy"
+
+[imports.org]
+url = "https://example.com/org/audits.toml"
+
+[policy.first-party]
+audit-as-crates-io = false
+
+[policy.shared]
+criteria = "reviewed"
+
+[workspaces.backend]
+manifest = "../backend/Cargo.toml"
+"#;
+
+const LOCAL_CONFIG: &str = r#"
+# cargo-vet config file
+extends = ["../org/base.toml"]
+
+[cargo-vet]
+version = "1.0"
+
+[policy.shared]
+criteria = "safe-to-run"
+"#;
+
+const EMPTY_AUDITS: &str = r#"
+# cargo-vet audits file
+
+[audits]
+"#;
+
+const ORG_IMPORTS: &str = r#"
+# cargo-vet imports lock
+
+[audits.org.audits]
+"#;
+
+fn acquire_with_base(config: &str, base: &str) -> Result<Store, StoreAcquireError> {
+    let today = chrono::NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+    Store::mock_acquire_with_bases(
+        config,
+        EMPTY_AUDITS,
+        ORG_IMPORTS,
+        &[(BASE_PATH, base), (BASE_URL, base)],
+        today,
+        true,
+    )
+}
+
+#[test]
+fn extends_layered_under_local() {
+    // Entries from the base config are visible in the store, but the store's
+    // own entries take precedence, and inherited entries aren't written back.
+
+    let store = acquire_with_base(LOCAL_CONFIG, BASE_CONFIG).unwrap();
+
+    assert_eq!(store.config.default_criteria, "reviewed");
+    assert!(store.audits.criteria.contains_key("reviewed"));
+    assert!(store.config.imports.contains_key("org"));
+    assert!(store.config.policy.package.contains_key("first-party"));
+    assert!(store.config.workspaces.contains_key("backend"));
+    let shared = store.config.policy.get("shared", &ver(1)).unwrap();
+    assert_eq!(
+        shared.criteria.as_ref().unwrap()[0],
+        "safe-to-run".to_owned()
+    );
+
+    let output = store.mock_commit();
+    assert_eq!(output["config.toml"].trim(), LOCAL_CONFIG.trim());
+    assert_eq!(output["audits.toml"].trim(), EMPTY_AUDITS.trim());
+}
+
+#[test]
+fn extends_modified_entry_kept() {
+    // Once an inherited entry is modified, it's written to the store's own
+    // config.toml as an override.
+
+    let mut store = acquire_with_base(LOCAL_CONFIG, BASE_CONFIG).unwrap();
+    store
+        .config
+        .policy
+        .get_mut("first-party", None)
+        .unwrap()
+        .audit_as_crates_io = Some(true);
+
+    let output = store.mock_commit();
+    insta::assert_snapshot!(output["config.toml"]);
+}
+
+#[test]
+fn extends_invalid_criteria_in_base() {
+    // Validation errors for inherited entries point at the base config.
+
+    let base = r#"
+[imports.org]
+url = "https://example.com/org/audits.toml"
+
+[policy.first-party]
+criteria = "nonexistent"
+"#;
+
+    let error = acquire_with_base(LOCAL_CONFIG, base).err().unwrap();
+    insta::assert_snapshot!(format!("{:?}", miette::Report::new(error)));
+}
+
+#[test]
+fn extends_url_not_pinned() {
+    let config = r#"
+# cargo-vet config file
+extends = ["https://example.com/org/base.toml"]
+
+[cargo-vet]
+version = "1.0"
+"#;
+
+    let error = acquire_with_base(config, BASE_CONFIG).err().unwrap();
+    insta::assert_snapshot!(format!("{:?}", miette::Report::new(error)));
+}
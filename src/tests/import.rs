@@ -2054,3 +2054,1520 @@ fn local_wildcard_audit_preferred_to_fresh_import() {
     let output = get_imports_file_changes_noprune(&metadata, &store);
     insta::assert_snapshot!(output);
 }
+
+#[test]
+fn signed_import_rejects_unverified() {
+    // (Fail) An import with trusted-keys rejects audits which are unsigned or
+    // have a bad signature, as well as wildcard audits, which can't be signed.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.remove("third-party1");
+    audits.audits.remove("third-party2");
+
+    let mut badly_signed = full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY);
+    badly_signed.signature = Some("untrusted comment: not a real signature".to_owned());
+
+    let foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        audits: [
+            ("third-party1".to_owned(), vec![badly_signed]),
+            (
+                "third-party2".to_owned(),
+                vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+            ),
+        ]
+        .into(),
+        wildcard_audits: [(
+            "transitive-third-party1".to_owned(),
+            vec![wildcard_audit(1, SAFE_TO_DEPLOY)],
+        )]
+        .into(),
+        trusted: SortedMap::new(),
+    };
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            trusted_keys: vec![
+                "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl"
+                    .to_owned(),
+            ],
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve_toml(FOREIGN_URL, &foreign_audits);
+
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let output = get_imports_file_changes_noprune(&metadata, &store);
+    insta::assert_snapshot!(output);
+}
+
+/// Generate an SSH key in `dir` to sign audits with, returning its public key.
+fn generate_ssh_key(dir: &std::path::Path) -> String {
+    let key = dir.join("id_ed25519");
+    let status = std::process::Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-C", "", "-f"])
+        .arg(&key)
+        .status()
+        .unwrap();
+    assert!(status.success(), "ssh-keygen failed");
+    std::fs::read_to_string(key.with_extension("pub"))
+        .unwrap()
+        .trim()
+        .to_owned()
+}
+
+/// Sign `audit` of `package` with the key generated in `dir`.
+fn sign_audit(dir: &std::path::Path, package: PackageStr<'_>, audit: &mut AuditEntry) {
+    audit.signature = Some(
+        TEST_RUNTIME
+            .block_on(crate::signing::sign(
+                &crate::format::SigningConfig {
+                    format: crate::format::SignatureFormat::Ssh,
+                    key: dir.join("id_ed25519"),
+                },
+                dir,
+                &crate::signing::audit_payload(package, audit),
+            ))
+            .unwrap(),
+    );
+}
+
+#[test]
+fn signed_audit_payload() {
+    // The signed payload uses a fixed serialization which doesn't depend on
+    // how audits are formatted in audits.toml.
+
+    let mut audit = delta_audit(ver(1), ver(DEFAULT_VER), SAFE_TO_DEPLOY);
+    audit.criteria.push(SAFE_TO_RUN.to_owned().into());
+    audit.notes = Some("multi-line\nnotes".to_owned());
+    audit.signature = Some("ignored".to_owned());
+    insta::assert_snapshot!(crate::signing::audit_payload("third-party1", &audit));
+}
+
+#[test]
+fn signed_import_accepts_verified() {
+    // (Pass) An import with trusted-keys accepts audits signed by one of them,
+    // while still rejecting unsigned audits.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.remove("third-party1");
+    audits.audits.remove("third-party2");
+
+    let key_dir = tempfile::tempdir().unwrap();
+    let public_key = generate_ssh_key(key_dir.path());
+
+    let mut signed = full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY);
+    sign_audit(key_dir.path(), "third-party1", &mut signed);
+
+    let foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        audits: [
+            ("third-party1".to_owned(), vec![signed]),
+            (
+                "third-party2".to_owned(),
+                vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+            ),
+        ]
+        .into(),
+        wildcard_audits: SortedMap::new(),
+        trusted: SortedMap::new(),
+    };
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            trusted_keys: vec![public_key],
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve_toml(FOREIGN_URL, &foreign_audits);
+
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let output = get_imports_file_changes_noprune(&metadata, &store);
+    // The signature differs on every run, so check which audits were imported
+    // rather than snapshotting them.
+    assert!(
+        output.contains("[[audits.peer-company.audits.third-party1]]"),
+        "signed audit wasn't imported:\n{output}"
+    );
+    assert!(
+        !output.contains("third-party2"),
+        "unsigned audit was imported:\n{output}"
+    );
+}
+
+#[test]
+fn pinned_import_keeps_locked_audits() {
+    // (Pass) An import pinned to a sha256 which no longer matches its contents
+    // keeps using the audits in imports.lock, ignoring the new audits.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, mut imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.remove("third-party1");
+    audits.audits.remove("third-party2");
+
+    let old_foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [(
+            "third-party1".to_owned(),
+            vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+        )]
+        .into(),
+        trusted: SortedMap::new(),
+    };
+
+    let mut new_foreign_audits = old_foreign_audits.clone();
+    new_foreign_audits.audits.insert(
+        "third-party2".to_owned(),
+        vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+    );
+
+    imports
+        .audits
+        .insert(FOREIGN.to_owned(), old_foreign_audits);
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            sha256: Some("0".repeat(64)),
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve_toml(FOREIGN_URL, &new_foreign_audits);
+
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let output = get_imports_file_changes_noprune(&metadata, &store);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn pinned_import_not_locked() {
+    // (Fail) An import pinned to a sha256 which doesn't match its contents
+    // can't be used if there are no audits for it in imports.lock.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_inited(&metadata);
+
+    let foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [(
+            "third-party1".to_owned(),
+            vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+        )]
+        .into(),
+        trusted: SortedMap::new(),
+    };
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            sha256: Some("0".repeat(64)),
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve_toml(FOREIGN_URL, &foreign_audits);
+
+    let result = Store::mock_online(&cfg, config, audits, imports, &network, true);
+    assert!(matches!(
+        result,
+        Err(crate::errors::StoreAcquireError::PinnedImportChanged { import_name, .. })
+            if import_name == FOREIGN
+    ));
+}
+
+#[test]
+fn import_update_diff() {
+    // The diff shown by `import --update` lists the entries which were added
+    // or removed, ignoring those which are unchanged.
+
+    let old_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [
+            (
+                "third-party1".to_owned(),
+                vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+            ),
+            (
+                "third-party2".to_owned(),
+                vec![
+                    full_audit(ver(5), SAFE_TO_RUN),
+                    delta_audit(ver(5), ver(DEFAULT_VER), SAFE_TO_RUN),
+                ],
+            ),
+        ]
+        .into(),
+        trusted: SortedMap::new(),
+    };
+
+    let new_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: [(
+            "transitive-third-party1".to_owned(),
+            vec![wildcard_audit(1, SAFE_TO_DEPLOY)],
+        )]
+        .into(),
+        audits: [
+            (
+                "third-party1".to_owned(),
+                vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+            ),
+            (
+                "third-party2".to_owned(),
+                vec![
+                    full_audit(ver(5), SAFE_TO_RUN),
+                    delta_audit(ver(5), ver(DEFAULT_VER), SAFE_TO_DEPLOY),
+                ],
+            ),
+        ]
+        .into(),
+        trusted: SortedMap::new(),
+    };
+
+    let output = crate::import_diff(&old_audits, &new_audits);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn local_file_import() {
+    // (Pass) Audits can be imported from a `file://` URL, as well as from the
+    // network.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.remove("third-party1");
+
+    let foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [(
+            "third-party1".to_owned(),
+            vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+        )]
+        .into(),
+        trusted: SortedMap::new(),
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audits.toml");
+    std::fs::write(
+        &path,
+        crate::serialization::to_formatted_toml(&foreign_audits, None)
+            .unwrap()
+            .to_string(),
+    )
+    .unwrap();
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![reqwest::Url::from_file_path(&path).unwrap().to_string()],
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let network = Network::new_mock();
+
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let output = get_imports_file_changes_noprune(&metadata, &store);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn git_import_at_rev() {
+    // (Pass) Audits can be imported from a local git repository at a specific
+    // revision, ignoring any later changes to the repository.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.remove("third-party1");
+
+    let mut foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [(
+            "third-party1".to_owned(),
+            vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+        )]
+        .into(),
+        trusted: SortedMap::new(),
+    };
+
+    let repo = tempfile::tempdir().unwrap();
+    let commit_audits = |audits: &AuditsFile| {
+        std::fs::create_dir_all(repo.path().join("supply-chain")).unwrap();
+        std::fs::write(
+            repo.path().join("supply-chain/audits.toml"),
+            crate::serialization::to_formatted_toml(audits, None)
+                .unwrap()
+                .to_string(),
+        )
+        .unwrap();
+        git(repo.path(), &["add", "-A"]);
+        git(repo.path(), &["commit", "-m", "Update audits"]);
+        git(repo.path(), &["rev-parse", "HEAD"])
+    };
+
+    git(repo.path(), &["init"]);
+    let rev = commit_audits(&foreign_audits);
+    foreign_audits.audits.insert(
+        "third-party2".to_owned(),
+        vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+    );
+    commit_audits(&foreign_audits);
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![format!(
+                "git+{}#{rev}",
+                reqwest::Url::from_directory_path(repo.path()).unwrap()
+            )],
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let network = Network::new_mock();
+
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let output = get_imports_file_changes_noprune(&metadata, &store);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn git_import_rejects_option_rev() {
+    // (Fail) A git import's revision can't be passed to git as an option.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let repo = tempfile::tempdir().unwrap();
+    let status = std::process::Command::new("git")
+        .arg("init")
+        .current_dir(repo.path())
+        .output()
+        .unwrap()
+        .status;
+    assert!(status.success(), "git init failed");
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![format!(
+                "git+{}#--output=audits.toml",
+                reqwest::Url::from_directory_path(repo.path()).unwrap()
+            )],
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let network = Network::new_mock();
+
+    let result = Store::mock_online(&cfg, config, audits, imports, &network, true);
+    assert!(
+        matches!(
+            &result,
+            Err(crate::errors::StoreAcquireError::FetchAuditError(error))
+                if matches!(
+                    **error,
+                    crate::errors::FetchAuditError::InvalidGitRev { ref rev, .. }
+                        if rev == "--output=audits.toml"
+                )
+        ),
+        "unexpected result: {:?}",
+        result.err()
+    );
+    assert!(!repo.path().join("audits.toml").exists());
+}
+
+#[test]
+fn require_review_holds_back_fresh_imports() {
+    // (Pass) New entries from an import which requires review aren't imported
+    // until they're accepted, while previously imported entries are kept.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, mut imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.remove("third-party1");
+    audits.audits.remove("third-party2");
+
+    let old_foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [(
+            "third-party1".to_owned(),
+            vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+        )]
+        .into(),
+        trusted: SortedMap::new(),
+    };
+
+    let mut new_foreign_audits = old_foreign_audits.clone();
+    new_foreign_audits.audits.insert(
+        "third-party2".to_owned(),
+        vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+    );
+
+    imports
+        .audits
+        .insert(FOREIGN.to_owned(), old_foreign_audits);
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            require_review: true,
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve_toml(FOREIGN_URL, &new_foreign_audits);
+
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let output = get_imports_file_changes_noprune(&metadata, &store);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn rejected_imports_are_ignored() {
+    // (Pass) Entries which were rejected when reviewing an import are never
+    // imported, even if the peer's notes for them change.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, mut imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.remove("third-party1");
+    audits.audits.remove("third-party2");
+
+    let mut renoted = full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY);
+    renoted.notes = Some("Looked at it again".to_owned());
+
+    let foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [
+            ("third-party1".to_owned(), vec![renoted]),
+            (
+                "third-party2".to_owned(),
+                vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+            ),
+        ]
+        .into(),
+        trusted: SortedMap::new(),
+    };
+
+    imports.rejected.insert(
+        FOREIGN.to_owned(),
+        AuditsFile {
+            criteria: SortedMap::new(),
+            wildcard_audits: SortedMap::new(),
+            audits: [(
+                "third-party1".to_owned(),
+                vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+            )]
+            .into(),
+            trusted: SortedMap::new(),
+        },
+    );
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve_toml(FOREIGN_URL, &foreign_audits);
+
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let output = get_imports_file_changes_noprune(&metadata, &store);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn review_imports_accept_and_reject() {
+    // Reviewing imports offers each new entry from the import, recording
+    // accepted and rejected entries in imports.lock.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.remove("third-party1");
+    audits.audits.remove("third-party2");
+
+    let mut audit = full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY);
+    audit.who = vec!["Alice <alice@example.com>".to_owned().into()];
+    audit.notes = Some("Reviewed the unsafe code".to_owned());
+
+    let foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [
+            ("third-party1".to_owned(), vec![audit]),
+            (
+                "third-party2".to_owned(),
+                vec![
+                    full_audit(ver(5), SAFE_TO_DEPLOY),
+                    delta_audit(ver(5), ver(DEFAULT_VER), SAFE_TO_DEPLOY),
+                ],
+            ),
+        ]
+        .into(),
+        trusted: SortedMap::new(),
+    };
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve_toml(FOREIGN_URL, &foreign_audits);
+
+    let mut store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    // Accept the first entry, and reject every other one.
+    let answers = std::sync::atomic::AtomicUsize::new(0);
+    let output = BasicTestOutput::with_callbacks(
+        move |_| {
+            let answer = answers.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(if answer == 0 { "y" } else { "n" }.to_owned())
+        },
+        |_| Err(io::ErrorKind::Unsupported.into()),
+    );
+
+    let accepted = crate::review_imports(&output.clone().as_dyn(), &mut store, None).unwrap();
+    crate::import_reviewed_entries(&cfg, &mut store, accepted);
+
+    let imports = crate::serialization::to_formatted_toml(
+        &store.imports,
+        Some(&crate::storage::user_info_map(&store.imports)),
+    )
+    .unwrap()
+    .to_string();
+
+    insta::assert_snapshot!(format!("{output}\n\nimports.lock:\n{imports}"));
+}
+
+#[test]
+fn review_imports_records_accepted_trusted() {
+    // Trusted entries from an import which requires review are held back until
+    // they're accepted, and once accepted are recorded in imports.lock so they
+    // aren't offered again.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: SortedMap::new(),
+        trusted: [(
+            "third-party1".to_owned(),
+            vec![trusted_entry(1, SAFE_TO_DEPLOY)],
+        )]
+        .into(),
+    };
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            require_review: true,
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve_toml(FOREIGN_URL, &foreign_audits);
+
+    let store = Store::mock_online(
+        &cfg,
+        config.clone(),
+        audits.clone(),
+        imports.clone(),
+        &network,
+        true,
+    )
+    .unwrap();
+    assert!(
+        store.imported_audits()[FOREIGN].trusted.is_empty(),
+        "unreviewed trusted entry was imported"
+    );
+
+    // Review the import as `cargo vet import --review` would, accepting the
+    // trusted entry.
+    let mut reviewing_config = config.clone();
+    reviewing_config
+        .imports
+        .get_mut(FOREIGN)
+        .unwrap()
+        .require_review = false;
+    let mut store = Store::mock_online(
+        &cfg,
+        reviewing_config,
+        audits.clone(),
+        imports,
+        &network,
+        true,
+    )
+    .unwrap();
+    let output = BasicTestOutput::with_callbacks(
+        |_| Ok("y".to_owned()),
+        |_| Err(io::ErrorKind::Unsupported.into()),
+    );
+    let accepted = crate::review_imports(&output.clone().as_dyn(), &mut store, None).unwrap();
+    store.config = config.clone();
+    crate::import_reviewed_entries(&cfg, &mut store, accepted);
+
+    let imports = crate::serialization::to_formatted_toml(
+        &store.imports,
+        Some(&crate::storage::user_info_map(&store.imports)),
+    )
+    .unwrap()
+    .to_string();
+
+    // The accepted entry is now imported, and isn't offered again.
+    let mut store =
+        Store::mock_online(&cfg, config, audits, store.imports, &network, true).unwrap();
+    assert_eq!(
+        store.imported_audits()[FOREIGN].trusted["third-party1"].len(),
+        1,
+        "accepted trusted entry wasn't imported"
+    );
+    let second_output = BasicTestOutput::with_callbacks(
+        |_| panic!("accepted trusted entry was offered again"),
+        |_| Err(io::ErrorKind::Unsupported.into()),
+    );
+    crate::review_imports(&second_output.as_dyn(), &mut store, None).unwrap();
+
+    insta::assert_snapshot!(format!("{output}\n\nimports.lock:\n{imports}"));
+}
+
+#[test]
+fn import_allow_lists() {
+    // (Pass) Only the entries allowed by an import's `include`,
+    // `allowed-criteria` and `who` lists are imported, and the criteria of
+    // the remaining audits are narrowed to the allowed criteria. Violations
+    // are only filtered by `include`.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_inited(&metadata);
+
+    let audit_by = |who: &str, entry: AuditEntry| AuditEntry {
+        who: vec![who.to_owned().into()],
+        ..entry
+    };
+
+    let new_foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: [(
+            "third-party2".to_owned(),
+            vec![WildcardEntry {
+                who: vec!["Mallory <mallory@example.org>".to_owned().into()],
+                ..wildcard_audit(1, SAFE_TO_DEPLOY)
+            }],
+        )]
+        .into(),
+        audits: [
+            (
+                "third-party1".to_owned(),
+                vec![
+                    audit_by(
+                        "Alice <alice@example.com>",
+                        full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY),
+                    ),
+                    audit_by(
+                        "Mallory <mallory@example.org>",
+                        delta_audit(ver(DEFAULT_VER), ver(11), SAFE_TO_DEPLOY),
+                    ),
+                    violation(VersionReq::parse("=5.0.0").unwrap(), SAFE_TO_DEPLOY),
+                ],
+            ),
+            (
+                "third-party2".to_owned(),
+                vec![audit_by(
+                    "Eve <eve@example.com>",
+                    full_audit(ver(DEFAULT_VER), SAFE_TO_RUN),
+                )],
+            ),
+            (
+                "transitive-third-party1".to_owned(),
+                vec![
+                    audit_by(
+                        "Alice <alice@example.com>",
+                        full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY),
+                    ),
+                    violation(VersionReq::parse("=5.0.0").unwrap(), SAFE_TO_DEPLOY),
+                ],
+            ),
+        ]
+        .into(),
+        trusted: [(
+            "third-party1".to_owned(),
+            vec![trusted_entry(1, SAFE_TO_DEPLOY)],
+        )]
+        .into(),
+    };
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            include: vec!["third-party*".to_owned()],
+            allowed_criteria: vec![SAFE_TO_RUN.to_owned().into()],
+            who: crate::format::WhoFilter {
+                allow: vec!["*@example.com".to_owned()],
+                deny: vec!["eve@example.com".to_owned()],
+            },
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve_toml(FOREIGN_URL, &new_foreign_audits);
+
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let output = crate::serialization::to_formatted_toml(&store.imported_audits()[FOREIGN], None)
+        .unwrap()
+        .to_string();
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn import_scopes() {
+    // (Pass) Scopes restrict each criteria to different crates and auditors,
+    // here trusting the peer for safe-to-run on any crate, but for
+    // safe-to-deploy only on the crates they authored, and only from certain
+    // reviewers. Entries outside of the safe-to-deploy scope are narrowed to
+    // safe-to-run.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_inited(&metadata);
+
+    let audit_by = |who: &str, entry: AuditEntry| AuditEntry {
+        who: vec![who.to_owned().into()],
+        ..entry
+    };
+
+    let new_foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: [(
+            "third-party1".to_owned(),
+            vec![WildcardEntry {
+                who: vec!["Alice <alice@example.com>".to_owned().into()],
+                ..wildcard_audit(1, SAFE_TO_DEPLOY)
+            }],
+        )]
+        .into(),
+        audits: [
+            (
+                "third-party1".to_owned(),
+                vec![
+                    audit_by(
+                        "Alice <alice@example.com>",
+                        full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY),
+                    ),
+                    audit_by(
+                        "Mallory <mallory@example.org>",
+                        delta_audit(ver(DEFAULT_VER), ver(11), SAFE_TO_DEPLOY),
+                    ),
+                ],
+            ),
+            (
+                "third-party2".to_owned(),
+                vec![
+                    audit_by(
+                        "Alice <alice@example.com>",
+                        full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY),
+                    ),
+                    violation(VersionReq::parse("=5.0.0").unwrap(), SAFE_TO_DEPLOY),
+                ],
+            ),
+        ]
+        .into(),
+        trusted: [(
+            "third-party1".to_owned(),
+            vec![trusted_entry(1, SAFE_TO_DEPLOY)],
+        )]
+        .into(),
+    };
+
+    let import: crate::format::RemoteImport = toml::de::from_str(&format!(
+        r#"
+url = "{FOREIGN_URL}"
+
+[[scope]]
+criteria = "safe-to-run"
+
+[[scope]]
+criteria = "safe-to-deploy"
+crates = ["third-party1"]
+who.allow = ["alice@example.com", "bob@example.com"]
+"#
+    ))
+    .unwrap();
+    config.imports.insert(FOREIGN.to_owned(), import);
+
+    let cfg = mock_cfg(&metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve_toml(FOREIGN_URL, &new_foreign_audits);
+    MockRegistryBuilder::new()
+        .user(1, "user1", "User One")
+        .package(
+            "third-party1",
+            &[reg_published_by(ver(DEFAULT_VER), Some(1), "2022-12-12")],
+        )
+        .serve(&mut network);
+
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let output = crate::serialization::to_formatted_toml(&store.imported_audits()[FOREIGN], None)
+        .unwrap()
+        .to_string();
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn transitive_import_chain() {
+    // (Pass) A transitive import also imports the audits its peer imports,
+    // composing their criteria maps and recording the chain of imports in
+    // `aggregated-from`. Imports which cycle back, or which are deeper than
+    // the depth limit, are ignored.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_inited(&metadata);
+
+    let peer_url = "https://peercompany.co.uk/supply-chain/audits.toml";
+    let rival_url = "https://rivalcompany.ca/supply-chain/audits.toml";
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![peer_url.to_owned()],
+            criteria_map: [(
+                "peer-reviewed".to_owned().into(),
+                vec![SAFE_TO_DEPLOY.to_owned().into()],
+            )]
+            .into_iter()
+            .collect(),
+            transitive: true,
+            transitive_depth: Some(2),
+            ..Default::default()
+        },
+    );
+
+    let mut network = Network::new_mock();
+    network.mock_serve(
+        peer_url,
+        r#"
+[criteria.peer-reviewed]
+description = "reviewed by peer-company"
+
+[[audits.third-party1]]
+criteria = "peer-reviewed"
+version = "10.0.0"
+"#,
+    );
+    network.mock_serve(
+        "https://peercompany.co.uk/supply-chain/config.toml",
+        r#"
+[imports.rival-company]
+url = "https://rivalcompany.ca/supply-chain/audits.toml"
+transitive = true
+
+[imports.rival-company.criteria-map]
+rival-reviewed = "peer-reviewed"
+"#,
+    );
+    network.mock_serve(
+        rival_url,
+        r#"
+[criteria.rival-reviewed]
+description = "reviewed by rival-company"
+
+[[audits.third-party2]]
+criteria = "rival-reviewed"
+version = "10.0.0"
+"#,
+    );
+    network.mock_serve(
+        "https://rivalcompany.ca/supply-chain/config.toml",
+        r#"
+[imports.peer-company]
+url = "https://peercompany.co.uk/supply-chain/audits.toml"
+
+[imports.third-company]
+url = "../../third.example/supply-chain/audits.toml"
+transitive = true
+"#,
+    );
+    network.mock_serve(
+        "https://rivalcompany.ca/third.example/supply-chain/audits.toml",
+        r#"
+[[audits.transitive-third-party1]]
+criteria = "safe-to-run"
+version = "10.0.0"
+"#,
+    );
+    network.mock_serve(
+        "https://rivalcompany.ca/third.example/supply-chain/config.toml",
+        r#"
+[imports.fourth-company]
+url = "https://fourth.example/supply-chain/audits.toml"
+"#,
+    );
+    network.mock_serve(
+        "https://fourth.example/supply-chain/audits.toml",
+        r#"
+[[audits.first-party]]
+criteria = "safe-to-deploy"
+version = "10.0.0"
+"#,
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let output = crate::serialization::to_formatted_toml(&store.imported_audits()[FOREIGN], None)
+        .unwrap()
+        .to_string();
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn transitive_import_checks_trusted_keys() {
+    // (Pass) The audits a signed import imports transitively must also be
+    // signed by one of its trusted-keys, even if the peer doesn't require
+    // signatures on them.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_inited(&metadata);
+
+    let key_dir = tempfile::tempdir().unwrap();
+    let public_key = generate_ssh_key(key_dir.path());
+
+    let peer_url = "https://peercompany.co.uk/supply-chain/audits.toml";
+    let rival_url = "https://rivalcompany.ca/supply-chain/audits.toml";
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![peer_url.to_owned()],
+            trusted_keys: vec![public_key],
+            transitive: true,
+            ..Default::default()
+        },
+    );
+
+    let mut signed = full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY);
+    sign_audit(key_dir.path(), "third-party1", &mut signed);
+    let rival_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [
+            ("third-party1".to_owned(), vec![signed]),
+            (
+                "third-party2".to_owned(),
+                vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+            ),
+        ]
+        .into(),
+        trusted: SortedMap::new(),
+    };
+
+    let mut network = Network::new_mock();
+    network.mock_serve(peer_url, "");
+    network.mock_serve(
+        "https://peercompany.co.uk/supply-chain/config.toml",
+        r#"
+[imports.rival-company]
+url = "https://rivalcompany.ca/supply-chain/audits.toml"
+"#,
+    );
+    network.mock_serve_toml(rival_url, &rival_audits);
+
+    let cfg = mock_cfg(&metadata);
+
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let imported = &store.imported_audits()[FOREIGN].audits;
+    assert_eq!(
+        imported.keys().collect::<Vec<_>>(),
+        ["third-party1"],
+        "unexpected transitively imported audits"
+    );
+}
+
+#[test]
+fn transitive_import_checks_trusted_keys_of_every_hop() {
+    // (Pass) The trusted-keys of an import part-way along a chain of
+    // transitive imports also apply to the audits imported through it.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_inited(&metadata);
+
+    let key_dir = tempfile::tempdir().unwrap();
+    let public_key = generate_ssh_key(key_dir.path());
+
+    let peer_url = "https://peercompany.co.uk/supply-chain/audits.toml";
+    let rival_url = "https://rivalcompany.ca/supply-chain/audits.toml";
+    let third_url = "https://third.example/supply-chain/audits.toml";
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![peer_url.to_owned()],
+            transitive: true,
+            ..Default::default()
+        },
+    );
+
+    let mut signed = full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY);
+    sign_audit(key_dir.path(), "third-party1", &mut signed);
+    let rival_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [("third-party1".to_owned(), vec![signed])].into(),
+        trusted: SortedMap::new(),
+    };
+    let third_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [(
+            "third-party2".to_owned(),
+            vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+        )]
+        .into(),
+        trusted: SortedMap::new(),
+    };
+
+    let mut network = Network::new_mock();
+    network.mock_serve(peer_url, "");
+    network.mock_serve(
+        "https://peercompany.co.uk/supply-chain/config.toml",
+        format!(
+            r#"
+[imports.rival-company]
+url = "{rival_url}"
+trusted-keys = ["{public_key}"]
+transitive = true
+"#
+        ),
+    );
+    network.mock_serve_toml(rival_url, &rival_audits);
+    network.mock_serve(
+        "https://rivalcompany.ca/supply-chain/config.toml",
+        format!(
+            r#"
+[imports.third-company]
+url = "{third_url}"
+"#
+        ),
+    );
+    network.mock_serve_toml(third_url, &third_audits);
+
+    let cfg = mock_cfg(&metadata);
+
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let imported = &store.imported_audits()[FOREIGN].audits;
+    assert_eq!(
+        imported.keys().collect::<Vec<_>>(),
+        ["third-party1"],
+        "unexpected transitively imported audits"
+    );
+}
+
+#[test]
+fn pinned_transitive_import() {
+    // (Fail) An import can't be pinned to a sha256 while also being
+    // transitive, as the hash doesn't cover the audits imported through it.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_inited(&metadata);
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            sha256: Some("0".repeat(64)),
+            transitive: true,
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve(FOREIGN_URL, "");
+
+    let result = Store::mock_online(&cfg, config, audits, imports, &network, true);
+    assert!(matches!(
+        result,
+        Err(crate::errors::StoreAcquireError::PinnedTransitiveImport { import_name })
+            if import_name == FOREIGN
+    ));
+}
+
+#[test]
+fn relative_git_import() {
+    // (Pass) A git import's repository may be relative to the store, and is
+    // read without the network.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.remove("third-party1");
+
+    let foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [(
+            "third-party1".to_owned(),
+            vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+        )]
+        .into(),
+        trusted: SortedMap::new(),
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("supply-chain");
+    let repo = dir.path().join("shared-audits");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::create_dir_all(repo.join("audits")).unwrap();
+    std::fs::write(
+        repo.join("audits/audits.toml"),
+        crate::serialization::to_formatted_toml(&foreign_audits, None)
+            .unwrap()
+            .to_string(),
+    )
+    .unwrap();
+    git(&repo, &["init"]);
+    git(&repo, &["add", "-A"]);
+    git(&repo, &["commit", "-m", "Add audits"]);
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec!["git+file:../shared-audits?path=audits/audits.toml#HEAD".to_owned()],
+            ..Default::default()
+        },
+    );
+
+    let mut store = Store::mock(config, audits, imports);
+    TEST_RUNTIME
+        .block_on(store.read_local_imports(&root, true))
+        .unwrap();
+
+    let output = get_imports_file_changes_noprune(&metadata, &store);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn local_imports_read_offline() {
+    // (Pass) Without the network, local imports are still read, while imports
+    // from the network keep the audits recorded in imports.lock.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, mut audits, mut imports) = builtin_files_full_audited(&metadata);
+
+    audits.audits.remove("third-party1");
+    audits.audits.remove("third-party2");
+
+    let foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [(
+            "third-party1".to_owned(),
+            vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+        )]
+        .into(),
+        trusted: SortedMap::new(),
+    };
+    let other_foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [(
+            "third-party2".to_owned(),
+            vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+        )]
+        .into(),
+        trusted: SortedMap::new(),
+    };
+
+    let root = tempfile::tempdir().unwrap();
+    std::fs::write(
+        root.path().join("audits.toml"),
+        crate::serialization::to_formatted_toml(&foreign_audits, None)
+            .unwrap()
+            .to_string(),
+    )
+    .unwrap();
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec!["audits.toml".to_owned()],
+            ..Default::default()
+        },
+    );
+    config.imports.insert(
+        OTHER_FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![OTHER_FOREIGN_URL.to_owned()],
+            ..Default::default()
+        },
+    );
+    imports
+        .audits
+        .insert(OTHER_FOREIGN.to_owned(), other_foreign_audits);
+
+    let mut store = Store::mock(config, audits, imports);
+    TEST_RUNTIME
+        .block_on(store.read_local_imports(root.path(), true))
+        .unwrap();
+
+    let output = get_imports_file_changes_noprune(&metadata, &store);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn require_review_keeps_fresh_violations() {
+    // (Fail) New violations from an import which requires review are used
+    // immediately, without waiting for the import to be reviewed.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, mut imports) = builtin_files_full_audited(&metadata);
+
+    let old_foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: SortedMap::new(),
+        trusted: SortedMap::new(),
+    };
+
+    let mut new_foreign_audits = old_foreign_audits.clone();
+    new_foreign_audits.audits.insert(
+        "third-party1".to_owned(),
+        vec![violation(
+            VersionReq::parse(&format!("={DEFAULT_VER}")).unwrap(),
+            SAFE_TO_DEPLOY,
+        )],
+    );
+
+    imports
+        .audits
+        .insert(FOREIGN.to_owned(), old_foreign_audits);
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            require_review: true,
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve_toml(FOREIGN_URL, &new_foreign_audits);
+
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let report = crate::resolver::resolve(&metadata, None, &store);
+    assert!(report.has_errors(), "new violation was held back");
+
+    assert_report_snapshot!("require-review-keeps-fresh-violations", metadata, store);
+}
+
+#[test]
+fn review_imports_offers_every_fresh_entry() {
+    // Reviewing imports offers every new entry from the import, including
+    // violations and audits which aren't needed to vet the graph, and records
+    // each accepted entry in imports.lock.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [
+            (
+                "third-party1".to_owned(),
+                vec![violation(
+                    VersionReq::parse("=5.0.0").unwrap(),
+                    SAFE_TO_DEPLOY,
+                )],
+            ),
+            (
+                "unrelated-crate".to_owned(),
+                vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+            ),
+        ]
+        .into(),
+        trusted: [(
+            "unrelated-crate".to_owned(),
+            vec![trusted_entry(1, SAFE_TO_DEPLOY)],
+        )]
+        .into(),
+    };
+
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve_toml(FOREIGN_URL, &foreign_audits);
+
+    let mut store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let output = BasicTestOutput::with_callbacks(
+        |_| Ok("y".to_owned()),
+        |_| Err(io::ErrorKind::Unsupported.into()),
+    );
+    let accepted = crate::review_imports(&output.clone().as_dyn(), &mut store, None).unwrap();
+    crate::import_reviewed_entries(&cfg, &mut store, accepted);
+
+    let imports = crate::serialization::to_formatted_toml(
+        &store.imports,
+        Some(&crate::storage::user_info_map(&store.imports)),
+    )
+    .unwrap()
+    .to_string();
+
+    insta::assert_snapshot!(format!("{output}\n\nimports.lock:\n{imports}"));
+}
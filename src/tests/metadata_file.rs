@@ -0,0 +1,156 @@
+use crate::errors::MetadataAcquireError;
+
+use super::*;
+
+fn metadata_json(metadata: &Metadata) -> serde_json::Value {
+    serde_json::to_value(metadata).unwrap()
+}
+
+fn parse(json: &serde_json::Value) -> Result<Metadata, MetadataAcquireError> {
+    crate::parse_metadata(&json.to_string())
+}
+
+#[test]
+fn metadata_file_simple() {
+    // (Pass) Metadata produced elsewhere resolves just like metadata from
+    // running `cargo metadata`.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+    let store = Store::mock(config, audits, imports);
+
+    let metadata = parse(&metadata_json(&metadata)).unwrap();
+
+    assert_report_snapshot!("metadata-file-simple", metadata, store);
+}
+
+#[test]
+fn metadata_file_invalid() {
+    // Malformed metadata files are rejected up front, rather than causing
+    // panics while building the dependency graph.
+
+    let metadata = MockMetadata::simple().metadata();
+    let valid = metadata_json(&metadata);
+
+    assert!(matches!(
+        crate::parse_metadata("not json"),
+        Err(MetadataAcquireError::InvalidJson(_))
+    ));
+
+    let mut json = valid.clone();
+    json["version"] = 2.into();
+    assert!(matches!(
+        parse(&json),
+        Err(MetadataAcquireError::UnsupportedVersion(_))
+    ));
+
+    let mut json = valid.clone();
+    json["resolve"] = serde_json::Value::Null;
+    assert!(matches!(
+        parse(&json),
+        Err(MetadataAcquireError::MissingResolve)
+    ));
+
+    let mut json = valid.clone();
+    json["workspace_members"] = serde_json::json!([]);
+    assert!(matches!(
+        parse(&json),
+        Err(MetadataAcquireError::NoWorkspaceMembers)
+    ));
+
+    let mut json = valid.clone();
+    json["packages"].as_array_mut().unwrap().pop();
+    assert!(matches!(
+        parse(&json),
+        Err(MetadataAcquireError::UnknownPackage(_))
+    ));
+
+    let mut json = valid;
+    json["resolve"]["nodes"].as_array_mut().unwrap().clear();
+    assert!(matches!(
+        parse(&json),
+        Err(MetadataAcquireError::UnresolvedPackage(_))
+    ));
+}
+
+#[test]
+fn metadata_file_not_maximal() {
+    // Metadata files which weren't produced with `--all-features`, or were
+    // produced with `--filter-platform`, are rejected.
+
+    let metadata = MockMetadata::simple().metadata();
+    let valid = metadata_json(&metadata);
+    let root_idx = |json: &serde_json::Value| {
+        json["packages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .position(|p| p["name"] == "root-package")
+            .unwrap()
+    };
+
+    let mut json = valid.clone();
+    let idx = root_idx(&json);
+    json["packages"][idx]["features"] = serde_json::json!({ "extra": [] });
+    assert!(matches!(
+        parse(&json),
+        Err(MetadataAcquireError::NotAllFeatures(_))
+    ));
+    json["resolve"]["nodes"][idx]["features"] = serde_json::json!(["extra"]);
+    parse(&json).unwrap();
+
+    let mut json = valid;
+    let idx = root_idx(&json);
+    let removed = json["resolve"]["nodes"][idx]["deps"]
+        .as_array_mut()
+        .unwrap()
+        .remove(0);
+    let dependency = removed["name"].as_str().unwrap().to_owned();
+    match parse(&json) {
+        Err(MetadataAcquireError::MissingDependency {
+            dependency: missing,
+            ..
+        }) => assert_eq!(missing, dependency),
+        other => panic!("unexpected result {other:?}"),
+    }
+
+    let deps = json["packages"][idx]["dependencies"]
+        .as_array_mut()
+        .unwrap();
+    let dep = deps.iter_mut().find(|d| d["name"] == dependency).unwrap();
+    dep["optional"] = true.into();
+    assert!(matches!(
+        parse(&json),
+        Err(MetadataAcquireError::NotAllFeatures(_))
+    ));
+}
+
+#[test]
+fn metadata_file_workspace() {
+    // The metadata file has to describe the workspace cargo-vet is run in, as
+    // that's where the store is found.
+
+    let workspace = tempfile::tempdir().unwrap();
+    std::fs::write(workspace.path().join("Cargo.toml"), "").unwrap();
+    std::fs::create_dir(workspace.path().join("member")).unwrap();
+    let elsewhere = tempfile::tempdir().unwrap();
+
+    let mut metadata = MockMetadata::simple().metadata();
+    metadata.workspace_root = workspace.path().to_owned().try_into().unwrap();
+
+    crate::check_metadata_workspace(&metadata, workspace.path()).unwrap();
+    crate::check_metadata_workspace(&metadata, &workspace.path().join("member")).unwrap();
+    assert!(matches!(
+        crate::check_metadata_workspace(&metadata, elsewhere.path()),
+        Err(MetadataAcquireError::WorkspaceMismatch { .. })
+    ));
+
+    std::fs::remove_file(workspace.path().join("Cargo.toml")).unwrap();
+    assert!(matches!(
+        crate::check_metadata_workspace(&metadata, workspace.path()),
+        Err(MetadataAcquireError::WorkspaceMismatch { .. })
+    ));
+}
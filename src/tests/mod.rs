@@ -4,7 +4,7 @@ use std::{
     fmt,
     fmt::Write,
     fs, io,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
 
@@ -49,18 +49,32 @@ macro_rules! assert_report_snapshot {
 
 mod aggregate;
 mod audit_as_crates_io;
+mod audited_versions;
+mod build_criteria;
 mod certify;
 mod crate_policies;
+mod db_dump;
+mod diff_scope;
+mod diff_source;
+mod explain;
+mod extends;
 mod import;
+mod metadata_file;
+mod profiles;
 mod regenerate_unaudited;
 mod registry;
 mod renew;
 mod store_parsing;
+mod targets;
 mod trusted;
+mod unpack;
 mod unpublished;
 mod vet;
 mod violations;
+mod what_if;
+mod why;
 mod wildcard;
+mod workspaces;
 
 // Some room above and below
 const DEFAULT_VER: u64 = 10;
@@ -121,6 +135,8 @@ struct MockPackage {
 struct MockDependency {
     name: &'static str,
     version: VetVersion,
+    target: Option<&'static str>,
+    req: Option<&'static str>,
 }
 
 impl Default for MockPackage {
@@ -159,6 +175,24 @@ fn dep_ver(name: &'static str, version: u64) -> MockDependency {
     MockDependency {
         name,
         version: ver(version),
+        target: None,
+        req: None,
+    }
+}
+
+/// A dependency which is only enabled on platforms matching `target` (e.g.
+/// `cfg(windows)`).
+fn dep_target(name: &'static str, target: &'static str) -> MockDependency {
+    MockDependency {
+        target: Some(target),
+        ..dep(name)
+    }
+}
+
+fn dep_req(name: &'static str, req: &'static str) -> MockDependency {
+    MockDependency {
+        req: Some(req),
+        ..dep(name)
     }
 }
 
@@ -169,6 +203,7 @@ fn default_exemptions(version: VetVersion, config: &ConfigFile) -> ExemptedDepen
         criteria: vec![config.default_criteria.clone().into()],
         notes: None,
         suggest: true,
+        workspace: None,
     }
 }
 fn exemptions(version: VetVersion, criteria: CriteriaStr) -> ExemptedDependency {
@@ -177,6 +212,7 @@ fn exemptions(version: VetVersion, criteria: CriteriaStr) -> ExemptedDependency
         criteria: vec![criteria.to_string().into()],
         notes: None,
         suggest: true,
+        workspace: None,
     }
 }
 
@@ -188,6 +224,7 @@ fn delta_audit(from: VetVersion, to: VetVersion, criteria: CriteriaStr) -> Audit
         kind: AuditKind::Delta { from, to },
         importable: true,
         aggregated_from: vec![],
+        signature: None,
         is_fresh_import: false,
     }
 }
@@ -200,6 +237,7 @@ fn full_audit(version: VetVersion, criteria: CriteriaStr) -> AuditEntry {
         kind: AuditKind::Full { version },
         importable: true,
         aggregated_from: vec![],
+        signature: None,
         is_fresh_import: false,
     }
 }
@@ -215,6 +253,7 @@ fn full_audit_m(
         kind: AuditKind::Full { version },
         importable: true,
         aggregated_from: vec![],
+        signature: None,
         is_fresh_import: false,
     }
 }
@@ -227,6 +266,7 @@ fn violation_hard(version: VersionReq) -> AuditEntry {
         kind: AuditKind::Violation { violation: version },
         importable: true,
         aggregated_from: vec![],
+        signature: None,
         is_fresh_import: false,
     }
 }
@@ -239,6 +279,7 @@ fn violation(version: VersionReq, criteria: CriteriaStr) -> AuditEntry {
         kind: AuditKind::Violation { violation: version },
         importable: true,
         aggregated_from: vec![],
+        signature: None,
         is_fresh_import: false,
     }
 }
@@ -254,6 +295,7 @@ fn violation_m(
         kind: AuditKind::Violation { violation: version },
         importable: true,
         aggregated_from: vec![],
+        signature: None,
         is_fresh_import: false,
     }
 }
@@ -332,6 +374,7 @@ fn default_policy() -> PolicyEntry {
         audit_as_crates_io: None,
         criteria: None,
         dev_criteria: None,
+        build_criteria: None,
         dependency_criteria: SortedMap::new(),
         notes: None,
     }
@@ -470,6 +513,8 @@ impl MockMetadata {
                         version: "10.0.0@git:00112233445566778899aabbccddeeff00112233"
                             .parse()
                             .unwrap(),
+                        target: None,
+                        req: None,
                     },
                     dep("third-party2"),
                 ],
@@ -825,13 +870,13 @@ impl MockMetadata {
                 "dependencies": package.deps.iter().chain(&package.dev_deps).chain(&package.build_deps).map(|dep| json!({
                     "name": dep.name,
                     "source": self.source(self.package_by(dep.name, &dep.version)),
-                    "req": format!("={}", dep.version.semver),
+                    "req": dep.req.map(str::to_owned).unwrap_or_else(|| format!("={}", dep.version.semver)),
                     "kind": null,
                     "rename": null,
                     "optional": false,
                     "uses_default_features": true,
                     "features": [],
-                    "target": null,
+                    "target": dep.target,
                     "registry": null
                 })).collect::<Vec<_>>(),
                 "targets": package.targets.iter().map(|target| json!({
@@ -873,15 +918,15 @@ impl MockMetadata {
             }).collect::<Vec<_>>(),
             "resolve": {
                 "nodes": self.packages.iter().map(|package| {
-                    let mut all_deps = BTreeMap::<(PackageStr, &VetVersion), Vec<Option<&str>>>::new();
+                    let mut all_deps = BTreeMap::<(PackageStr, &VetVersion), Vec<(Option<&str>, Option<&str>)>>::new();
                     for dep in &package.deps {
-                        all_deps.entry((dep.name, &dep.version)).or_default().push(None);
+                        all_deps.entry((dep.name, &dep.version)).or_default().push((None, dep.target));
                     }
                     for dep in &package.build_deps {
-                        all_deps.entry((dep.name, &dep.version)).or_default().push(Some("build"));
+                        all_deps.entry((dep.name, &dep.version)).or_default().push((Some("build"), dep.target));
                     }
                     for dep in &package.dev_deps {
-                        all_deps.entry((dep.name, &dep.version)).or_default().push(Some("dev"));
+                        all_deps.entry((dep.name, &dep.version)).or_default().push((Some("dev"), dep.target));
                     }
                     json!({
                         "id": self.pkgid(package),
@@ -889,9 +934,9 @@ impl MockMetadata {
                         "deps": all_deps.iter().map(|((name, version), kinds)| json!({
                             "name": name,
                             "pkg": self.pkgid_by(name, version),
-                            "dep_kinds": kinds.iter().map(|kind| json!({
+                            "dep_kinds": kinds.iter().map(|(kind, target)| json!({
                                 "kind": kind,
-                                "target": null,
+                                "target": target,
                             })).collect::<Vec<_>>(),
                         })).collect::<Vec<_>>(),
                     })
@@ -914,7 +959,16 @@ fn init_files(
 ) -> (ConfigFile, AuditsFile, ImportsFile) {
     let mut config = ConfigFile {
         cargo_vet: Default::default(),
+        extends: Default::default(),
         default_criteria: default_criteria.to_owned(),
+        targets: Default::default(),
+        violation_conflicts: Default::default(),
+        profiles: Default::default(),
+        workspaces: Default::default(),
+        signing: Default::default(),
+        registry: None,
+        unpack: None,
+        diff: None,
         imports: Default::default(),
         policy: Default::default(),
         exemptions: Default::default(),
@@ -929,6 +983,8 @@ fn init_files(
         unpublished: SortedMap::new(),
         publisher: SortedMap::new(),
         audits: SortedMap::new(),
+        extends: SortedMap::new(),
+        rejected: SortedMap::new(),
     };
 
     // Make the root packages use our custom criteria instead of the builtins
@@ -942,6 +998,7 @@ fn init_files(
                             audit_as_crates_io: None,
                             criteria: Some(vec![default_criteria.to_string().into()]),
                             dev_criteria: Some(vec![default_criteria.to_string().into()]),
+                            build_criteria: None,
                             dependency_criteria: CriteriaMap::new(),
                             notes: None,
                         }),
@@ -1099,11 +1156,14 @@ where
     Config {
         metacfg: MetaConfig(vec![]),
         metadata: metadata.clone(),
+        workspaces: SortedMap::new(),
         _rest: PartialConfig {
             cli,
             now: mock_now(),
             cache_dir: PathBuf::new(),
             mock_cache: true,
+            registry: Default::default(),
+            unpack: Default::default(),
         },
     }
 }
@@ -1219,6 +1279,29 @@ impl Out for BasicTestOutput {
     }
 }
 
+/// Write `files`, each a path relative to `root` and its contents, creating
+/// any directories they need.
+fn write_files(root: &Path, files: &[(&str, &str)]) {
+    for (path, contents) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+}
+
+/// Run git with `args` in the repository at `repo`, returning its trimmed
+/// output.
+fn git(repo: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(repo)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8(output.stdout).unwrap().trim().to_owned()
+}
+
 /// Format a diff between the old and new strings for reporting.
 fn generate_diff(old: &str, new: &str) -> String {
     similar::utils::diff_lines(similar::Algorithm::Myers, old, new)
@@ -1251,6 +1334,44 @@ struct MockRegistryVersion {
     version: semver::Version,
     published_by: Option<CratesUserId>,
     created_at: chrono::DateTime<chrono::Utc>,
+    /// The `deps` of the version's index entry.
+    deps: Vec<Value>,
+    /// The `features` of the version's index entry.
+    features: Value,
+    yanked: bool,
+}
+
+impl MockRegistryVersion {
+    /// Mark the version as yanked in the index.
+    fn yanked(mut self) -> Self {
+        self.yanked = true;
+        self
+    }
+
+    /// Add a dependency to the version's index entry. Fields in `extra`
+    /// override those of a non-optional normal dependency.
+    fn dep(mut self, name: &str, req: &str, extra: Value) -> Self {
+        let mut dep = json!({
+            "name": name,
+            "req": req,
+            "features": [],
+            "optional": false,
+            "default_features": true,
+            "target": null,
+            "kind": "normal",
+        });
+        dep.as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        self.deps.push(dep);
+        self
+    }
+
+    /// Set the features of the version's index entry.
+    fn features(mut self, features: Value) -> Self {
+        self.features = features;
+        self
+    }
 }
 
 fn reg_published_by(
@@ -1272,6 +1393,9 @@ fn reg_published_by(
             ),
             chrono::Utc,
         ),
+        deps: Vec::new(),
+        features: json!({}),
+        yanked: false,
     }
 }
 
@@ -1333,11 +1457,16 @@ impl MockRegistryBuilder {
     }
 
     fn serve(&self, network: &mut Network) {
+        self.serve_at(network, "https://crates.io", "https://index.crates.io");
+    }
+
+    /// Serve the registry from a mirror with the given API and index URLs.
+    fn serve_at(&self, network: &mut Network, api_url: &str, index_url: &str) {
         for (name, pkg) in &self.packages {
             // Serve the index entry as part of the http index.
             network.mock_serve(
                 format!(
-                    "https://index.crates.io/{}/{}/{name}",
+                    "{index_url}/{}/{}/{name}",
                     &name[0..2],
                     &name[2..4]
                 ).to_ascii_lowercase(),
@@ -1347,10 +1476,10 @@ impl MockRegistryBuilder {
                         serde_json::to_string(&json!({
                             "name": name,
                             "vers": &v.version,
-                            "deps": [],
+                            "deps": &v.deps,
                             "cksum": "90527ab4abff2f0608cdb1a78e2349180e1d92059f59b5a65ce2a1a15a499b73",
-                            "features": {},
-                            "yanked": false
+                            "features": &v.features,
+                            "yanked": v.yanked
                         }))
                         .unwrap()
                     })
@@ -1365,7 +1494,7 @@ impl MockRegistryBuilder {
             // Preserving case here matches how we currently construct the API
             // url internally, but may need to be changed in the future.
             network.mock_serve_json(
-                format!("https://crates.io/api/v1/crates/{name}"),
+                format!("{api_url}/api/v1/crates/{name}"),
                 &CratesAPICrate {
                     crate_data: pkg.metadata.clone(),
                     versions: pkg
@@ -0,0 +1,205 @@
+use crate::format::FeatureProfile;
+use crate::resolver::ProfileReport;
+
+use super::*;
+
+fn profile(features: &[&str]) -> FeatureProfile {
+    FeatureProfile {
+        features: features.iter().map(|f| f.to_string()).collect(),
+        default_features: true,
+        all_features: false,
+        targets: vec![],
+    }
+}
+
+fn client_metadata() -> MockMetadata {
+    MockMetadata::new(vec![
+        MockPackage {
+            name: "root-package",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![dep("third-party1")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "third-party1",
+            ..Default::default()
+        },
+    ])
+}
+
+fn server_metadata() -> MockMetadata {
+    MockMetadata::new(vec![
+        MockPackage {
+            name: "root-package",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![dep("third-party1"), dep("server-only")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "third-party1",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "server-only",
+            ..Default::default()
+        },
+    ])
+}
+
+fn get_profile_reports(store: &Store, profiles: &[(&'static str, &Metadata)]) -> (String, String) {
+    console::set_colors_enabled(false);
+    console::set_colors_enabled_stderr(false);
+
+    let cfg = mock_cfg(profiles[0].1);
+    let reports: Vec<_> = profiles
+        .iter()
+        .map(|(name, metadata)| {
+            let report = crate::resolver::resolve_for_targets(metadata, None, store, &[]);
+            let suggest = report.compute_suggest(&cfg, store, None).unwrap();
+            ProfileReport {
+                name,
+                report,
+                suggest,
+            }
+        })
+        .collect();
+
+    let human_output = BasicTestOutput::new();
+    crate::resolver::print_profiles_human(&human_output.clone().as_dyn(), &cfg, &reports).unwrap();
+    let json_output = BasicTestOutput::new();
+    crate::resolver::print_profiles_json(&json_output.clone().as_dyn(), &reports).unwrap();
+    (human_output.to_string(), json_output.to_string())
+}
+
+#[test]
+fn profiles_all_pass() {
+    // (Pass) Every profile is fully audited.
+
+    let _enter = TEST_RUNTIME.enter();
+    let client = client_metadata().metadata();
+    let server = server_metadata().metadata();
+
+    let (config, audits, imports) = builtin_files_full_audited(&server);
+    let store = Store::mock(config, audits, imports);
+
+    let (human, json) = get_profile_reports(&store, &[("client", &client), ("server", &server)]);
+    insta::assert_snapshot!("profiles-all-pass", human);
+    insta::assert_snapshot!("profiles-all-pass.json", json);
+}
+
+#[test]
+fn profiles_fail_only_where_active() {
+    // (Fail) A package which is unvetted only fails the profile it is
+    // actually active in.
+
+    let _enter = TEST_RUNTIME.enter();
+    let client = client_metadata().metadata();
+    let server = server_metadata().metadata();
+
+    let (config, audits, imports) = builtin_files_full_audited(&client);
+    let store = Store::mock(config, audits, imports);
+
+    let (human, json) = get_profile_reports(&store, &[("client", &client), ("server", &server)]);
+    insta::assert_snapshot!("profiles-fail-only-where-active", human);
+    insta::assert_snapshot!("profiles-fail-only-where-active.json", json);
+}
+
+#[test]
+fn profiles_selection() {
+    // Every profile is vetted by default, `--profile` picks a subset, and
+    // feature flags on the command line bypass profiles entirely.
+
+    let metadata = client_metadata().metadata();
+    let (mut config, audits, imports) = builtin_files_full_audited(&metadata);
+    config
+        .profiles
+        .insert("client".to_owned(), profile(&["client"]));
+    config
+        .profiles
+        .insert("server".to_owned(), profile(&["server"]));
+    let store = Store::mock(config, audits, imports);
+
+    let selected = |args: &[&str]| {
+        let cfg = mock_cfg_args(&metadata, ["cargo", "vet"].iter().chain(args));
+        crate::selected_profiles(&cfg, &store, &cfg.cli.check_args).map(|profiles| {
+            profiles
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        })
+    };
+
+    assert_eq!(selected(&[]).unwrap(), ["client", "server"]);
+    assert_eq!(selected(&["--profile", "server"]).unwrap(), ["server"]);
+    assert!(selected(&["--features", "server"]).unwrap().is_empty());
+    assert!(matches!(
+        selected(&["--profile", "desktop"]),
+        Err(crate::errors::ProfileError::UnknownProfile { .. })
+    ));
+    assert!(matches!(
+        selected(&["--profile", "server", "--no-default-features"]),
+        Err(crate::errors::ProfileError::ConflictingFeatures)
+    ));
+}
+
+#[test]
+fn profiles_import_for_every_graph() {
+    // Audits are imported for packages which only some profile's graph
+    // contains, even if the default graph doesn't.
+
+    let _enter = TEST_RUNTIME.enter();
+    let client = client_metadata().metadata();
+    let server = server_metadata().metadata();
+    let (mut config, audits, imports) = builtin_files_full_audited(&client);
+
+    let foreign_audits = AuditsFile {
+        criteria: SortedMap::new(),
+        wildcard_audits: SortedMap::new(),
+        audits: [(
+            "server-only".to_owned(),
+            vec![full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+        )]
+        .into_iter()
+        .collect(),
+        trusted: SortedMap::new(),
+    };
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            ..Default::default()
+        },
+    );
+
+    let cfg = mock_cfg(&client);
+    let mut network = Network::new_mock();
+    network.mock_serve_toml(FOREIGN_URL, &foreign_audits);
+    let store = Store::mock_online(&cfg, config, audits, imports, &network, true).unwrap();
+
+    let mode = |_: PackageStr<'_>| crate::resolver::UpdateMode {
+        search_mode: crate::resolver::SearchMode::PreferExemptions,
+        prune_exemptions: false,
+        prune_non_importable_audits: false,
+        prune_imports: false,
+    };
+    let imports_server_only = |store: &Store| {
+        store.imports.audits[FOREIGN]
+            .audits
+            .contains_key("server-only")
+    };
+
+    let mut default_store = store.clone_for_suggest(false);
+    crate::resolver::update_store(&cfg, &mut default_store, mode);
+    assert!(!imports_server_only(&default_store));
+
+    let mut profiles_store = store.clone_for_suggest(false);
+    crate::resolver::update_store_for_graphs(
+        &cfg,
+        &mut profiles_store,
+        &[(&client, &[]), (&server, &[])],
+        mode,
+    );
+    assert!(imports_server_only(&profiles_store));
+}
@@ -1,3 +1,5 @@
+use crate::format::{RegistryEntry, RegistryFile};
+
 use super::*;
 
 #[test]
@@ -34,3 +36,191 @@ url = 10 # invalid!
 
     insta::assert_snapshot!(human_output.to_string());
 }
+
+#[test]
+fn registry_search_and_coverage() {
+    // Explore the audits of the peers in the registry, and then explore them
+    // again from the cache while offline.
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_no_exemptions(&metadata);
+
+    let mut network = Network::new_mock();
+    network.mock_serve_toml(
+        crate::storage::REGISTRY_URL,
+        &RegistryFile {
+            registry: [
+                (
+                    FOREIGN.to_owned(),
+                    RegistryEntry {
+                        url: vec![FOREIGN_URL.to_owned()],
+                    },
+                ),
+                (
+                    OTHER_FOREIGN.to_owned(),
+                    RegistryEntry {
+                        url: vec![OTHER_FOREIGN_URL.to_owned()],
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        },
+    );
+    network.mock_serve_toml(
+        FOREIGN_URL,
+        &AuditsFile {
+            criteria: SortedMap::new(),
+            wildcard_audits: SortedMap::new(),
+            audits: [
+                (
+                    "third-party1".to_owned(),
+                    vec![
+                        AuditEntry {
+                            who: vec!["Alice <alice@example.com>".to_owned().into()],
+                            notes: Some("Reviewed the unsafe code".to_owned()),
+                            ..full_audit(ver(DEFAULT_VER), SAFE_TO_DEPLOY)
+                        },
+                        full_audit(ver(1), SAFE_TO_RUN),
+                    ],
+                ),
+                (
+                    "third-party2".to_owned(),
+                    vec![delta_audit(ver(1), ver(DEFAULT_VER), SAFE_TO_DEPLOY)],
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            trusted: SortedMap::new(),
+        },
+    );
+    network.mock_serve_toml(
+        OTHER_FOREIGN_URL,
+        &AuditsFile {
+            criteria: SortedMap::new(),
+            wildcard_audits: [(
+                "third-party1".to_owned(),
+                vec![wildcard_audit(1, SAFE_TO_RUN)],
+            )]
+            .into_iter()
+            .collect(),
+            audits: [(
+                "transitive-third-party1".to_owned(),
+                vec![violation(VersionReq::parse("*").unwrap(), SAFE_TO_RUN)],
+            )]
+            .into_iter()
+            .collect(),
+            trusted: SortedMap::new(),
+        },
+    );
+
+    let cfg = mock_cfg(&metadata);
+    let store = Store::mock(config, audits, imports);
+    let cache = crate::storage::Cache::acquire(&cfg).unwrap();
+
+    let explore = |network: Option<&Network>| {
+        let output = BasicTestOutput::new();
+        for sub_args in [
+            crate::cli::RegistrySubcommands::List(crate::cli::RegistryListArgs {}),
+            crate::cli::RegistrySubcommands::Search(crate::cli::RegistrySearchArgs {
+                package: "third-party1".to_owned(),
+            }),
+            crate::cli::RegistrySubcommands::Coverage(crate::cli::RegistryCoverageArgs {}),
+        ] {
+            crate::do_cmd_registry(
+                &output.clone().as_dyn(),
+                &cfg,
+                &sub_args,
+                &store,
+                network,
+                &cache,
+            )
+            .expect("do_cmd_registry failed");
+        }
+        output.to_string()
+    };
+
+    let online = explore(Some(&network));
+    let offline = explore(None);
+    assert_eq!(online, offline, "cached registry should match");
+
+    insta::assert_snapshot!(online);
+
+    // The audits of the peers should also have been added to the audit index.
+    let output = BasicTestOutput::new();
+    for (package, version) in [
+        ("third-party1", None),
+        ("third-party1", Some("10")),
+        ("transitive-third-party1", Some("10")),
+        ("first-party", None),
+    ] {
+        crate::do_cmd_search(
+            &output.clone().as_dyn(),
+            &crate::cli::SearchArgs {
+                package: package.to_owned(),
+                version: version.map(|version| VersionReq::parse(version).unwrap()),
+            },
+            &cache,
+        );
+    }
+    insta::assert_snapshot!("registry_search_and_coverage_index", output.to_string());
+}
+
+#[test]
+fn local_registry_mirror() {
+    // The audits of peers in a local mirror of the registry are read relative
+    // to the mirror, rather than to the working directory.
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_no_exemptions(&metadata);
+
+    let mirror = tempfile::tempdir().unwrap();
+    std::fs::create_dir(mirror.path().join("peer")).unwrap();
+    std::fs::write(
+        mirror.path().join("registry.toml"),
+        r#"
+[registry.peer]
+url = "peer/audits.toml"
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        mirror.path().join("peer/audits.toml"),
+        r#"
+[[audits.third-party1]]
+criteria = "safe-to-deploy"
+version = "10.0.0"
+"#,
+    )
+    .unwrap();
+
+    let registry_path = mirror.path().join("registry.toml");
+    let cfg = mock_cfg_args(
+        &metadata,
+        [
+            "cargo".as_ref(),
+            "vet".as_ref(),
+            "--registry-url".as_ref(),
+            registry_path.as_os_str(),
+        ],
+    );
+    let network = Network::new_mock();
+    let mut store = Store::mock_online(&cfg, config, audits, imports, &network, false).unwrap();
+    let cache = crate::storage::Cache::acquire(&cfg).unwrap();
+
+    let registry_audits = TEST_RUNTIME
+        .block_on(store.fetch_registry_audits(&cfg, &network, &cache))
+        .unwrap();
+    assert_eq!(registry_audits.len(), 1, "the peer's audits should be read");
+    let (name, entry, audits_file) = &registry_audits[0];
+    assert_eq!(name, "peer");
+    assert_eq!(
+        entry.url,
+        [mirror.path().join("peer/audits.toml").display().to_string()]
+    );
+    assert!(audits_file.audits.contains_key("third-party1"));
+}
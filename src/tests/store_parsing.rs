@@ -439,3 +439,24 @@ description = "fuzzed"
     let acquire_errors = get_valid_store(config, EMPTY_AUDITS, imports);
     insta::assert_snapshot!(acquire_errors);
 }
+
+#[test]
+fn test_invalid_exemption_workspace() {
+    let config = r#"
+# cargo-vet config file
+
+[cargo-vet]
+version = "1.0"
+
+[workspaces.backend]
+manifest = "../backend/Cargo.toml"
+
+[[exemptions.zzz]]
+version = "1.0.0"
+criteria = "safe-to-deploy"
+workspace = "frontend"
+"#;
+
+    let acquire_errors = get_valid_store(config, EMPTY_AUDITS, EMPTY_IMPORTS);
+    insta::assert_snapshot!(acquire_errors);
+}
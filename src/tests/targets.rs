@@ -0,0 +1,182 @@
+use cargo_platform::Cfg;
+
+use crate::resolver::TargetPlatform;
+
+use super::*;
+
+fn mock_target(triple: &str, cfg: &[&str]) -> TargetPlatform {
+    TargetPlatform {
+        triple: triple.to_owned(),
+        cfg: cfg.iter().map(|c| c.parse::<Cfg>().unwrap()).collect(),
+    }
+}
+
+fn linux() -> TargetPlatform {
+    mock_target(
+        "x86_64-unknown-linux-gnu",
+        &["unix", "target_os=\"linux\"", "target_arch=\"x86_64\""],
+    )
+}
+
+fn windows() -> TargetPlatform {
+    mock_target(
+        "x86_64-pc-windows-msvc",
+        &["windows", "target_os=\"windows\"", "target_arch=\"x86_64\""],
+    )
+}
+
+fn platform_deps() -> MockMetadata {
+    MockMetadata::new(vec![
+        MockPackage {
+            name: "root-package",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![
+                dep("third-party1"),
+                dep_target("unix-only", "cfg(unix)"),
+                dep_target("windows-only", "cfg(windows)"),
+            ],
+            build_deps: vec![dep_target("linux-build", "cfg(target_os = \"linux\")")],
+            dev_deps: vec![dep_target("windows-dev", "x86_64-pc-windows-msvc")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "third-party1",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "unix-only",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "windows-only",
+            deps: vec![dep("windows-transitive")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "windows-transitive",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "linux-build",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "windows-dev",
+            ..Default::default()
+        },
+    ])
+}
+
+#[test]
+fn all_targets() {
+    // (Fail) Without any targets specified, every dependency must be vetted.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = platform_deps();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = files_no_exemptions(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("targets-all", metadata, store);
+}
+
+#[test]
+fn linux_target() {
+    // (Fail) Only dependencies which are built for linux must be vetted.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = platform_deps();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = files_no_exemptions(&metadata);
+
+    let mut store = Store::mock(config, audits, imports);
+    store.target_platforms = vec![linux()];
+
+    assert_report_snapshot!("targets-linux", metadata, store);
+}
+
+#[test]
+fn windows_target() {
+    // (Fail) Only dependencies which are built for windows must be vetted,
+    // including target-specific dev-dependencies of workspace members.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = platform_deps();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = files_no_exemptions(&metadata);
+
+    let mut store = Store::mock(config, audits, imports);
+    store.target_platforms = vec![windows()];
+
+    assert_report_snapshot!("targets-windows", metadata, store);
+}
+
+#[test]
+fn multiple_targets() {
+    // (Fail) Each failure reports which of the targets it was required for.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = platform_deps();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = files_no_exemptions(&metadata);
+
+    let mut store = Store::mock(config, audits, imports);
+    store.target_platforms = vec![linux(), windows()];
+
+    assert_report_snapshot!("targets-multiple", metadata, store);
+}
+
+#[test]
+fn multiple_targets_audited() {
+    // (Pass) Everything required for the targets is audited, and packages only
+    // used on other platforms don't need to be.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = platform_deps();
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = files_full_audited(&metadata);
+    audits.audits.remove("windows-only");
+    audits.audits.remove("windows-transitive");
+    audits.audits.remove("windows-dev");
+
+    let mut store = Store::mock(config, audits, imports);
+    store.target_platforms = vec![linux()];
+
+    assert_report_snapshot!("targets-audited", metadata, store);
+}
+
+#[test]
+fn target_prune_keeps_other_platforms() {
+    // (Pass) Pruning while vetting for a single target with `--target` keeps
+    // the exemptions which other platforms need, as the store is maintained
+    // for every platform listed in config.toml.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = platform_deps();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = files_inited(&metadata);
+
+    let mut store = Store::mock(config, audits, imports);
+    store.target_platforms = vec![linux()];
+    let exemptions = store.config.exemptions.clone();
+
+    crate::resolver::update_store(&mock_cfg(&metadata), &mut store, |_| {
+        crate::resolver::UpdateMode {
+            search_mode: crate::resolver::SearchMode::PreferExemptions,
+            prune_exemptions: true,
+            prune_non_importable_audits: true,
+            prune_imports: true,
+        }
+    });
+
+    assert_eq!(store.config.exemptions, exemptions);
+    assert!(store.config.exemptions.contains_key("windows-only"));
+}
@@ -0,0 +1,336 @@
+use std::io::{Seek, Write};
+
+use sha2::Digest;
+
+use crate::storage::{unpack_package, UnpackLimits};
+
+use super::*;
+
+const LIMITS: UnpackLimits = UnpackLimits {
+    max_size: 1024,
+    max_files: 8,
+};
+
+struct MockEntry {
+    path: &'static str,
+    entry_type: tar::EntryType,
+    link: Option<&'static str>,
+    size: usize,
+}
+
+fn file(path: &'static str, size: usize) -> MockEntry {
+    MockEntry {
+        path,
+        entry_type: tar::EntryType::Regular,
+        link: None,
+        size,
+    }
+}
+
+fn link(path: &'static str, entry_type: tar::EntryType, link: &'static str) -> MockEntry {
+    MockEntry {
+        path,
+        entry_type,
+        link: Some(link),
+        size: 0,
+    }
+}
+
+/// Build a .crate containing `entries`, writing the names into the headers
+/// directly so that invalid paths can be produced.
+fn build_crate(entries: &[MockEntry]) -> Vec<u8> {
+    let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    for entry in entries {
+        let mut header = tar::Header::new_old();
+        header.as_old_mut().name[..entry.path.len()].copy_from_slice(entry.path.as_bytes());
+        if let Some(link) = entry.link {
+            header.as_old_mut().linkname[..link.len()].copy_from_slice(link.as_bytes());
+        }
+        header.set_entry_type(entry.entry_type);
+        header.set_mode(0o644);
+        header.set_size(entry.size as u64);
+        header.set_cksum();
+        builder
+            .append(&header, &vec![b'x'; entry.size][..])
+            .unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap()
+}
+
+/// Build a .crate containing `entries` and try to unpack it.
+fn try_unpack(entries: &[MockEntry], limits: UnpackLimits) -> String {
+    let mut tarball = tempfile::tempfile().unwrap();
+    tarball.write_all(&build_crate(entries)).unwrap();
+    tarball.rewind().unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let unpack_dir = dir.path().join("test-crate-1.0.0");
+    match unpack_package(&tarball, &unpack_dir, Some("checksum"), limits) {
+        Ok(()) => "ok".to_owned(),
+        Err(error) => error.to_string(),
+    }
+}
+
+#[test]
+fn unpack_rejects_unsafe_entries() {
+    use tar::EntryType::{Block, Char, Fifo, Link, Symlink};
+
+    let cases: Vec<(&str, Vec<MockEntry>)> = vec![
+        (
+            "valid",
+            vec![
+                file("test-crate-1.0.0/Cargo.toml", 100),
+                file("test-crate-1.0.0/src/lib.rs", 100),
+            ],
+        ),
+        ("absolute", vec![file("/test-crate-1.0.0/src/lib.rs", 1)]),
+        (
+            "parent-dir",
+            vec![file("test-crate-1.0.0/../other-1.0.0/lib.rs", 1)],
+        ),
+        ("other-crate", vec![file("other-1.0.0/src/lib.rs", 1)]),
+        (
+            "symlink",
+            vec![link("test-crate-1.0.0/src/alias.rs", Symlink, "lib.rs")],
+        ),
+        (
+            "hardlink",
+            vec![link(
+                "test-crate-1.0.0/README",
+                Link,
+                "test-crate-1.0.0/Cargo.toml",
+            )],
+        ),
+        (
+            "symlink-escape",
+            vec![link(
+                "test-crate-1.0.0/src/evil",
+                Symlink,
+                "../../other-1.0.0",
+            )],
+        ),
+        (
+            "symlink-absolute",
+            vec![link("test-crate-1.0.0/src/evil", Symlink, "/etc/passwd")],
+        ),
+        (
+            "hardlink-escape",
+            vec![link(
+                "test-crate-1.0.0/src/evil",
+                Link,
+                "other-1.0.0/src/lib.rs",
+            )],
+        ),
+        ("block", vec![link("test-crate-1.0.0/dev", Block, "")]),
+        ("char", vec![link("test-crate-1.0.0/dev", Char, "")]),
+        ("fifo", vec![link("test-crate-1.0.0/dev", Fifo, "")]),
+        (
+            "too-large",
+            vec![
+                file("test-crate-1.0.0/a", 1000),
+                file("test-crate-1.0.0/b", 1000),
+            ],
+        ),
+        (
+            "too-many-files",
+            (0..9).map(|_| file("test-crate-1.0.0/file", 1)).collect(),
+        ),
+    ];
+
+    let mut output = String::new();
+    for (name, entries) in cases {
+        output.push_str(&format!("{name}: {}\n", try_unpack(&entries, LIMITS)));
+    }
+    insta::assert_snapshot!(output);
+}
+
+const DOWNLOAD_URL: &str = "https://crates.io/api/v1/crates/test-crate/1.0.0/download";
+const INDEX_URL: &str = "https://index.crates.io/te/st/test-crate";
+
+/// A valid .crate for test-crate 1.0.0, along with its checksum.
+fn mock_crate(size: usize) -> (Vec<u8>, String) {
+    let tarball = build_crate(&[
+        file("test-crate-1.0.0/Cargo.toml", 10),
+        file("test-crate-1.0.0/src/lib.rs", size),
+    ]);
+    let checksum = format!("{:x}", sha2::Sha256::digest(&tarball));
+    (tarball, checksum)
+}
+
+/// Serve `tarball` as test-crate 1.0.0, with `checksum` listed in the index.
+fn mock_network(tarball: &[u8], checksum: &str) -> Network {
+    let mut network = Network::new_mock();
+    network.mock_serve(DOWNLOAD_URL, tarball);
+    network.mock_serve(
+        INDEX_URL,
+        format!(
+            r#"{{"name":"test-crate","vers":"1.0.0","deps":[],"cksum":"{checksum}","features":{{}},"yanked":false}}"#
+        ),
+    );
+    network
+}
+
+/// Fetch test-crate 1.0.0 into the cache at `cache_dir`, from a workspace whose
+/// Cargo.lock records `lockfile_checksum` for it.
+fn fetch(
+    cache_dir: &std::path::Path,
+    lockfile_checksum: Option<&str>,
+    network: Option<&Network>,
+) -> Result<std::path::PathBuf, crate::errors::FetchError> {
+    let workspace = tempfile::tempdir().unwrap();
+    if let Some(checksum) = lockfile_checksum {
+        std::fs::write(
+            workspace.path().join("Cargo.lock"),
+            format!(
+                r#"
+version = 3
+
+[[package]]
+name = "test-crate"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "{checksum}"
+"#
+            ),
+        )
+        .unwrap();
+    }
+
+    let mut metadata = MockMetadata::simple().metadata();
+    metadata.workspace_root = workspace.path().to_owned().try_into().unwrap();
+    let mut cfg = mock_cfg(&metadata);
+    cfg._rest.mock_cache = false;
+    cfg._rest.cache_dir = cache_dir.to_owned();
+    let cache = crate::storage::Cache::acquire(&cfg).unwrap();
+    TEST_RUNTIME.block_on(cache.fetch_package(&metadata, network, "test-crate", &ver(1)))
+}
+
+#[test]
+fn fetch_rejects_corrupted_tarball() {
+    // (Fail) A tarball which doesn't match the checksum in the index isn't
+    // unpacked, and is removed from the cache so that it's downloaded again.
+    let (_, checksum) = mock_crate(10);
+    let (corrupted, _) = mock_crate(20);
+    let network = mock_network(&corrupted, &checksum);
+
+    let cache_dir = tempfile::tempdir().unwrap();
+    let result = fetch(cache_dir.path(), None, Some(&network));
+    assert!(
+        matches!(
+            &result,
+            Err(crate::errors::FetchError::ChecksumMismatch { expected, .. }) if *expected == checksum
+        ),
+        "unexpected result: {result:?}"
+    );
+    assert!(!cache_dir
+        .path()
+        .join("cache/test-crate-1.0.0.crate")
+        .exists());
+    assert!(!cache_dir
+        .path()
+        .join("src/test-crate-1.0.0/Cargo.toml")
+        .exists());
+}
+
+#[test]
+fn fetch_reunpacks_unverified_tree() {
+    // (Pass) An unpacked tree without a recorded checksum is unpacked again
+    // from the cached tarball, after checking it against Cargo.lock.
+    let (tarball, checksum) = mock_crate(10);
+    let network = mock_network(&tarball, &checksum);
+
+    let cache_dir = tempfile::tempdir().unwrap();
+    let src = fetch(cache_dir.path(), None, Some(&network)).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(src.join(".cargo-vet-checksum")).unwrap(),
+        checksum
+    );
+
+    std::fs::remove_file(src.join(".cargo-vet-checksum")).unwrap();
+    std::fs::write(src.join("src/lib.rs"), "tampered").unwrap();
+
+    let src = fetch(cache_dir.path(), Some(&checksum), None).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(src.join("src/lib.rs")).unwrap(),
+        "x".repeat(10)
+    );
+    assert_eq!(
+        std::fs::read_to_string(src.join(".cargo-vet-checksum")).unwrap(),
+        checksum
+    );
+}
+
+#[test]
+fn fetch_checks_recorded_checksum_against_lockfile() {
+    // (Fail) An unpacked tree whose recorded checksum doesn't match Cargo.lock
+    // isn't used, and the cached tarball it came from is rejected too.
+    let (tarball, checksum) = mock_crate(10);
+    let (_, other_checksum) = mock_crate(20);
+    let network = mock_network(&tarball, &checksum);
+
+    let cache_dir = tempfile::tempdir().unwrap();
+    fetch(cache_dir.path(), Some(&checksum), Some(&network)).unwrap();
+
+    let result = fetch(cache_dir.path(), Some(&other_checksum), None);
+    assert!(
+        matches!(
+            &result,
+            Err(crate::errors::FetchError::ChecksumMismatch { expected, actual, .. })
+                if *expected == other_checksum && *actual == checksum
+        ),
+        "unexpected result: {result:?}"
+    );
+}
+
+#[test]
+fn fetch_reunpacks_modified_tree() {
+    // (Pass) An unpacked tree whose files were changed after it was unpacked
+    // no longer matches its recorded tree hash, so is unpacked again from the
+    // cached tarball.
+    let (tarball, checksum) = mock_crate(10);
+    let network = mock_network(&tarball, &checksum);
+
+    let cache_dir = tempfile::tempdir().unwrap();
+    let src = fetch(cache_dir.path(), Some(&checksum), Some(&network)).unwrap();
+    let tree_hash = crate::storage::tree_hash(&src).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(src.join(".cargo-vet-tree-hash")).unwrap(),
+        tree_hash
+    );
+
+    std::fs::write(src.join("src/lib.rs"), "tampered").unwrap();
+    std::fs::write(src.join("src/extra.rs"), "added").unwrap();
+    assert_ne!(crate::storage::tree_hash(&src).unwrap(), tree_hash);
+
+    let src = fetch(cache_dir.path(), Some(&checksum), None).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(src.join("src/lib.rs")).unwrap(),
+        "x".repeat(10)
+    );
+    assert!(!src.join("src/extra.rs").exists());
+    assert_eq!(crate::storage::tree_hash(&src).unwrap(), tree_hash);
+}
+
+#[test]
+fn fetch_offline_without_checksum_is_unverified() {
+    // (Pass) A cached tarball unpacked while offline without a checksum in
+    // Cargo.lock isn't marked as verified, so it's checked again once a
+    // checksum is known.
+    let (tarball, checksum) = mock_crate(10);
+    let network = mock_network(&tarball, &checksum);
+
+    let cache_dir = tempfile::tempdir().unwrap();
+    let src = fetch(cache_dir.path(), None, Some(&network)).unwrap();
+    std::fs::remove_dir_all(&src).unwrap();
+
+    let src = fetch(cache_dir.path(), None, None).unwrap();
+    assert!(src.join(".cargo-vet-tree-hash").exists());
+    assert!(!src.join(".cargo-vet-checksum").exists());
+
+    let src = fetch(cache_dir.path(), Some(&checksum), None).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(src.join(".cargo-vet-checksum")).unwrap(),
+        checksum
+    );
+}
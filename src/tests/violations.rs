@@ -263,3 +263,91 @@ fn mock_simple_violation_hit_with_extra_junk() {
 
     assert_report_snapshot!("mock-simple-violation-hit-with-extra-junk", metadata, store);
 }
+
+/// Store files in which `third-party1` is audited by one import and has a
+/// violation from another.
+fn conflicting_imports(
+    metadata: &Metadata,
+    policy: crate::format::ViolationConflictPolicy,
+) -> (ConfigFile, AuditsFile, ImportsFile) {
+    let (mut config, mut audits, mut imports) = files_full_audited(metadata);
+    audits.audits.remove("third-party1");
+
+    config.violation_conflicts = policy;
+    config.imports.insert(
+        FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![FOREIGN_URL.to_owned()],
+            priority: 1,
+            ..Default::default()
+        },
+    );
+    config.imports.insert(
+        OTHER_FOREIGN.to_owned(),
+        crate::format::RemoteImport {
+            url: vec![OTHER_FOREIGN_URL.to_owned()],
+            ..Default::default()
+        },
+    );
+
+    let violation_ver = VersionReq::parse(&format!("={DEFAULT_VER}")).unwrap();
+    imports.audits.insert(
+        FOREIGN.to_owned(),
+        AuditsFile {
+            audits: [(
+                "third-party1".to_owned(),
+                vec![violation(violation_ver, "reviewed")],
+            )]
+            .into(),
+            ..Default::default()
+        },
+    );
+    imports.audits.insert(
+        OTHER_FOREIGN.to_owned(),
+        AuditsFile {
+            audits: [(
+                "third-party1".to_owned(),
+                vec![full_audit(ver(DEFAULT_VER), "reviewed")],
+            )]
+            .into(),
+            ..Default::default()
+        },
+    );
+    (config, audits, imports)
+}
+
+#[test]
+fn violation_conflict_prefer_higher_priority() {
+    // (Fail) The violation comes from the import with the higher priority, so
+    // the conflicting audit is ignored, leaving the crate unaudited.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = conflicting_imports(
+        &metadata,
+        crate::format::ViolationConflictPolicy::PreferHigherPriority,
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("violation-conflict-prefer-higher-priority", metadata, store);
+}
+
+#[test]
+fn violation_conflict_warn() {
+    // (Pass) The conflicting violation is ignored, and the conflict is
+    // reported alongside the successful result.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) =
+        conflicting_imports(&metadata, crate::format::ViolationConflictPolicy::Warn);
+
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("violation-conflict-warn", metadata, store);
+}
@@ -0,0 +1,314 @@
+use super::*;
+
+use crate::errors::WhatIfError;
+use crate::resolver::WhatIfChange;
+
+fn get_what_if(
+    metadata: &Metadata,
+    store: &Store,
+    change: &WhatIfChange<'_>,
+) -> Result<(String, String), WhatIfError> {
+    get_what_if_online(metadata, store, None, change)
+}
+
+fn get_what_if_online(
+    metadata: &Metadata,
+    store: &Store,
+    network: Option<&Network>,
+    change: &WhatIfChange<'_>,
+) -> Result<(String, String), WhatIfError> {
+    // FIXME: Figure out how to handle disabling output colours better in tests.
+    console::set_colors_enabled(false);
+    console::set_colors_enabled_stderr(false);
+
+    let cfg = mock_cfg_args(metadata, ["cargo", "vet", "--no-registry-suggestions"]);
+    let what_if = crate::resolver::what_if(&cfg, store, network, change)?;
+
+    let human_output = BasicTestOutput::new();
+    crate::resolver::print_what_if_human(&human_output.clone().as_dyn(), &what_if).unwrap();
+    let json_output = BasicTestOutput::new();
+    serde_json::to_writer_pretty(&*json_output.clone().as_dyn(), &what_if).unwrap();
+    Ok((human_output.to_string(), json_output.to_string()))
+}
+
+#[test]
+fn what_if_add_imported() {
+    // Adding a package which a peer has already audited costs nothing.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, mut imports) = builtin_files_full_audited(&metadata);
+    imports.audits.insert(
+        FOREIGN.to_owned(),
+        AuditsFile {
+            criteria: SortedMap::new(),
+            wildcard_audits: SortedMap::new(),
+            audits: [(
+                "new-crate".to_owned(),
+                vec![full_audit(ver(2), SAFE_TO_DEPLOY)],
+            )]
+            .into_iter()
+            .collect(),
+            trusted: SortedMap::new(),
+        },
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    let version = ver(2).semver;
+    let change = WhatIfChange::Add {
+        package: "new-crate",
+        version: &version,
+        dependent: None,
+    };
+    let (human, json) = get_what_if(&metadata, &store, &change).unwrap();
+    insta::assert_snapshot!("what_if_add_imported", human);
+    insta::assert_snapshot!("what_if_add_imported.json", json);
+}
+
+#[test]
+fn what_if_add_unaudited() {
+    // Adding a package with no audits suggests a full audit of it.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    let version = ver(DEFAULT_VER).semver;
+    let change = WhatIfChange::Add {
+        package: "new-crate",
+        version: &version,
+        dependent: Some("first-party"),
+    };
+    let (human, json) = get_what_if(&metadata, &store, &change).unwrap();
+    insta::assert_snapshot!("what_if_add_unaudited", human);
+    insta::assert_snapshot!("what_if_add_unaudited.json", json);
+}
+
+#[test]
+fn what_if_add_index_dependencies() {
+    // The dependencies of an added package are resolved from the index,
+    // reusing versions which are already in the graph, and skipping
+    // dev-dependencies, disabled optional dependencies and yanked versions.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    let mut network = Network::new_mock();
+    MockRegistryBuilder::new()
+        .package(
+            "new-crate",
+            &[reg_published_by(ver(DEFAULT_VER), None, "2022-01-01")
+                .dep("third-party2", "^10", json!({}))
+                .dep("new-dep", "^1", json!({}))
+                .dep("opt-dep", "^1", json!({ "optional": true }))
+                .dep("unused-dep", "^1", json!({ "optional": true }))
+                .dep("dev-dep", "^1", json!({ "kind": "dev" }))
+                .features(json!({ "default": ["std"], "std": ["dep:opt-dep"] }))],
+        )
+        .package(
+            "new-dep",
+            &[
+                reg_published_by(ver(1), None, "2022-01-01").dep(
+                    "build-dep",
+                    "^2",
+                    json!({ "kind": "build" }),
+                ),
+                reg_published_by(ver(2), None, "2022-02-01"),
+                reg_published_by(VetVersion::parse("1.1.0").unwrap(), None, "2022-03-01").yanked(),
+            ],
+        )
+        .package("opt-dep", &[reg_published_by(ver(1), None, "2022-01-01")])
+        .package(
+            "build-dep",
+            &[
+                reg_published_by(ver(2), None, "2022-01-01"),
+                reg_published_by(VetVersion::parse("2.1.0").unwrap(), None, "2022-02-01"),
+            ],
+        )
+        .serve(&mut network);
+
+    let version = ver(DEFAULT_VER).semver;
+    let change = WhatIfChange::Add {
+        package: "new-crate",
+        version: &version,
+        dependent: Some("first-party"),
+    };
+    let (human, _) = get_what_if_online(&metadata, &store, Some(&network), &change).unwrap();
+    insta::assert_snapshot!("what_if_add_index_dependencies", human);
+}
+
+#[test]
+fn what_if_add_existing() {
+    // Adding a package which is already in the graph adds nothing.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    let version = ver(DEFAULT_VER).semver;
+    let change = WhatIfChange::Add {
+        package: "third-party2",
+        version: &version,
+        dependent: None,
+    };
+    let (human, _) = get_what_if(&metadata, &store, &change).unwrap();
+    insta::assert_snapshot!("what_if_add_existing", human);
+}
+
+#[test]
+fn what_if_upgrade_delta() {
+    // Upgrading a package with an existing delta audit replaces the old
+    // version with a vetted new one.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    audits
+        .audits
+        .get_mut("third-party1")
+        .unwrap()
+        .push(delta_audit(ver(DEFAULT_VER), ver(11), SAFE_TO_DEPLOY));
+
+    let store = Store::mock(config, audits, imports);
+
+    let version = ver(11).semver;
+    let change = WhatIfChange::Upgrade {
+        package: "third-party1",
+        from: None,
+        version: &version,
+    };
+    let (human, json) = get_what_if(&metadata, &store, &change).unwrap();
+    insta::assert_snapshot!("what_if_upgrade_delta", human);
+    insta::assert_snapshot!("what_if_upgrade_delta.json", json);
+}
+
+#[test]
+fn what_if_upgrade_unaudited() {
+    // Upgrading a package without a delta audit suggests one.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    let version = ver(11).semver;
+    let from = ver(DEFAULT_VER);
+    let change = WhatIfChange::Upgrade {
+        package: "transitive-third-party1",
+        from: Some(&from),
+        version: &version,
+    };
+    let (human, _) = get_what_if(&metadata, &store, &change).unwrap();
+    insta::assert_snapshot!("what_if_upgrade_unaudited", human);
+}
+
+#[test]
+fn what_if_upgrade_to_existing() {
+    // Upgrading to a version which is already in the graph alongside older
+    // ones replaces the older versions with it.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::new(vec![
+        MockPackage {
+            name: "root",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![dep_ver("third-party", 9), dep("first-party")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "first-party",
+            is_first_party: true,
+            deps: vec![dep_ver("third-party", DEFAULT_VER)],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "third-party",
+            version: ver(9),
+            ..Default::default()
+        },
+        MockPackage {
+            name: "third-party",
+            ..Default::default()
+        },
+    ]);
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    let version = ver(DEFAULT_VER).semver;
+    let change = WhatIfChange::Upgrade {
+        package: "third-party",
+        from: None,
+        version: &version,
+    };
+    let (human, _) = get_what_if(&metadata, &store, &change).unwrap();
+    insta::assert_snapshot!("what_if_upgrade_to_existing", human);
+}
+
+#[test]
+fn what_if_errors() {
+    // Changes which can't be applied to the graph are rejected.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    let version = ver(11).semver;
+    let missing = WhatIfChange::Upgrade {
+        package: "missing",
+        from: None,
+        version: &version,
+    };
+    assert!(matches!(
+        get_what_if(&metadata, &store, &missing),
+        Err(WhatIfError::NotInGraph { .. })
+    ));
+
+    let first_party = WhatIfChange::Upgrade {
+        package: "first-party",
+        from: None,
+        version: &version,
+    };
+    assert!(matches!(
+        get_what_if(&metadata, &store, &first_party),
+        Err(WhatIfError::UpgradeFirstParty { .. })
+    ));
+
+    let bad_dependent = WhatIfChange::Add {
+        package: "new-crate",
+        version: &version,
+        dependent: Some("third-party1"),
+    };
+    assert!(matches!(
+        get_what_if(&metadata, &store, &bad_dependent),
+        Err(WhatIfError::NotFirstParty { .. })
+    ));
+}
@@ -0,0 +1,155 @@
+use super::*;
+
+fn get_why(
+    metadata: &Metadata,
+    store: &Store,
+    package: &str,
+    max_paths: usize,
+) -> (String, String) {
+    // FIXME: Figure out how to handle disabling output colours better in tests.
+    console::set_colors_enabled(false);
+    console::set_colors_enabled_stderr(false);
+
+    let why = crate::resolver::why(metadata, None, store, package, max_paths).unwrap();
+
+    let human_output = BasicTestOutput::new();
+    crate::resolver::print_why_human(&human_output.clone().as_dyn(), &why).unwrap();
+    let json_output = BasicTestOutput::new();
+    serde_json::to_writer_pretty(&*json_output.clone().as_dyn(), &why).unwrap();
+    (human_output.to_string(), json_output.to_string())
+}
+
+#[test]
+fn why_default_criteria() {
+    // Requirements are inherited from the root with the default criteria.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    let (human, json) = get_why(&metadata, &store, "transitive-third-party1", 100);
+    insta::assert_snapshot!("why_default_criteria", human);
+    insta::assert_snapshot!("why_default_criteria.json", json);
+}
+
+#[test]
+fn why_policy_overrides() {
+    // Policy entries changing the requirements along the path are reported.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_full_audited(&metadata);
+    config.policy.insert(
+        "root-package".to_owned(),
+        self_policy([SAFE_TO_RUN, SAFE_TO_DEPLOY]),
+    );
+    config.policy.insert(
+        "first-party".to_owned(),
+        dep_policy([("third-party1", [SAFE_TO_RUN])]),
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    let (human, json) = get_why(&metadata, &store, "transitive-third-party1", 100);
+    insta::assert_snapshot!("why_policy_overrides", human);
+    insta::assert_snapshot!("why_policy_overrides.json", json);
+}
+
+#[test]
+fn why_multiple_paths_and_versions() {
+    // Every path to every version of the package is reported.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::complex();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    let (human, json) = get_why(&metadata, &store, "third-core", 100);
+    insta::assert_snapshot!("why_multiple_paths_and_versions", human);
+    insta::assert_snapshot!("why_multiple_paths_and_versions.json", json);
+}
+
+#[test]
+fn why_dev_deps() {
+    // Dev-dependencies use the dev-criteria, and dev-dependency cycles aren't
+    // followed.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::dev_detection();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    let (human, _) = get_why(&metadata, &store, "both", 100);
+    insta::assert_snapshot!("why_dev_deps_both", human);
+    let (human, _) = get_why(&metadata, &store, "dev-cycle-indirect", 100);
+    insta::assert_snapshot!("why_dev_deps_cycle", human);
+}
+
+#[test]
+fn why_max_paths() {
+    // Paths beyond `--max-paths` are omitted.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::complex();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    let (human, _) = get_why(&metadata, &store, "third-core", 1);
+    insta::assert_snapshot!("why_max_paths", human);
+}
+
+#[test]
+fn why_not_in_graph() {
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple();
+
+    let metadata = mock.metadata();
+    let (config, audits, imports) = builtin_files_full_audited(&metadata);
+
+    let store = Store::mock(config, audits, imports);
+
+    assert!(matches!(
+        crate::resolver::why(&metadata, None, &store, "unknown-package", 100),
+        Err(crate::errors::WhyError::NotInGraph { .. })
+    ));
+}
+
+#[test]
+fn why_build_criteria() {
+    // Build-dependencies and proc-macros get the `build-criteria` of their
+    // parent's policy entry.
+
+    let _enter = TEST_RUNTIME.enter();
+    let mock = MockMetadata::simple_deps();
+
+    let metadata = mock.metadata();
+    let (mut config, audits, imports) = builtin_files_full_audited(&metadata);
+    config.policy.insert(
+        "root".to_owned(),
+        PackagePolicyEntry::Unversioned(PolicyEntry {
+            build_criteria: Some(vec![SAFE_TO_RUN.to_owned().into()]),
+            ..default_policy()
+        }),
+    );
+
+    let store = Store::mock(config, audits, imports);
+
+    let (human, json) = get_why(&metadata, &store, "proc-macro", 100);
+    insta::assert_snapshot!("why_build_criteria", human);
+    insta::assert_snapshot!("why_build_criteria.json", json);
+}
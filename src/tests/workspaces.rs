@@ -0,0 +1,207 @@
+use crate::format::{WorkspaceEntry, WorkspaceName};
+use crate::Workspace;
+
+use super::*;
+
+fn backend_metadata() -> MockMetadata {
+    MockMetadata::new(vec![
+        MockPackage {
+            name: "backend",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![dep("shared"), dep("backend-only")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "shared",
+            deps: vec![dep("shared-transitive")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "shared-transitive",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "backend-only",
+            ..Default::default()
+        },
+    ])
+}
+
+fn frontend_metadata() -> MockMetadata {
+    MockMetadata::new(vec![
+        MockPackage {
+            name: "frontend",
+            is_workspace: true,
+            is_first_party: true,
+            deps: vec![dep("shared"), dep("frontend-only")],
+            dev_deps: vec![dep("shared-transitive")],
+            ..Default::default()
+        },
+        MockPackage {
+            name: "shared",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "shared-transitive",
+            ..Default::default()
+        },
+        MockPackage {
+            name: "frontend-only",
+            ..Default::default()
+        },
+    ])
+}
+
+/// Merge the backend and frontend workspaces, returning the merged metadata
+/// and the packages in each workspace.
+fn merged_workspaces() -> (Metadata, SortedMap<WorkspaceName, Workspace>) {
+    let mut metadata = backend_metadata().metadata();
+    let frontend = frontend_metadata().metadata();
+
+    let mut workspaces = SortedMap::new();
+    for (name, metadata) in [("backend", &metadata), ("frontend", &frontend)] {
+        workspaces.insert(
+            name.to_owned(),
+            Workspace {
+                manifest: PathBuf::from(format!("../{name}/Cargo.toml")),
+                packages: metadata.packages.iter().map(|p| p.id.clone()).collect(),
+            },
+        );
+    }
+
+    crate::merge_metadata(&mut metadata, frontend);
+    (metadata, workspaces)
+}
+
+#[test]
+fn workspaces_merged_graph() {
+    // (Fail) Every workspace's dependencies are vetted together, and packages
+    // shared between workspaces keep the dependencies they have in each.
+
+    let _enter = TEST_RUNTIME.enter();
+    let (metadata, _) = merged_workspaces();
+
+    let (config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    audits.audits.remove("frontend-only");
+    audits.audits.remove("shared-transitive");
+    let store = Store::mock(config, audits, imports);
+
+    assert_report_snapshot!("workspaces-merged-graph", metadata, store);
+}
+
+#[test]
+fn workspaces_regenerate_exemptions() {
+    // (Pass) Regenerated exemptions record which workspace needs them, unless
+    // they're needed by several workspaces.
+
+    let _enter = TEST_RUNTIME.enter();
+    let (metadata, workspaces) = merged_workspaces();
+
+    let (mut config, audits, imports) = builtin_files_inited(&metadata);
+    for name in workspaces.keys() {
+        config.workspaces.insert(
+            name.clone(),
+            WorkspaceEntry {
+                manifest: workspaces[name].manifest.clone(),
+            },
+        );
+    }
+    let mut store = Store::mock(config, audits, imports);
+
+    let mut cfg = mock_cfg(&metadata);
+    cfg.workspaces = workspaces;
+    crate::resolver::update_store(&cfg, &mut store, |_| crate::resolver::UpdateMode {
+        search_mode: crate::resolver::SearchMode::RegenerateExemptions,
+        prune_exemptions: true,
+        prune_non_importable_audits: true,
+        prune_imports: true,
+    });
+
+    let exemptions = toml_edit::ser::to_string_pretty(&store.config.exemptions).unwrap();
+    insta::assert_snapshot!("workspaces-regenerate-exemptions", exemptions);
+}
+
+#[test]
+fn workspaces_exemption_scoped_to_workspace() {
+    // (Fail) An exemption which records the workspace needing it doesn't
+    // apply to a version which another workspace also uses.
+
+    let _enter = TEST_RUNTIME.enter();
+    let (metadata, workspaces) = merged_workspaces();
+
+    let (mut config, mut audits, imports) = builtin_files_full_audited(&metadata);
+    audits.audits.remove("shared");
+    audits.audits.remove("backend-only");
+    for (name, workspace) in [("shared", "backend"), ("backend-only", "backend")] {
+        config.exemptions.insert(
+            name.to_owned(),
+            vec![ExemptedDependency {
+                workspace: Some(workspace.to_owned().into()),
+                ..exemptions(ver(DEFAULT_VER), SAFE_TO_DEPLOY)
+            }],
+        );
+    }
+    let mut store = Store::mock(config, audits, imports);
+
+    let mut cfg = mock_cfg(&metadata);
+    cfg.workspaces = workspaces;
+    store.workspace_users = crate::storage::workspace_users(&cfg);
+
+    assert_report_snapshot!("workspaces-exemption-scoped-to-workspace", metadata, store);
+}
+
+#[test]
+fn workspaces_read_from_base() {
+    // The workspaces governed by a store can be inherited from a base config.
+
+    let store_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        store_dir.path().join("config.toml"),
+        r#"
+extends = ["base.toml"]
+
+[cargo-vet]
+version = "1.0"
+
+[workspaces.frontend]
+manifest = "../frontend/Cargo.toml"
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        store_dir.path().join("base.toml"),
+        r#"
+[workspaces.backend]
+manifest = "../backend/Cargo.toml"
+
+[workspaces.frontend]
+manifest = "../old-frontend/Cargo.toml"
+"#,
+    )
+    .unwrap();
+    std::fs::write(store_dir.path().join("imports.lock"), "").unwrap();
+
+    let metacfg = crate::format::MetaConfig(vec![crate::format::MetaConfigInstance {
+        version: Some(1),
+        store: Some(crate::format::StoreInfo {
+            path: Some(store_dir.path().to_owned()),
+        }),
+    }]);
+    let manifests = Store::read_workspaces(&metacfg).unwrap();
+    assert_eq!(
+        manifests,
+        [
+            (
+                "backend".to_owned(),
+                store_dir.path().join("../backend/Cargo.toml")
+            ),
+            (
+                "frontend".to_owned(),
+                store_dir.path().join("../frontend/Cargo.toml")
+            ),
+        ]
+        .into_iter()
+        .collect::<SortedMap<_, _>>()
+    );
+}